@@ -0,0 +1,847 @@
+//! WinZip AES encryption and decryption (APPNOTE Appendix E).
+//!
+//! An AES entry is stored with [`CompressionMethod::AES`] (99). Its real
+//! compression method, the key strength, and the format version (AE-1 or
+//! AE-2) live in the 0x9901 extra field, modeled by [`Parameters`]. The entry
+//! data is a salt, a two byte password verifier, the compressed data
+//! encrypted with AES in CTR mode, and a 10 byte authentication code: the
+//! truncated HMAC-SHA1 of the ciphertext.
+//!
+//! rawzip owns this framing but not the cryptography, so it stays free of
+//! dependencies. Implement [`Primitives`] on top of a crypto library to
+//! provide the AES block cipher, HMAC-SHA1, and PBKDF2-HMAC-SHA1. The CTR
+//! mode is implemented here, as WinZip's little-endian counter is a detail
+//! that is easy to get wrong.
+//!
+//! [`Decryptor`] wraps an entry's encrypted data and yields the decrypted
+//! (still compressed) body, checking the authentication code at the end.
+//! [`Encryptor`] is the write-side counterpart, paired with
+//! [`ZipFileBuilder::aes`](crate::ZipFileBuilder::aes) to write the headers.
+//!
+//! AE-1 entries keep the CRC32 of the uncompressed data, while AE-2 entries
+//! store zero and rely on the authentication code alone, so only verify the
+//! CRC32 when [`Version::stores_crc32`] says so.
+
+#[cfg(feature = "std")]
+use crate::extra_fields::{AesField, ExtraField, ExtraFieldValue, ExtraFields};
+#[cfg(feature = "std")]
+use crate::{CompressionMethod, Crc32Option, Error, ErrorKind};
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+
+/// Length of the password verifier following the salt.
+pub const PASSWORD_VERIFIER_LEN: usize = 2;
+
+/// Length of the authentication code following the encrypted data.
+pub const AUTHENTICATION_CODE_LEN: usize = 10;
+
+/// The PBKDF2 iteration count fixed by the format.
+pub const PBKDF2_ITERATIONS: u32 = 1000;
+
+const BLOCK_LEN: usize = 16;
+#[cfg(feature = "std")]
+const MAX_KEY_LEN: usize = 32;
+
+/// The cryptographic primitives WinZip AES is built on.
+///
+/// # Examples
+///
+/// An implementation on top of the RustCrypto crates:
+///
+/// ```rust
+/// use aes::cipher::{BlockCipherEncrypt, KeyInit};
+/// use hmac::{Hmac, Mac};
+///
+/// struct RustCrypto;
+///
+/// enum Aes {
+///     Aes128(aes::Aes128),
+///     Aes192(aes::Aes192),
+///     Aes256(aes::Aes256),
+/// }
+///
+/// impl rawzip::aes::BlockCipher for Aes {
+///     fn encrypt_block(&mut self, block: &mut [u8; 16]) {
+///         let block = block.into();
+///         match self {
+///             Aes::Aes128(cipher) => cipher.encrypt_block(block),
+///             Aes::Aes192(cipher) => cipher.encrypt_block(block),
+///             Aes::Aes256(cipher) => cipher.encrypt_block(block),
+///         }
+///     }
+/// }
+///
+/// struct HmacSha1(Hmac<sha1::Sha1>);
+///
+/// impl rawzip::aes::Mac for HmacSha1 {
+///     fn update(&mut self, data: &[u8]) {
+///         self.0.update(data);
+///     }
+///
+///     fn finalize(self) -> [u8; 20] {
+///         self.0.finalize().into_bytes().into()
+///     }
+/// }
+///
+/// impl rawzip::aes::Primitives for RustCrypto {
+///     type Cipher = Aes;
+///     type Mac = HmacSha1;
+///
+///     fn cipher(&self, key: &[u8]) -> Aes {
+///         match key.len() {
+///             16 => Aes::Aes128(aes::Aes128::new_from_slice(key).unwrap()),
+///             24 => Aes::Aes192(aes::Aes192::new_from_slice(key).unwrap()),
+///             _ => Aes::Aes256(aes::Aes256::new_from_slice(key).unwrap()),
+///         }
+///     }
+///
+///     fn mac(&self, key: &[u8]) -> HmacSha1 {
+///         HmacSha1(Hmac::new_from_slice(key).unwrap())
+///     }
+///
+///     fn pbkdf2(&self, password: &[u8], salt: &[u8], iterations: u32, output: &mut [u8]) {
+///         pbkdf2::pbkdf2::<Hmac<sha1::Sha1>>(password, salt, iterations, output).unwrap();
+///     }
+/// }
+/// ```
+pub trait Primitives {
+    /// The AES block cipher
+    type Cipher: BlockCipher;
+
+    /// The HMAC-SHA1 state
+    type Mac: Mac;
+
+    /// Creates an AES block cipher, where the key is 16, 24, or 32 bytes for
+    /// AES-128, AES-192, or AES-256.
+    fn cipher(&self, key: &[u8]) -> Self::Cipher;
+
+    /// Creates an HMAC-SHA1 keyed with `key`.
+    fn mac(&self, key: &[u8]) -> Self::Mac;
+
+    /// Fills `output` with PBKDF2-HMAC-SHA1 of the password and salt.
+    fn pbkdf2(&self, password: &[u8], salt: &[u8], iterations: u32, output: &mut [u8]);
+}
+
+/// An AES block cipher, used in the forward direction only.
+pub trait BlockCipher {
+    /// Encrypts a single block in place.
+    fn encrypt_block(&mut self, block: &mut [u8; BLOCK_LEN]);
+}
+
+/// An HMAC-SHA1 computation.
+pub trait Mac {
+    /// Feeds data into the MAC.
+    fn update(&mut self, data: &[u8]);
+
+    /// Returns the full 20 byte MAC.
+    fn finalize(self) -> [u8; 20];
+}
+
+/// The key size of an AES entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Strength {
+    /// AES-128, stored as 1
+    Aes128,
+    /// AES-192, stored as 2
+    Aes192,
+    /// AES-256, stored as 3
+    Aes256,
+}
+
+impl Strength {
+    /// Returns the strength for the byte stored in the extra field.
+    pub fn from_u8(strength: u8) -> Option<Self> {
+        match strength {
+            1 => Some(Strength::Aes128),
+            2 => Some(Strength::Aes192),
+            3 => Some(Strength::Aes256),
+            _ => None,
+        }
+    }
+
+    /// Returns the byte stored in the extra field.
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            Strength::Aes128 => 1,
+            Strength::Aes192 => 2,
+            Strength::Aes256 => 3,
+        }
+    }
+
+    /// Returns the length of the AES key in bytes.
+    pub fn key_len(&self) -> usize {
+        match self {
+            Strength::Aes128 => 16,
+            Strength::Aes192 => 24,
+            Strength::Aes256 => 32,
+        }
+    }
+
+    /// Returns the length of the salt in bytes, which is half the key length.
+    pub fn salt_len(&self) -> usize {
+        self.key_len() / 2
+    }
+
+    /// Returns the bytes that encryption adds to the compressed data: the
+    /// salt, the password verifier, and the authentication code.
+    pub fn overhead(&self) -> u64 {
+        (self.salt_len() + PASSWORD_VERIFIER_LEN + AUTHENTICATION_CODE_LEN) as u64
+    }
+}
+
+/// The version of the AES format, which decides the stored CRC32.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Version {
+    /// AE-1 stores the CRC32 of the uncompressed data.
+    Ae1,
+    /// AE-2 stores a CRC32 of zero, so the CRC32 cannot leak information
+    /// about small files.
+    Ae2,
+}
+
+impl Version {
+    /// Returns the version for the vendor version stored in the extra field.
+    pub fn from_u16(version: u16) -> Option<Self> {
+        match version {
+            1 => Some(Version::Ae1),
+            2 => Some(Version::Ae2),
+            _ => None,
+        }
+    }
+
+    /// Returns the vendor version stored in the extra field.
+    pub fn as_u16(&self) -> u16 {
+        match self {
+            Version::Ae1 => 1,
+            Version::Ae2 => 2,
+        }
+    }
+
+    /// Returns true if entries of this version store the CRC32 of the
+    /// uncompressed data, so that it can be verified.
+    pub fn stores_crc32(&self) -> bool {
+        matches!(self, Version::Ae1)
+    }
+
+    /// Returns the CRC32 option that writes the CRC32 this version expects.
+    #[cfg(feature = "std")]
+    pub fn crc32_option(&self) -> Crc32Option {
+        match self {
+            Version::Ae1 => Crc32Option::Calculate,
+            Version::Ae2 => Crc32Option::Skip,
+        }
+    }
+}
+
+/// The contents of the WinZip AES extra field (0x9901).
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Parameters {
+    /// The format version
+    pub version: Version,
+
+    /// The key size
+    pub strength: Strength,
+
+    /// The compression method of the data before encryption
+    pub compression_method: CompressionMethod,
+}
+
+#[cfg(feature = "std")]
+impl Parameters {
+    /// Validates a parsed AES extra field.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the vendor id, version, or strength are unknown.
+    pub fn from_field(field: &AesField<'_>) -> Result<Self, Error> {
+        if &field.vendor_id() != b"AE" {
+            return Err(invalid_input("unknown aes vendor id"));
+        }
+
+        let version = Version::from_u16(field.vendor_version())
+            .ok_or_else(|| invalid_input("unknown aes vendor version"))?;
+        let strength = Strength::from_u8(field.strength())
+            .ok_or_else(|| invalid_input("unknown aes strength"))?;
+        Ok(Parameters {
+            version,
+            strength,
+            compression_method: field.compression_method(),
+        })
+    }
+
+    /// Finds and validates the AES extra field among an entry's extra fields.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the field is missing or invalid.
+    pub fn from_extra_fields(fields: ExtraFields<'_>) -> Result<Self, Error> {
+        fields
+            .typed()
+            .find_map(|field| match field {
+                ExtraField::Aes(field) => Some(field),
+                _ => None,
+            })
+            .ok_or_else(|| invalid_input("missing aes extra field"))
+            .and_then(|field| Self::from_field(&field))
+    }
+
+    /// Returns the extra field to write to both headers.
+    pub fn extra_field(&self) -> ExtraFieldValue<'static> {
+        ExtraFieldValue::Aes {
+            vendor_version: self.version.as_u16(),
+            strength: self.strength.as_u8(),
+            compression_method: self.compression_method,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn invalid_input(msg: &str) -> Error {
+    Error::from(ErrorKind::InvalidInput {
+        msg: msg.to_string(),
+    })
+}
+
+/// AES in CTR mode with WinZip's counter: a 128-bit little-endian integer
+/// starting at 1.
+#[cfg(feature = "std")]
+struct Ctr<C> {
+    cipher: C,
+    counter: u128,
+    keystream: [u8; BLOCK_LEN],
+    used: usize,
+}
+
+#[cfg(feature = "std")]
+impl<C: BlockCipher> Ctr<C> {
+    fn new(cipher: C) -> Self {
+        Ctr {
+            cipher,
+            counter: 1,
+            keystream: [0u8; BLOCK_LEN],
+            used: BLOCK_LEN,
+        }
+    }
+
+    fn apply_keystream(&mut self, buf: &mut [u8]) {
+        for byte in buf {
+            if self.used == BLOCK_LEN {
+                self.keystream = self.counter.to_le_bytes();
+                self.cipher.encrypt_block(&mut self.keystream);
+                self.counter = self.counter.wrapping_add(1);
+                self.used = 0;
+            }
+            *byte ^= self.keystream[self.used];
+            self.used += 1;
+        }
+    }
+}
+
+/// Derives the cipher, the MAC, and the password verifier.
+#[cfg(feature = "std")]
+fn derive<P: Primitives>(
+    primitives: &P,
+    password: &[u8],
+    salt: &[u8],
+    strength: Strength,
+) -> (Ctr<P::Cipher>, P::Mac, [u8; PASSWORD_VERIFIER_LEN]) {
+    let key_len = strength.key_len();
+    let mut derived = [0u8; MAX_KEY_LEN * 2 + PASSWORD_VERIFIER_LEN];
+    let derived = &mut derived[..key_len * 2 + PASSWORD_VERIFIER_LEN];
+    primitives.pbkdf2(password, salt, PBKDF2_ITERATIONS, derived);
+
+    let (encryption_key, rest) = derived.split_at(key_len);
+    let (authentication_key, verifier) = rest.split_at(key_len);
+    let cipher = Ctr::new(primitives.cipher(encryption_key));
+    let mac = primitives.mac(authentication_key);
+    (cipher, mac, [verifier[0], verifier[1]])
+}
+
+/// Compares without exiting early on the first difference.
+#[cfg(feature = "std")]
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// A [`Read`] adapter that decrypts a WinZip AES entry on the fly.
+///
+/// On construction it consumes the salt and password verifier, rejecting a
+/// wrong password. Reads then yield the decrypted (but still compressed)
+/// body, and the authentication code is checked once the end is reached. A
+/// decompressor may stop reading before then, so call
+/// [`finish`](Decryptor::finish) to be sure the code was checked.
+///
+/// # Examples
+///
+/// ```rust
+/// # use rawzip::{RECOMMENDED_BUFFER_SIZE, ZipArchive};
+/// # use rawzip::aes::{Decryptor, Parameters};
+/// # use std::io::Read;
+/// # include!("../tests/it/aes_primitives.rs");
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = std::fs::read("assets/aes256.zip")?;
+/// let archive = ZipArchive::from_slice(&data)?;
+/// let entry = archive.entries().next().unwrap()?;
+/// let parameters = Parameters::from_extra_fields(entry.extra_fields())?;
+/// let zip_entry = archive.get_entry(entry.wayfinder())?;
+///
+/// let decryptor = Decryptor::new(
+///     zip_entry.data(),
+///     &RustCrypto,
+///     b"rawzipiscool",
+///     parameters.strength,
+///     entry.compressed_size_hint(),
+/// )?;
+/// let mut inflater = flate2::read::DeflateDecoder::new(decryptor);
+/// let mut output = Vec::new();
+/// inflater.read_to_end(&mut output)?;
+/// inflater.into_inner().finish()?;
+///
+/// // The entry is AE-2, which stores no CRC32 to verify
+/// assert!(!parameters.version.stores_crc32());
+/// assert_eq!(output, b"aaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub struct Decryptor<R, P: Primitives> {
+    reader: R,
+    ctr: Ctr<P::Cipher>,
+    mac: Option<P::Mac>,
+
+    /// Count of encrypted bytes left before the authentication code
+    remaining: u64,
+}
+
+#[cfg(feature = "std")]
+impl<R, P: Primitives> std::fmt::Debug for Decryptor<R, P>
+where
+    R: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Decryptor")
+            .field("reader", &self.reader)
+            .field("remaining", &self.remaining)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Read, P: Primitives> Decryptor<R, P> {
+    /// Wraps a reader positioned at the start of an entry's data, reading the
+    /// salt and password verifier.
+    ///
+    /// `compressed_size` is the entry's compressed size, which includes the
+    /// salt, password verifier, and authentication code.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::ErrorKind::InvalidData`] if the compressed size is too
+    /// small for the strength or the password does not match the verifier.
+    /// The verifier is two bytes, so a wrong password slips through 1 in
+    /// 65,536 times, to be caught by the authentication code.
+    pub fn new(
+        mut reader: R,
+        primitives: &P,
+        password: &[u8],
+        strength: Strength,
+        compressed_size: u64,
+    ) -> io::Result<Self> {
+        let remaining = compressed_size
+            .checked_sub(strength.overhead())
+            .ok_or_else(|| invalid_data("aes entry is too small"))?;
+
+        let mut salt = [0u8; MAX_KEY_LEN / 2];
+        let salt = &mut salt[..strength.salt_len()];
+        reader.read_exact(salt)?;
+        let mut verifier = [0u8; PASSWORD_VERIFIER_LEN];
+        reader.read_exact(&mut verifier)?;
+
+        let (ctr, mac, expected) = derive(primitives, password, salt, strength);
+        if !constant_time_eq(&verifier, &expected) {
+            return Err(invalid_data("incorrect aes password"));
+        }
+
+        Ok(Decryptor {
+            reader,
+            ctr,
+            mac: Some(mac),
+            remaining,
+        })
+    }
+
+    /// Reads and checks the authentication code, once.
+    fn authenticate(&mut self) -> io::Result<()> {
+        let Some(mac) = self.mac.take() else {
+            return Ok(());
+        };
+
+        let mut code = [0u8; AUTHENTICATION_CODE_LEN];
+        self.reader.read_exact(&mut code)?;
+        let expected = mac.finalize();
+        if !constant_time_eq(&code, &expected[..AUTHENTICATION_CODE_LEN]) {
+            return Err(invalid_data("aes authentication code mismatch"));
+        }
+
+        Ok(())
+    }
+
+    /// Reads any encrypted data left unread, checks the authentication code,
+    /// and returns the wrapped reader.
+    pub fn finish(mut self) -> io::Result<R> {
+        io::copy(&mut self, &mut io::sink())?;
+        self.authenticate()?;
+        Ok(self.reader)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Read, P: Primitives> Read for Decryptor<R, P> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        if self.remaining == 0 {
+            self.authenticate()?;
+            return Ok(0);
+        }
+
+        let len = buf
+            .len()
+            .min(usize::try_from(self.remaining).unwrap_or(usize::MAX));
+        let read = self.reader.read(&mut buf[..len])?;
+        if read == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "aes entry ended unexpectedly",
+            ));
+        }
+
+        let buf = &mut buf[..read];
+        if let Some(mac) = self.mac.as_mut() {
+            mac.update(buf);
+        }
+        self.ctr.apply_keystream(buf);
+        self.remaining -= read as u64;
+        Ok(read)
+    }
+}
+
+#[cfg(feature = "std")]
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(feature = "std")]
+const CHUNK: usize = 8 * 1024;
+
+/// A [`Write`] adapter that encrypts a WinZip AES entry on the fly.
+///
+/// On construction it emits the salt and password verifier; writes then
+/// encrypt the (already compressed) body. [`finish`](Encryptor::finish)
+/// writes the authentication code, so it must be called before the entry is
+/// finished.
+///
+/// # Examples
+///
+/// ```rust
+/// # use rawzip::{CompressionMethod, ZipArchive, ZipArchiveWriter};
+/// # use rawzip::aes::{Decryptor, Encryptor, Parameters, Strength, Version};
+/// # use std::io::{Read, Write};
+/// # include!("../tests/it/aes_primitives.rs");
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let password = b"rawzipiscool";
+/// let parameters = Parameters {
+///     version: Version::Ae2,
+///     strength: Strength::Aes256,
+///     compression_method: CompressionMethod::DEFLATE,
+/// };
+///
+/// let mut output = std::io::Cursor::new(Vec::new());
+/// let mut archive = ZipArchiveWriter::new(&mut output);
+/// let (mut entry, config) = archive.new_file("test.txt").aes(&parameters)?.start()?;
+///
+/// // In production the salt must come from a cryptographic RNG.
+/// let salt = [7u8; 16];
+/// let encryptor = Encryptor::new(&mut entry, &RustCrypto, password, parameters.strength, &salt)?;
+/// let deflater = flate2::write::DeflateEncoder::new(encryptor, flate2::Compression::default());
+/// let mut writer = config.wrap(deflater);
+/// writer.write_all(b"aaaaaaaaaaaaaaaa\n")?;
+/// let (deflater, descriptor) = writer.finish()?;
+/// deflater.finish()?.finish()?;
+/// entry.finish(descriptor)?;
+/// archive.finish()?;
+///
+/// let zip = output.into_inner();
+/// let archive = ZipArchive::from_slice(&zip)?;
+/// let entry = archive.entries().next().unwrap()?;
+/// assert_eq!(Parameters::from_extra_fields(entry.extra_fields())?, parameters);
+///
+/// let zip_entry = archive.get_entry(entry.wayfinder())?;
+/// let decryptor = Decryptor::new(
+///     zip_entry.data(),
+///     &RustCrypto,
+///     password,
+///     parameters.strength,
+///     entry.compressed_size_hint(),
+/// )?;
+/// let mut decoded = Vec::new();
+/// flate2::read::DeflateDecoder::new(decryptor).read_to_end(&mut decoded)?;
+/// assert_eq!(decoded, b"aaaaaaaaaaaaaaaa\n");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub struct Encryptor<W, P: Primitives> {
+    writer: W,
+    ctr: Ctr<P::Cipher>,
+    mac: P::Mac,
+    scratch: Box<[u8; CHUNK]>,
+}
+
+#[cfg(feature = "std")]
+impl<W, P: Primitives> std::fmt::Debug for Encryptor<W, P>
+where
+    W: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Encryptor")
+            .field("writer", &self.writer)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: Write, P: Primitives> Encryptor<W, P> {
+    /// Wraps a writer positioned at the start of an entry's body, emitting the
+    /// salt and password verifier.
+    ///
+    /// The salt must be [`Strength::salt_len`] bytes. Reusing a salt with the
+    /// same password reuses the keystream, so draw it from a cryptographic
+    /// RNG.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::ErrorKind::InvalidInput`] if the salt has the wrong
+    /// length, or an error from the writer.
+    pub fn new(
+        mut writer: W,
+        primitives: &P,
+        password: &[u8],
+        strength: Strength,
+        salt: &[u8],
+    ) -> io::Result<Self> {
+        if salt.len() != strength.salt_len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "aes salt length does not match the strength",
+            ));
+        }
+
+        let (ctr, mac, verifier) = derive(primitives, password, salt, strength);
+        writer.write_all(salt)?;
+        writer.write_all(&verifier)?;
+        Ok(Encryptor {
+            writer,
+            ctr,
+            mac,
+            scratch: Box::new([0u8; CHUNK]),
+        })
+    }
+
+    /// Writes the authentication code, returning the wrapped writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        let code = self.mac.finalize();
+        self.writer.write_all(&code[..AUTHENTICATION_CODE_LEN])?;
+        Ok(self.writer)
+    }
+
+    /// Gets a shared reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: Write, P: Primitives> Write for Encryptor<W, P> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for chunk in buf.chunks(CHUNK) {
+            let part = &mut self.scratch[..chunk.len()];
+            part.copy_from_slice(chunk);
+            self.ctr.apply_keystream(part);
+            self.mac.update(part);
+            self.writer.write_all(part)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    /// A stand-in cipher that XORs the block with the key, and a MAC that
+    /// sums its input. Enough to exercise the framing, not for real use.
+    struct Toy;
+
+    struct ToyCipher([u8; BLOCK_LEN]);
+
+    impl BlockCipher for ToyCipher {
+        fn encrypt_block(&mut self, block: &mut [u8; BLOCK_LEN]) {
+            for (byte, key) in block.iter_mut().zip(self.0) {
+                *byte ^= key;
+            }
+        }
+    }
+
+    struct ToyMac(u8);
+
+    impl Mac for ToyMac {
+        fn update(&mut self, data: &[u8]) {
+            for byte in data {
+                self.0 = self.0.wrapping_add(*byte);
+            }
+        }
+
+        fn finalize(self) -> [u8; 20] {
+            [self.0; 20]
+        }
+    }
+
+    impl Primitives for Toy {
+        type Cipher = ToyCipher;
+        type Mac = ToyMac;
+
+        fn cipher(&self, key: &[u8]) -> ToyCipher {
+            let mut block = [0u8; BLOCK_LEN];
+            block.copy_from_slice(&key[..BLOCK_LEN]);
+            ToyCipher(block)
+        }
+
+        fn mac(&self, key: &[u8]) -> ToyMac {
+            ToyMac(key[0])
+        }
+
+        fn pbkdf2(&self, password: &[u8], salt: &[u8], _iterations: u32, output: &mut [u8]) {
+            for (i, byte) in output.iter_mut().enumerate() {
+                *byte = password[i % password.len()] ^ salt[i % salt.len()] ^ i as u8;
+            }
+        }
+    }
+
+    fn encrypt(plaintext: &[u8]) -> Vec<u8> {
+        let mut output = Vec::new();
+        let mut encryptor =
+            Encryptor::new(&mut output, &Toy, b"hunter2", Strength::Aes128, &[9u8; 8]).unwrap();
+        encryptor.write_all(plaintext).unwrap();
+        encryptor.finish().unwrap();
+        output
+    }
+
+    fn decrypt(data: &[u8], password: &[u8]) -> io::Result<Vec<u8>> {
+        let mut decryptor =
+            Decryptor::new(data, &Toy, password, Strength::Aes128, data.len() as u64)?;
+        let mut output = Vec::new();
+        decryptor.read_to_end(&mut output)?;
+        Ok(output)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let plaintext = b"the quick brown fox jumps over the lazy dog".repeat(400);
+        let encrypted = encrypt(&plaintext);
+        assert_eq!(
+            encrypted.len() as u64,
+            plaintext.len() as u64 + Strength::Aes128.overhead()
+        );
+        assert_ne!(&encrypted[10..20], &plaintext[..10]);
+        assert_eq!(decrypt(&encrypted, b"hunter2").unwrap(), plaintext);
+    }
+
+    #[test]
+    fn test_counter_is_little_endian_from_one() {
+        let mut ctr = Ctr::new(ToyCipher([0u8; BLOCK_LEN]));
+        let mut keystream = [0u8; BLOCK_LEN * 2];
+        ctr.apply_keystream(&mut keystream);
+        assert_eq!(&keystream[..BLOCK_LEN], &1u128.to_le_bytes());
+        assert_eq!(&keystream[BLOCK_LEN..], &2u128.to_le_bytes());
+    }
+
+    #[test]
+    fn test_wrong_password() {
+        let encrypted = encrypt(b"secret");
+        let err = decrypt(&encrypted, b"swordfish").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_tampered_ciphertext() {
+        let mut encrypted = encrypt(b"secret");
+        encrypted[10] ^= 1;
+        let err = decrypt(&encrypted, b"hunter2").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_finish_authenticates_unread_data() {
+        let mut encrypted = encrypt(b"secret");
+        encrypted[15] ^= 1;
+        let size = encrypted.len() as u64;
+        let decryptor =
+            Decryptor::new(&encrypted[..], &Toy, b"hunter2", Strength::Aes128, size).unwrap();
+        let err = decryptor.finish().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_too_small() {
+        let err =
+            Decryptor::new(&[0u8; 19][..], &Toy, b"hunter2", Strength::Aes128, 19).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_wrong_salt_length() {
+        let err =
+            Encryptor::new(Vec::new(), &Toy, b"hunter2", Strength::Aes256, &[0u8; 8]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_parameters_from_field() {
+        let data = [2, 0, b'A', b'E', 3, 8, 0];
+        let ExtraField::Aes(field) =
+            ExtraField::parse(crate::extra_fields::ExtraFieldId::AES, &data)
+        else {
+            panic!("expected aes");
+        };
+        let parameters = Parameters::from_field(&field).unwrap();
+        assert_eq!(
+            parameters,
+            Parameters {
+                version: Version::Ae2,
+                strength: Strength::Aes256,
+                compression_method: CompressionMethod::DEFLATE,
+            }
+        );
+
+        for data in [
+            [3, 0, b'A', b'E', 3, 8, 0],
+            [2, 0, b'A', b'E', 4, 8, 0],
+            [2, 0, b'X', b'E', 3, 8, 0],
+        ] {
+            let ExtraField::Aes(field) =
+                ExtraField::parse(crate::extra_fields::ExtraFieldId::AES, &data)
+            else {
+                panic!("expected aes");
+            };
+            assert!(Parameters::from_field(&field).is_err());
+        }
+    }
+}
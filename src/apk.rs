@@ -0,0 +1,242 @@
+//! The APK Signing Block.
+//!
+//! Android's v2 and later signature schemes insert a block between the last
+//! entry's data and the central directory. Zip readers that only follow the
+//! central directory never see it, but it is what makes an APK verifiable:
+//!
+//! - size of the block excluding this field - 8 bytes
+//! - id-value pairs, each a `u64` length, a `u32` ID, and the value
+//! - size of the block excluding this field (repeated) - 8 bytes
+//! - magic `APK Sig Block 42` - 16 bytes
+//!
+//! The block is found by looking back from the central directory, see
+//! [`ZipSliceArchive::apk_signing_block`](crate::ZipSliceArchive::apk_signing_block).
+
+use crate::utils::{le_u32, le_u64};
+use core::ops::Range;
+
+/// The magic that ends an APK Signing Block.
+pub const MAGIC: [u8; 16] = *b"APK Sig Block 42";
+
+/// Length of the trailer at the end of the block: the repeated size and the
+/// magic. These are the bytes immediately before the central directory.
+pub const FOOTER_LEN: usize = 8 + MAGIC.len();
+
+/// The smallest valid block: both size fields and the magic with no pairs.
+const MIN_LEN: u64 = 8 + FOOTER_LEN as u64;
+
+/// A numeric identifier for an id-value pair in an APK Signing Block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SigningBlockId(u32);
+
+impl SigningBlockId {
+    /// APK Signature Scheme v2.
+    pub const V2_SIGNATURE: Self = Self(0x7109871a);
+    /// APK Signature Scheme v3.
+    pub const V3_SIGNATURE: Self = Self(0xf05368c0);
+    /// APK Signature Scheme v3.1.
+    pub const V3_1_SIGNATURE: Self = Self(0x1b93ad61);
+    /// Padding that rounds the block up to a multiple of 4 KiB for APK verity.
+    pub const VERITY_PADDING: Self = Self(0x42726577);
+    /// SourceStamp, which records the source of the APK.
+    pub const SOURCE_STAMP: Self = Self(0x6dff800d);
+
+    /// Creates an ID from its raw `u32` value.
+    #[inline]
+    pub const fn new(id: u32) -> Self {
+        Self(id)
+    }
+
+    /// Returns the raw `u32` value of the ID.
+    #[inline]
+    pub const fn as_u32(self) -> u32 {
+        self.0
+    }
+}
+
+/// Returns the size of the block ending at `directory_offset` from the
+/// `footer`, the [`FOOTER_LEN`] bytes right before it.
+///
+/// The size counts every byte of the block except the leading size field.
+pub(crate) fn block_len(footer: &[u8; FOOTER_LEN], directory_offset: u64) -> Option<u64> {
+    if footer[8..] != MAGIC {
+        return None;
+    }
+
+    let size = le_u64(&footer[..8]);
+    let len = size.checked_add(8)?;
+    (len >= MIN_LEN && len <= directory_offset).then_some(len)
+}
+
+/// A view of an APK Signing Block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SigningBlock<'a> {
+    data: &'a [u8],
+    offset: u64,
+}
+
+impl<'a> SigningBlock<'a> {
+    /// Parses the block in `data`, which starts at `offset` of the input.
+    ///
+    /// Returns `None` when `data` is not exactly one block: the magic is
+    /// missing or either size field disagrees with the length of `data`.
+    pub fn new(data: &'a [u8], offset: u64) -> Option<Self> {
+        let len = data.len() as u64;
+        if len < MIN_LEN || data[data.len() - MAGIC.len()..] != MAGIC {
+            return None;
+        }
+
+        let size = len - 8;
+        let trailing_size = le_u64(&data[data.len() - FOOTER_LEN..]);
+        (le_u64(data) == size && trailing_size == size).then_some(SigningBlock { data, offset })
+    }
+
+    /// The raw bytes of the block, from the leading size field to the magic.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// The byte range of the block in the input. The range ends where the
+    /// central directory starts.
+    pub fn range(&self) -> Range<u64> {
+        self.offset..self.offset + self.data.len() as u64
+    }
+
+    /// Returns an iterator over the id-value pairs of the block.
+    pub fn pairs(&self) -> SigningBlockPairs<'a> {
+        SigningBlockPairs {
+            data: &self.data[8..self.data.len() - FOOTER_LEN],
+        }
+    }
+
+    /// Returns the value of the first pair with the given ID.
+    pub fn find(&self, id: SigningBlockId) -> Option<&'a [u8]> {
+        self.pairs()
+            .find_map(|(pair_id, value)| (pair_id == id).then_some(value))
+    }
+}
+
+/// An iterator over the id-value pairs of an APK Signing Block.
+///
+/// If the iterator encounters malformed or truncated data, it will stop
+/// yielding pairs. You can check [`SigningBlockPairs::remaining_bytes()`]
+/// after iteration to detect if any data was left unparsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SigningBlockPairs<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> SigningBlockPairs<'a> {
+    /// Returns the remaining unparsed bytes of the pairs.
+    pub fn remaining_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+impl<'a> Iterator for SigningBlockPairs<'a> {
+    type Item = (SigningBlockId, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.len() < 12 {
+            return None;
+        }
+
+        // The pair length covers the ID and the value
+        let len = le_u64(self.data);
+        if len < 4 || len > (self.data.len() - 8) as u64 {
+            return None;
+        }
+
+        let (pair, rest) = self.data[8..].split_at(len as usize);
+        self.data = rest;
+        Some((SigningBlockId(le_u32(pair)), &pair[4..]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(pairs: &[(u32, &[u8])]) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (id, value) in pairs {
+            body.extend_from_slice(&(value.len() as u64 + 4).to_le_bytes());
+            body.extend_from_slice(&id.to_le_bytes());
+            body.extend_from_slice(value);
+        }
+
+        let size = (body.len() + FOOTER_LEN) as u64;
+        let mut data = size.to_le_bytes().to_vec();
+        data.extend_from_slice(&body);
+        data.extend_from_slice(&size.to_le_bytes());
+        data.extend_from_slice(&MAGIC);
+        data
+    }
+
+    #[test]
+    fn test_pairs() {
+        let data = block(&[(0x7109871a, b"v2"), (0x42726577, &[0; 6])]);
+        let block = SigningBlock::new(&data, 100).unwrap();
+        assert_eq!(block.range(), 100..100 + data.len() as u64);
+
+        let mut pairs = block.pairs();
+        assert_eq!(
+            pairs.next(),
+            Some((SigningBlockId::V2_SIGNATURE, &b"v2"[..]))
+        );
+        assert_eq!(
+            pairs.next(),
+            Some((SigningBlockId::VERITY_PADDING, &[0u8; 6][..]))
+        );
+        assert_eq!(pairs.next(), None);
+        assert!(pairs.remaining_bytes().is_empty());
+
+        assert_eq!(block.find(SigningBlockId::V2_SIGNATURE), Some(&b"v2"[..]));
+        assert_eq!(block.find(SigningBlockId::V3_SIGNATURE), None);
+    }
+
+    #[test]
+    fn test_empty_block() {
+        let data = block(&[]);
+        let block = SigningBlock::new(&data, 0).unwrap();
+        assert_eq!(block.pairs().next(), None);
+    }
+
+    #[test]
+    fn test_rejects_mismatched_sizes() {
+        let mut data = block(&[(0x7109871a, b"v2")]);
+        data[0] += 1;
+        assert_eq!(SigningBlock::new(&data, 0), None);
+
+        let data = block(&[(0x7109871a, b"v2")]);
+        assert_eq!(SigningBlock::new(&data[1..], 0), None);
+    }
+
+    #[test]
+    fn test_rejects_missing_magic() {
+        let mut data = block(&[]);
+        let last = data.len() - 1;
+        data[last] = b'3';
+        assert_eq!(SigningBlock::new(&data, 0), None);
+    }
+
+    #[test]
+    fn test_truncated_pair() {
+        let mut data = block(&[(0x7109871a, b"v2")]);
+        data[8] = 0xff;
+        let block = SigningBlock::new(&data, 0).unwrap();
+        let mut pairs = block.pairs();
+        assert_eq!(pairs.next(), None);
+        assert_eq!(pairs.remaining_bytes().len(), 14);
+    }
+
+    #[test]
+    fn test_block_len() {
+        let data = block(&[(0x7109871a, b"v2")]);
+        let footer: [u8; FOOTER_LEN] = data[data.len() - FOOTER_LEN..].try_into().unwrap();
+        let len = data.len() as u64;
+        assert_eq!(block_len(&footer, len), Some(len));
+        assert_eq!(block_len(&footer, len + 10), Some(len));
+        assert_eq!(block_len(&footer, len - 1), None);
+    }
+}
@@ -1,12 +1,19 @@
 #[cfg(feature = "std")]
 use crate::Crc32;
+use crate::aes;
+use crate::apk;
+#[cfg(feature = "std")]
+use crate::codec::{DecoderContext, Decompressors};
 use crate::errors::{Error, ErrorKind};
-use crate::extra_fields::{ExtraFieldId, ExtraFields};
+use crate::extra_fields::{AesField, ExtraField, ExtraFieldId, ExtraFields, find_unicode_field};
 use crate::headers::EntryFlags;
 use crate::mode::{
-    CREATOR_FAT, CREATOR_MACOS, CREATOR_NTFS, CREATOR_UNIX, CREATOR_VFAT, EntryMode,
-    msdos_mode_to_file_mode, unix_mode_to_file_mode,
+    CREATOR_FAT, CREATOR_MACOS, CREATOR_NTFS, CREATOR_NTFS_SPEC, CREATOR_UNIX, CREATOR_VFAT,
+    CreatorOs, DosAttributes, EntryMode, S_IFDIR, S_IFMT, msdos_mode_to_file_mode,
+    unix_mode_to_file_mode,
 };
+#[cfg(feature = "alloc")]
+use crate::path::{Cp437, NameDecoder, NormalizedPath, SymlinkTarget};
 use crate::path::{RawPath, ZipFilePath};
 #[cfg(feature = "std")]
 use crate::reader_at::{ReaderAt, ReaderAtExt};
@@ -20,6 +27,10 @@ use std::io::Write;
 mod reader;
 #[cfg(feature = "std")]
 pub use reader::{ZipEntries, ZipEntry, ZipReader, ZipSliceVerifier, ZipVerifier};
+#[cfg(feature = "std")]
+mod snapshot;
+#[cfg(feature = "std")]
+pub use snapshot::{ZipSnapshot, ZipSnapshotEntries, ZipSnapshotEntry};
 
 pub(crate) const END_OF_CENTRAL_DIR_SIGNATURE64: u32 = 0x06064b50;
 pub(crate) const END_OF_CENTRAL_DIR_LOCATOR_SIGNATURE: u32 = 0x07064b50;
@@ -153,6 +164,37 @@ impl<T: AsRef<[u8]>> ZipSliceArchive<T> {
         ZipStr::new(&data[comment_start..comment_start + comment_len])
     }
 
+    /// Returns the APK Signing Block that immediately precedes the central
+    /// directory, if any.
+    ///
+    /// Android APKs signed with the v2 or later schemes carry this block
+    /// between the last entry and the central directory. A block whose size
+    /// fields disagree is not reported, and its bytes remain unaccounted for
+    /// like any other gap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rawzip::apk::SigningBlockId;
+    ///
+    /// fn is_v2_signed(data: &[u8]) -> Result<bool, rawzip::Error> {
+    ///     let archive = rawzip::ZipArchive::from_slice(data)?;
+    ///     let block = archive.apk_signing_block();
+    ///     Ok(block.is_some_and(|block| block.find(SigningBlockId::V2_SIGNATURE).is_some()))
+    /// }
+    /// ```
+    pub fn apk_signing_block(&self) -> Option<apk::SigningBlock<'_>> {
+        let data = self.data.as_ref();
+        let directory_offset = self.eocd.directory_offset();
+        let footer_start = (directory_offset as usize).checked_sub(apk::FOOTER_LEN)?;
+        let footer = data[footer_start..directory_offset as usize]
+            .try_into()
+            .ok()?;
+        let len = apk::block_len(footer, directory_offset)?;
+        let start = directory_offset - len;
+        apk::SigningBlock::new(&data[start as usize..directory_offset as usize], start)
+    }
+
     /// Converts the [`ZipSliceArchive`] into a general [`ZipArchive`] by
     /// wrapping the data in a [`std::io::Cursor`].
     ///
@@ -199,8 +241,10 @@ impl<T: AsRef<[u8]>> ZipSliceArchive<T> {
             },
             local_header_offset: entry.local_header_offset,
             data_start_offset: header_size,
-            has_data_descriptor: entry.has_data_descriptor,
+            has_data_descriptor: entry.flags.has_data_descriptor(),
             data_descriptor_uses_zip64_sizes: entry.data_descriptor_uses_zip64_sizes,
+            compression_method: entry.compression_method,
+            flags: entry.flags,
             descriptor,
         })
     }
@@ -219,6 +263,10 @@ pub struct ZipSliceEntry<'a> {
     data_start_offset: u32,
     has_data_descriptor: bool,
     data_descriptor_uses_zip64_sizes: bool,
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    compression_method: CompressionMethod,
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    flags: EntryFlags,
     descriptor: &'a [u8],
 }
 
@@ -268,6 +316,54 @@ impl<'a> ZipSliceEntry<'a> {
         })
     }
 
+    /// Returns a verifying reader of the decompressed data, using the
+    /// decompressor registered for the entry's compression method.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::UnsupportedCompressionMethod`] if no decompressor
+    /// is registered for the method, and an error if the entry is encrypted
+    /// or the decompressor fails to initialize.
+    ///
+    /// ```rust
+    /// use rawzip::{CompressionMethod, Decompressors, ZipArchive};
+    /// use std::io::Read;
+    ///
+    /// let data = std::fs::read("assets/test.zip")?;
+    /// let archive = ZipArchive::from_slice(&data)?;
+    /// let entry = archive.entries().next_entry()?.unwrap();
+    ///
+    /// let mut decompressors = Decompressors::new();
+    /// decompressors.register(CompressionMethod::DEFLATE, |reader, _| {
+    ///     Ok(Box::new(flate2::read::DeflateDecoder::new(reader)))
+    /// });
+    ///
+    /// let mut contents = String::new();
+    /// let entry = archive.get_entry(entry.wayfinder())?;
+    /// entry.decoded_reader(&decompressors)?.read_to_string(&mut contents)?;
+    /// assert_eq!(contents, "This is a test text file.\n");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn decoded_reader(
+        &self,
+        decompressors: &Decompressors,
+    ) -> Result<ZipSliceVerifier<Box<dyn std::io::Read + 'a>>, Error> {
+        if self.flags.is_encrypted() {
+            return Err(Error::from(ErrorKind::InvalidInput {
+                msg: alloc::string::String::from("entry is encrypted"),
+            }));
+        }
+
+        let context = DecoderContext::new(
+            self.compression_method,
+            self.flags,
+            self.verifier.uncompressed_size,
+        );
+        let reader = decompressors.decoder(&context, Box::new(self.data()))?;
+        Ok(self.verifying_reader(reader))
+    }
+
     /// Returns the byte range of the compressed data within the archive.
     ///
     /// This range is calculated from the local file header and identifies the
@@ -516,6 +612,21 @@ impl<'a> ZipLocalFileHeader<'a> {
         self.file_path
     }
 
+    /// Returns the file path, preferring the UTF-8 name of an Info-ZIP
+    /// Unicode Path extra field.
+    ///
+    /// See [`ZipFileHeaderRecord::unicode_file_path`] for details.
+    #[inline]
+    pub fn unicode_file_path(&self) -> ZipFilePath<RawPath<'a>> {
+        let raw = self.file_path.as_bytes();
+        find_unicode_field(
+            self.extra_fields(),
+            ExtraFieldId::INFO_ZIP_UNICODE_PATH,
+            raw,
+        )
+        .map_or(self.file_path, ZipFilePath::from_bytes)
+    }
+
     /// Returns the raw MS-DOS modification timestamp from the local file header.
     ///
     /// This may differ from the central directory record's
@@ -531,6 +642,28 @@ impl<'a> ZipLocalFileHeader<'a> {
         self.fixed.compression_method
     }
 
+    /// Returns the compression method applied to the data before encryption,
+    /// as declared in the local file header.
+    ///
+    /// See [`ZipFileHeaderRecord::effective_compression_method`].
+    #[inline]
+    pub fn effective_compression_method(&self) -> CompressionMethod {
+        effective_compression_method(self.fixed.compression_method, self.extra_fields())
+    }
+
+    /// Returns how the entry is encrypted, as declared in the local file
+    /// header.
+    ///
+    /// See [`ZipFileHeaderRecord::encryption`].
+    #[inline]
+    pub fn encryption(&self) -> Encryption {
+        Encryption::of(
+            self.fixed.flags,
+            self.fixed.compression_method,
+            self.extra_fields(),
+        )
+    }
+
     /// Returns the last modification date and time declared in the local file header.
     ///
     /// Extended timestamps in the local header's extra fields are preferred
@@ -994,6 +1127,163 @@ impl CompressionMethod {
     pub const Aes: Self = Self::AES;
 }
 
+/// How an entry's data is encrypted, as returned by
+/// [`ZipFileHeaderRecord::encryption`] and [`ZipLocalFileHeader::encryption`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Encryption {
+    /// The entry is not encrypted.
+    None,
+
+    /// Traditional PKWARE encryption, see the `zipcrypto` module.
+    ZipCrypto,
+
+    /// WinZip AES, stored with [`CompressionMethod::AES`] and described by
+    /// the AES extra field (0x9901).
+    WinZipAes {
+        /// The format version, if the AES extra field is present and the
+        /// version is known
+        version: Option<aes::Version>,
+        /// The key size, if the AES extra field is present and the strength
+        /// is known
+        strength: Option<aes::Strength>,
+    },
+
+    /// PKWARE Strong Encryption.
+    StrongEncryption {
+        /// The algorithm from the Strong Encryption Header extra field
+        /// (0x0017), if present
+        algorithm: Option<EncryptionAlgorithm>,
+    },
+}
+
+impl Encryption {
+    /// Determines the encryption from an entry's flags, compression method,
+    /// and extra fields.
+    fn of(flags: EntryFlags, method: CompressionMethod, extra_fields: ExtraFields<'_>) -> Self {
+        if !flags.is_encrypted() {
+            return Encryption::None;
+        }
+
+        if flags.has_strong_encryption() {
+            let algorithm = extra_fields
+                .filter(|(id, _)| *id == ExtraFieldId::STRONG_ENCRYPTION_HEADER)
+                .find_map(|(_, data)| data.get(2..4))
+                .map(|id| EncryptionAlgorithm(le_u16(id)));
+            return Encryption::StrongEncryption { algorithm };
+        }
+
+        if method != CompressionMethod::AES {
+            return Encryption::ZipCrypto;
+        }
+
+        let field = aes_field(method, extra_fields);
+        Encryption::WinZipAes {
+            version: field.and_then(|aes| aes::Version::from_u16(aes.vendor_version())),
+            strength: field.and_then(|aes| aes::Strength::from_u8(aes.strength())),
+        }
+    }
+
+    /// Returns true unless the entry is not encrypted.
+    #[inline]
+    pub fn is_encrypted(&self) -> bool {
+        !matches!(self, Encryption::None)
+    }
+}
+
+/// Returns the AES extra field of an entry stored with
+/// [`CompressionMethod::AES`].
+fn aes_field(method: CompressionMethod, extra_fields: ExtraFields<'_>) -> Option<AesField<'_>> {
+    if method != CompressionMethod::AES {
+        return None;
+    }
+
+    extra_fields.typed().find_map(|field| match field {
+        ExtraField::Aes(aes) => Some(aes),
+        _ => None,
+    })
+}
+
+/// Returns the compression method applied before encryption.
+fn effective_compression_method(
+    method: CompressionMethod,
+    extra_fields: ExtraFields<'_>,
+) -> CompressionMethod {
+    aes_field(method, extra_fields).map_or(method, |aes| aes.compression_method())
+}
+
+/// The algorithm of a PKWARE Strong Encryption entry (APPNOTE § 7.2.3.2).
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EncryptionAlgorithm(u16);
+
+impl EncryptionAlgorithm {
+    /// DES.
+    pub const DES: Self = Self(0x6601);
+    /// RC2 before version 5.2.
+    pub const RC2_OLD: Self = Self(0x6602);
+    /// Triple DES with a 168-bit key.
+    pub const TRIPLE_DES_168: Self = Self(0x6603);
+    /// Triple DES with a 112-bit key.
+    pub const TRIPLE_DES_112: Self = Self(0x6609);
+    /// AES with a 128-bit key.
+    pub const AES128: Self = Self(0x660e);
+    /// AES with a 192-bit key.
+    pub const AES192: Self = Self(0x660f);
+    /// AES with a 256-bit key.
+    pub const AES256: Self = Self(0x6610);
+    /// RC2 from version 5.2 on.
+    pub const RC2: Self = Self(0x6702);
+    /// Blowfish.
+    pub const BLOWFISH: Self = Self(0x6720);
+    /// Twofish.
+    pub const TWOFISH: Self = Self(0x6721);
+    /// RC4.
+    pub const RC4: Self = Self(0x6801);
+    /// An algorithm the encoder did not identify.
+    pub const UNKNOWN: Self = Self(0xffff);
+
+    /// Wrap a raw algorithm id.
+    #[inline]
+    pub const fn new(id: u16) -> Self {
+        Self(id)
+    }
+
+    /// Returns the raw value of the algorithm id.
+    #[inline]
+    pub const fn as_u16(self) -> u16 {
+        self.0
+    }
+
+    /// Returns the algorithm name (eg: `"AES256"`) when known
+    #[inline]
+    pub const fn name(self) -> Option<&'static str> {
+        match self.0 {
+            0x6601 => Some("DES"),
+            0x6602 => Some("RC2_OLD"),
+            0x6603 => Some("TRIPLE_DES_168"),
+            0x6609 => Some("TRIPLE_DES_112"),
+            0x660e => Some("AES128"),
+            0x660f => Some("AES192"),
+            0x6610 => Some("AES256"),
+            0x6702 => Some("RC2"),
+            0x6720 => Some("BLOWFISH"),
+            0x6721 => Some("TWOFISH"),
+            0x6801 => Some("RC4"),
+            0xffff => Some("UNKNOWN"),
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Debug for EncryptionAlgorithm {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "EncryptionAlgorithm::{name}"),
+            None => write!(f, "EncryptionAlgorithm({:#06x})", self.0),
+        }
+    }
+}
+
 /// A borrowed data from a Zip archive, typically for comments or non-path text.
 ///
 /// Zip archives may contain text that is not strictly UTF-8. This type
@@ -1201,9 +1491,10 @@ impl<'a> ZipFileHeaderRecord<'a> {
             uncompressed_size: self.uncompressed_size,
             compressed_size: self.compressed_size,
             local_header_offset: self.local_header_offset,
-            has_data_descriptor: self.flags().has_data_descriptor(),
             crc: self.crc32,
+            flags: self.flags(),
             data_descriptor_uses_zip64_sizes: self.data_descriptor_uses_zip64_sizes,
+            compression_method: self.compression_method,
         }
     }
 
@@ -1245,6 +1536,48 @@ impl<'a> ZipFileHeaderRecord<'a> {
         self.compression_method
     }
 
+    /// The compression method applied to the data before encryption.
+    ///
+    /// WinZip AES entries are stored with [`CompressionMethod::AES`] and keep
+    /// the real method in their AES extra field (0x9901), which is returned
+    /// instead. Every other entry returns its
+    /// [`compression_method`](Self::compression_method).
+    ///
+    /// ```rust
+    /// # use rawzip::{CompressionMethod, Encryption, ZipArchive};
+    /// # use rawzip::aes::{Strength, Version};
+    /// let data = std::fs::read("assets/aes256.zip")?;
+    /// let archive = ZipArchive::from_slice(&data)?;
+    /// let entry = archive.entries().next_entry()?.unwrap();
+    /// assert_eq!(entry.compression_method(), CompressionMethod::AES);
+    /// assert_eq!(entry.effective_compression_method(), CompressionMethod::DEFLATE);
+    /// assert_eq!(
+    ///     entry.encryption(),
+    ///     Encryption::WinZipAes {
+    ///         version: Some(Version::Ae2),
+    ///         strength: Some(Strength::Aes256),
+    ///     }
+    /// );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn effective_compression_method(&self) -> CompressionMethod {
+        effective_compression_method(self.compression_method, self.extra_fields())
+    }
+
+    /// Returns how the entry is encrypted.
+    ///
+    /// Entries without the encrypted flag are [`Encryption::None`]. Among
+    /// encrypted entries, the strong encryption flag selects
+    /// [`Encryption::StrongEncryption`], the AES method selects
+    /// [`Encryption::WinZipAes`], and the rest are [`Encryption::ZipCrypto`].
+    /// An AES entry without a valid AES extra field reports `None` for the
+    /// parameters it lacks.
+    #[inline]
+    pub fn encryption(&self) -> Encryption {
+        Encryption::of(self.flags, self.compression_method, self.extra_fields())
+    }
+
     /// Returns the file path in its raw form.
     ///
     /// # Safety
@@ -1296,6 +1629,157 @@ println!("Safe path: {}", safe_path.as_ref());
         self.file_name
     }
 
+    /// Returns the file path, preferring the UTF-8 name of an Info-ZIP
+    /// Unicode Path extra field (0x7075).
+    ///
+    /// Older tools write names in a legacy code page and store the UTF-8 name
+    /// in the extra field alongside a CRC-32 of the header name. The extra
+    /// field is only used when its CRC-32 matches the header name and it is
+    /// valid UTF-8, as a mismatch means the name was changed by a tool unaware
+    /// of the field. Otherwise, this is the same as
+    /// [`ZipFileHeaderRecord::file_path`].
+    #[inline]
+    pub fn unicode_file_path(&self) -> ZipFilePath<RawPath<'a>> {
+        let raw = self.file_name.as_bytes();
+        find_unicode_field(
+            ExtraFields::new(self.extra_field),
+            ExtraFieldId::INFO_ZIP_UNICODE_PATH,
+            raw,
+        )
+        .map_or(self.file_name, ZipFilePath::from_bytes)
+    }
+
+    /// Returns the normalized file path, decoding the name according to the
+    /// entry's flags.
+    ///
+    /// - Names flagged as UTF-8 are validated as UTF-8.
+    /// - Otherwise, a matching Info-ZIP Unicode Path extra field is used
+    ///   (see [`ZipFileHeaderRecord::unicode_file_path`]).
+    /// - Otherwise, names that are valid UTF-8 are used as is, as many
+    ///   archivers write UTF-8 without setting the flag.
+    /// - Otherwise, the name is decoded as code page 437.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a name flagged as UTF-8 is not valid UTF-8.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn decoded_file_path(&self) -> Result<ZipFilePath<NormalizedPath<'a>>, Error> {
+        self.decoded_file_path_with(&Cp437)
+    }
+
+    /// Returns the normalized file path like
+    /// [`ZipFileHeaderRecord::decoded_file_path`], but decodes names that are
+    /// not UTF-8 with the given decoder instead of code page 437.
+    #[cfg(feature = "alloc")]
+    pub fn decoded_file_path_with<D>(
+        &self,
+        decoder: &D,
+    ) -> Result<ZipFilePath<NormalizedPath<'a>>, Error>
+    where
+        D: NameDecoder + ?Sized,
+    {
+        match self.decode_file_name(decoder)? {
+            alloc::borrow::Cow::Borrowed(name) => Ok(ZipFilePath::from_str(name)),
+            alloc::borrow::Cow::Owned(name) => Ok(ZipFilePath::from_str(&name).into_owned().into()),
+        }
+    }
+
+    /// Decodes the file name for [`ZipFileHeaderRecord::decoded_file_path_with`]
+    /// without normalizing it.
+    #[cfg(feature = "alloc")]
+    fn decode_file_name<D>(&self, decoder: &D) -> Result<alloc::borrow::Cow<'a, str>, Error>
+    where
+        D: NameDecoder + ?Sized,
+    {
+        let unicode = self.unicode_file_path();
+        let name = match self.flags.is_utf8() {
+            true => self.file_name,
+            false => unicode,
+        };
+
+        match core::str::from_utf8(name.as_bytes()) {
+            Ok(name) => Ok(alloc::borrow::Cow::Borrowed(name)),
+            Err(e) if self.flags.is_utf8() || name != self.file_name => Err(Error::utf8(e)),
+            Err(_) => Ok(decoder.decode(self.file_name.as_bytes())),
+        }
+    }
+
+    /// Validates the target of a symbolic link entry from its decompressed
+    /// data.
+    ///
+    /// The link is resolved from the entry's
+    /// [decoded path](ZipFileHeaderRecord::decoded_file_path). For stored
+    /// entries of slice-backed archives, the data is
+    /// [`ZipSliceEntry::data`].
+    ///
+    /// ```rust
+    /// use rawzip::{ZipArchive, ZipArchiveWriter};
+    ///
+    /// let mut output = Vec::new();
+    /// let mut archive = ZipArchiveWriter::new(&mut output);
+    /// archive.new_symlink("docs/latest", "v2").create()?;
+    /// archive.finish()?;
+    ///
+    /// let archive = ZipArchive::from_slice(&output)?;
+    /// let mut entries = archive.entries();
+    /// let entry = entries.next_entry()?.unwrap();
+    /// assert!(entry.mode().is_symlink());
+    ///
+    /// let data = archive.get_entry(entry.wayfinder())?.data();
+    /// let target = entry.symlink_target(data)?;
+    /// assert_eq!(target.as_str(), "v2");
+    /// assert_eq!(target.resolved().unwrap().as_str(), "docs/v2");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entry is not a symbolic link, its name is not
+    /// valid, or the target is invalid (see [`SymlinkTarget`]).
+    #[cfg(feature = "alloc")]
+    pub fn symlink_target<'t>(&self, data: &'t [u8]) -> Result<SymlinkTarget<'t>, Error> {
+        let link = self.symlink_path()?;
+        SymlinkTarget::new(&link, data)
+    }
+
+    /// Reads and validates the target of a symbolic link entry from a reader
+    /// of its decompressed data, like
+    /// [`ZipFileHeaderRecord::symlink_target`]. At most
+    /// [`MAX_SYMLINK_TARGET_LEN`](crate::path::MAX_SYMLINK_TARGET_LEN) + 1
+    /// bytes are read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entry is not a symbolic link, reading fails,
+    /// or the target is invalid.
+    #[cfg(feature = "std")]
+    pub fn read_symlink_target<R>(&self, reader: R) -> Result<SymlinkTarget<'static>, Error>
+    where
+        R: std::io::Read,
+    {
+        let link = self.symlink_path()?;
+        SymlinkTarget::read(&link, reader)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn symlink_path(&self) -> Result<ZipFilePath<NormalizedPath<'a>>, Error> {
+        if !self.mode().is_symlink() {
+            return Err(Error::from(ErrorKind::InvalidInput {
+                msg: alloc::string::String::from("entry is not a symbolic link"),
+            }));
+        }
+
+        self.decoded_file_path()
+    }
+
+    /// Returns the file name decoded like
+    /// [`ZipFileHeaderRecord::decoded_file_path`], but without normalization.
+    #[cfg(feature = "std")]
+    pub(crate) fn decoded_file_name(&self) -> Result<alloc::borrow::Cow<'a, str>, Error> {
+        self.decode_file_name(&Cp437)
+    }
+
     /// Returns the last modification date and time.
     ///
     /// This method parses the extra field data to locate more accurate timestamps.
@@ -1318,25 +1802,55 @@ println!("Safe path: {}", safe_path.as_ref());
     /// Returns the file mode information extracted from the external file attributes.
     #[inline]
     pub fn mode(&self) -> EntryMode {
-        let creator_version = self.version_made_by >> 8;
-
-        let mut mode = match creator_version {
-            // Unix and macOS
-            CREATOR_UNIX | CREATOR_MACOS => unix_mode_to_file_mode(self.external_file_attrs >> 16),
-            // NTFS, VFAT, FAT
-            CREATOR_NTFS | CREATOR_VFAT | CREATOR_FAT => {
-                msdos_mode_to_file_mode(self.external_file_attrs)
-            }
-            // default to basic permissions
-            _ => 0o644,
-        };
+        entry_mode(
+            self.version_made_by,
+            self.external_file_attrs,
+            self.is_dir(),
+        )
+    }
 
-        // Check if it's a directory by filename ending with '/'
-        if self.is_dir() {
-            mode |= 0o040000; // S_IFDIR
-        }
+    /// Returns the raw external file attributes.
+    ///
+    /// Their interpretation depends on the [`creator_os`](Self::creator_os):
+    /// Unix-like systems store the mode in the upper 16 bits, while MS-DOS
+    /// attributes occupy the low byte.
+    #[inline]
+    pub fn external_attributes(&self) -> u32 {
+        self.external_file_attrs
+    }
+
+    /// Returns the operating system of the tool that created the entry.
+    #[inline]
+    pub fn creator_os(&self) -> CreatorOs {
+        CreatorOs::new((self.version_made_by >> 8) as u8)
+    }
 
-        EntryMode::new(mode)
+    /// Returns the MS-DOS attributes from the low byte of the external file
+    /// attributes.
+    ///
+    /// ```rust
+    /// # use std::io::Write;
+    /// use rawzip::{DosAttributes, ZipArchive, ZipArchiveWriter};
+    ///
+    /// let mut output = Vec::new();
+    /// let mut archive = ZipArchiveWriter::new(&mut output);
+    /// let (mut entry, config) = archive
+    ///     .new_file("desktop.ini")
+    ///     .dos_attributes(DosAttributes::HIDDEN | DosAttributes::SYSTEM)
+    ///     .start()?;
+    /// let (_, descriptor) = config.wrap(&mut entry).finish()?;
+    /// entry.finish(descriptor)?;
+    /// archive.finish()?;
+    ///
+    /// let archive = ZipArchive::from_slice(&output)?;
+    /// let entry = archive.entries().next_entry()?.unwrap();
+    /// assert!(entry.dos_attributes().contains(DosAttributes::HIDDEN));
+    /// assert!(!entry.dos_attributes().contains(DosAttributes::READ_ONLY));
+    /// # Ok::<(), rawzip::Error>(())
+    /// ```
+    #[inline]
+    pub fn dos_attributes(&self) -> DosAttributes {
+        DosAttributes::new(self.external_file_attrs as u8)
     }
 
     /// The declared CRC32 checksum of the uncompressed data.
@@ -1402,6 +1916,45 @@ println!("Safe path: {}", safe_path.as_ref());
     pub fn comment(&self) -> ZipStr<'_> {
         self.file_comment
     }
+
+    /// Returns the file entry's comment, preferring the UTF-8 comment of an
+    /// Info-ZIP Unicode Comment extra field (0x6375).
+    ///
+    /// The same CRC-32 rules as [`ZipFileHeaderRecord::unicode_file_path`]
+    /// apply.
+    #[inline]
+    pub fn unicode_comment(&self) -> ZipStr<'a> {
+        let raw = self.file_comment.as_bytes();
+        find_unicode_field(
+            ExtraFields::new(self.extra_field),
+            ExtraFieldId::INFO_ZIP_UNICODE_COMMENT,
+            raw,
+        )
+        .map_or(self.file_comment, ZipStr::new)
+    }
+}
+
+/// Derives the file mode from the creator OS and the external file attributes.
+fn entry_mode(version_made_by: u16, external_file_attrs: u32, is_dir: bool) -> EntryMode {
+    let creator_version = version_made_by >> 8;
+
+    let mut mode = match creator_version {
+        // Unix and macOS
+        CREATOR_UNIX | CREATOR_MACOS => unix_mode_to_file_mode(external_file_attrs >> 16),
+        // NTFS, VFAT, FAT
+        CREATOR_NTFS | CREATOR_NTFS_SPEC | CREATOR_VFAT | CREATOR_FAT => {
+            msdos_mode_to_file_mode(external_file_attrs)
+        }
+        // default to basic permissions
+        _ => 0o644,
+    };
+
+    // Check if it's a directory by filename ending with '/'
+    if is_dir {
+        mode = (mode & !S_IFMT) | S_IFDIR;
+    }
+
+    EntryMode::new(mode)
 }
 
 /// Contains directions to where the Zip entry's data is located within the Zip archive.
@@ -1411,8 +1964,9 @@ pub struct ZipArchiveEntryWayfinder {
     compressed_size: u64,
     local_header_offset: u64,
     crc: u32,
-    has_data_descriptor: bool,
+    flags: EntryFlags,
     data_descriptor_uses_zip64_sizes: bool,
+    compression_method: CompressionMethod,
 }
 
 impl ZipArchiveEntryWayfinder {
@@ -1433,6 +1987,12 @@ impl ZipArchiveEntryWayfinder {
     pub fn compressed_size_hint(&self) -> u64 {
         self.compressed_size
     }
+
+    /// Equivalent to [`ZipFileHeaderRecord::compression_method`]
+    #[inline]
+    pub fn compression_method(&self) -> CompressionMethod {
+        self.compression_method
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1645,6 +2205,109 @@ mod tests {
         assert_eq!(CompressionMethod::new(13).to_string(), "13 (UNKNOWN)");
     }
 
+    #[test]
+    fn encryption_from_flags_and_extra_fields() {
+        const ENCRYPTED: u16 = 1;
+        const STRONG: u16 = 1 | 1 << 6;
+        let aes = [0x01, 0x99, 7, 0, 1, 0, b'A', b'E', 1, 8, 0];
+        let strong = [0x17, 0x00, 8, 0, 2, 0, 0x10, 0x66, 0, 1, 0, 0];
+
+        let unknown_strength = [0x01, 0x99, 7, 0, 1, 0, b'A', b'E', 9, 8, 0];
+
+        let cases: [(u16, CompressionMethod, &[u8], Encryption, CompressionMethod); 8] = [
+            (
+                0,
+                CompressionMethod::DEFLATE,
+                &[],
+                Encryption::None,
+                CompressionMethod::DEFLATE,
+            ),
+            (
+                ENCRYPTED,
+                CompressionMethod::DEFLATE,
+                &[],
+                Encryption::ZipCrypto,
+                CompressionMethod::DEFLATE,
+            ),
+            (
+                ENCRYPTED,
+                CompressionMethod::AES,
+                &aes,
+                Encryption::WinZipAes {
+                    version: Some(aes::Version::Ae1),
+                    strength: Some(aes::Strength::Aes128),
+                },
+                CompressionMethod::DEFLATE,
+            ),
+            (
+                ENCRYPTED,
+                CompressionMethod::AES,
+                &unknown_strength,
+                Encryption::WinZipAes {
+                    version: Some(aes::Version::Ae1),
+                    strength: None,
+                },
+                CompressionMethod::DEFLATE,
+            ),
+            // The AES method without its extra field is still AES
+            (
+                ENCRYPTED,
+                CompressionMethod::AES,
+                &[],
+                Encryption::WinZipAes {
+                    version: None,
+                    strength: None,
+                },
+                CompressionMethod::AES,
+            ),
+            // The AES field is only honored with the AES method
+            (
+                ENCRYPTED,
+                CompressionMethod::STORE,
+                &aes,
+                Encryption::ZipCrypto,
+                CompressionMethod::STORE,
+            ),
+            (
+                STRONG,
+                CompressionMethod::DEFLATE,
+                &strong,
+                Encryption::StrongEncryption {
+                    algorithm: Some(EncryptionAlgorithm::AES256),
+                },
+                CompressionMethod::DEFLATE,
+            ),
+            (
+                STRONG,
+                CompressionMethod::DEFLATE,
+                &[],
+                Encryption::StrongEncryption { algorithm: None },
+                CompressionMethod::DEFLATE,
+            ),
+        ];
+
+        for (flags, method, extra, encryption, effective) in cases {
+            let fields = ExtraFields::new(extra);
+            assert_eq!(
+                Encryption::of(EntryFlags::new(flags), method, fields),
+                encryption
+            );
+            assert_eq!(effective_compression_method(method, fields), effective);
+        }
+    }
+
+    #[test]
+    fn encryption_algorithm_debug() {
+        assert_eq!(
+            format!("{:?}", EncryptionAlgorithm::AES256),
+            "EncryptionAlgorithm::AES256"
+        );
+        assert_eq!(
+            format!("{:?}", EncryptionAlgorithm::new(0x1234)),
+            "EncryptionAlgorithm(0x1234)"
+        );
+    }
+
     #[test]
     pub fn trunc_comment_zips() {
         let data = [
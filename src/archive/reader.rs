@@ -250,6 +250,61 @@ where
             body_end_offset,
         })
     }
+
+    /// Returns a Read implementation for the APK Signing Block that
+    /// immediately precedes the central directory, if any.
+    ///
+    /// This has the same semantics as [`ZipSliceArchive::apk_signing_block`].
+    /// Read the block into memory and parse it with
+    /// [`apk::SigningBlock::new`] to access its id-value pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rawzip::{ZipArchive, RECOMMENDED_BUFFER_SIZE, apk::SigningBlock};
+    /// use std::io::Read;
+    /// use std::fs::File;
+    ///
+    /// let file = File::open("assets/test.zip")?;
+    /// let mut buffer = vec![0u8; RECOMMENDED_BUFFER_SIZE];
+    /// let archive = ZipArchive::from_file(file, &mut buffer)?;
+    ///
+    /// if let Some(mut reader) = archive.apk_signing_block()? {
+    ///     let offset = reader.position();
+    ///     let mut data = Vec::new();
+    ///     reader.read_to_end(&mut data)?;
+    ///     let block = SigningBlock::new(&data, offset).expect("validated block");
+    ///     for (id, value) in block.pairs() {
+    ///         println!("{:?}: {} bytes", id, value.len());
+    ///     }
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn apk_signing_block(&self) -> Result<Option<RangeReader<&R>>, Error> {
+        let directory_offset = self.eocd.directory_offset();
+        let Some(footer_offset) = directory_offset.checked_sub(apk::FOOTER_LEN as u64) else {
+            return Ok(None);
+        };
+
+        let mut footer = [0u8; apk::FOOTER_LEN];
+        self.reader.read_exact_at(&mut footer, footer_offset)?;
+        let Some(len) = apk::block_len(&footer, directory_offset) else {
+            return Ok(None);
+        };
+
+        // The leading size field must agree with the trailing one
+        let start = directory_offset - len;
+        let mut size = [0u8; 8];
+        self.reader.read_exact_at(&mut size, start)?;
+        if u64::from_le_bytes(size) != len - 8 {
+            return Ok(None);
+        }
+
+        Ok(Some(RangeReader::new(
+            &self.reader,
+            start..directory_offset,
+        )))
+    }
 }
 
 impl<T: ReaderAt> ZipSliceArchive<T> {
@@ -318,6 +373,51 @@ where
         }
     }
 
+    /// Returns a verifying reader of the decompressed data, using the
+    /// decompressor registered for the entry's compression method.
+    ///
+    /// This has the same semantics as [`ZipSliceEntry::decoded_reader`].
+    ///
+    /// ```rust
+    /// use rawzip::{CompressionMethod, Decompressors, ZipArchive, RECOMMENDED_BUFFER_SIZE};
+    /// use std::io::Read;
+    ///
+    /// let file = std::fs::File::open("assets/test.zip")?;
+    /// let mut buffer = vec![0u8; RECOMMENDED_BUFFER_SIZE];
+    /// let archive = ZipArchive::from_file(file, &mut buffer)?;
+    /// let mut entries = archive.entries(&mut buffer);
+    /// let entry = entries.next_entry()?.unwrap();
+    ///
+    /// let mut decompressors = Decompressors::new();
+    /// decompressors.register(CompressionMethod::DEFLATE, |reader, _| {
+    ///     Ok(Box::new(flate2::read::DeflateDecoder::new(reader)))
+    /// });
+    ///
+    /// let mut contents = String::new();
+    /// let entry = archive.get_entry(entry.wayfinder())?;
+    /// entry.decoded_reader(&decompressors)?.read_to_string(&mut contents)?;
+    /// assert_eq!(contents, "This is a test text file.\n");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decoded_reader(
+        &self,
+        decompressors: &Decompressors,
+    ) -> Result<ZipVerifier<Box<dyn Read + 'archive>>, Error> {
+        if self.entry.flags.is_encrypted() {
+            return Err(Error::from(ErrorKind::InvalidInput {
+                msg: String::from("entry is encrypted"),
+            }));
+        }
+
+        let context = DecoderContext::new(
+            self.entry.compression_method,
+            self.entry.flags,
+            self.entry.uncompressed_size,
+        );
+        let reader = decompressors.decoder(&context, Box::new(self.reader()))?;
+        Ok(self.verifying_reader(reader))
+    }
+
     /// Returns a tuple of start and end byte offsets for the compressed data
     /// within the underlying reader.
     ///
@@ -483,7 +583,7 @@ where
 
     /// Reads the trailing [`ZipDataDescriptor`] for this entry, if present.
     pub fn data_descriptor(&self) -> Result<Option<ZipDataDescriptor>, Error> {
-        if !self.entry.has_data_descriptor {
+        if !self.entry.flags.has_data_descriptor() {
             return Ok(None);
         }
 
@@ -0,0 +1,528 @@
+use super::*;
+use crate::reader_at::ReaderAt;
+
+/// Identifies the snapshot encoding.
+const MAGIC: [u8; 4] = *b"RZCD";
+
+/// The current snapshot encoding. Snapshots of any other version are
+/// rejected, as they are cheap to recreate.
+const VERSION: u16 = 1;
+
+/// Magic, version, archive size, EOCD region hash, and trailing data flag.
+const HEADER_SIZE: usize = 4 + 2 + 8 + 4 + 1;
+
+/// Size of an entry excluding its name.
+const ENTRY_SIZE: usize = 2 + 8 + 8 + 8 + 4 + 2 + 2 + 2 + 2 + 2 + 4 + 1;
+
+/// The parsed central directory of an archive that can be saved and reloaded
+/// to skip parsing it again.
+///
+/// A snapshot holds the End of Central Directory values and, for each entry,
+/// the name, the [`ZipArchiveEntryWayfinder`], and the fields most commonly
+/// consulted before reading an entry. Create one with
+/// [`ZipArchive::snapshot`], persist it with [`ZipSnapshot::to_bytes`], and
+/// reload it with [`ZipSnapshot::from_bytes`].
+///
+/// A snapshot is bound to its archive by the archive size and a CRC32 of the
+/// End of Central Directory region, both checked by [`ZipSnapshot::open`].
+/// The size is checked by probing the byte after the archive, so a reader
+/// that was extended, like an archive that was appended to, is rejected.
+/// Bytes that already trailed the archive when the snapshot was taken are
+/// allowed, but their length is not checked. This catches an archive that
+/// was replaced or rewritten, but it is not an integrity check of the
+/// central directory. As with a parsed central
+/// directory, the local header of an entry is only validated when it is
+/// accessed with [`ZipArchive::get_entry`].
+///
+/// # Examples
+///
+/// ```rust
+/// use rawzip::{ZipArchive, ZipSnapshot, RECOMMENDED_BUFFER_SIZE};
+/// use std::fs::File;
+/// use std::io::Read;
+///
+/// let mut buffer = vec![0u8; RECOMMENDED_BUFFER_SIZE];
+/// let archive = ZipArchive::from_file(File::open("assets/test.zip")?, &mut buffer)?;
+/// let saved = archive.snapshot()?.to_bytes();
+///
+/// // Later, possibly in another process
+/// let snapshot = ZipSnapshot::from_bytes(&saved)?;
+/// let archive = snapshot.open(rawzip::FileReader::from(File::open("assets/test.zip")?))?;
+/// let entry = snapshot.entries().find(|x| x.file_path().as_ref() == b"test.txt").unwrap();
+///
+/// // The local header is validated here, as with a parsed central directory
+/// let mut compressed = Vec::new();
+/// archive.get_entry(entry.wayfinder())?.reader().read_to_end(&mut compressed)?;
+/// assert_eq!(compressed.len() as u64, entry.compressed_size_hint());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct ZipSnapshot {
+    eocd: EndOfCentralDirectory,
+    size: u64,
+    eocd_crc: u32,
+
+    /// Whether the reader held bytes past the end of the archive
+    trailing_data: bool,
+    entries: Vec<SnapshotRecord>,
+    names: Vec<u8>,
+}
+
+/// An entry of a snapshot, with its name stored in the shared name buffer.
+#[derive(Debug, Clone)]
+struct SnapshotRecord {
+    name_offset: usize,
+    name_len: u16,
+    wayfinder: ZipArchiveEntryWayfinder,
+    flags: EntryFlags,
+    last_mod_time: u16,
+    last_mod_date: u16,
+    version_made_by: u16,
+    external_file_attrs: u32,
+}
+
+impl<R: ReaderAt> ZipArchive<R> {
+    /// Parses the central directory into a [`ZipSnapshot`].
+    ///
+    /// This iterates every entry of the central directory, so it returns the
+    /// same errors as [`ZipArchive::entries`].
+    pub fn snapshot(&self) -> Result<ZipSnapshot, Error> {
+        let mut entries = Vec::with_capacity(self.entries_hint().min(1 << 16) as usize);
+        let mut names = Vec::new();
+        let mut buffer = vec![0u8; RECOMMENDED_BUFFER_SIZE];
+        let mut iter = self.entries(&mut buffer);
+        while let Some(entry) = iter.next_entry()? {
+            let name = entry.file_path();
+            let name = name.as_bytes();
+            entries.push(SnapshotRecord {
+                name_offset: names.len(),
+                name_len: name.len() as u16,
+                wayfinder: entry.wayfinder(),
+                flags: entry.flags(),
+                last_mod_time: entry.last_mod_time,
+                last_mod_date: entry.last_mod_date,
+                version_made_by: entry.version_made_by,
+                external_file_attrs: entry.external_file_attrs,
+            });
+            names.extend_from_slice(name);
+        }
+
+        let size = self.end_offset();
+        let eocd_crc = eocd_region_crc(&self.reader, &self.eocd, size)?;
+        let trailing_data = has_data_at(&self.reader, size)?;
+        Ok(ZipSnapshot {
+            eocd: self.eocd.clone(),
+            size,
+            eocd_crc,
+            trailing_data,
+            entries,
+            names,
+        })
+    }
+}
+
+/// Computes the CRC32 of the End of Central Directory region: the zip64
+/// records, if any, through the end of the archive comment.
+fn eocd_region_crc<R: ReaderAt>(
+    reader: &R,
+    eocd: &EndOfCentralDirectory,
+    end_offset: u64,
+) -> Result<u32, Error> {
+    let mut crc = Crc32::new();
+    let mut buffer = [0u8; 1024];
+    let mut offset = eocd.head_eocd_offset();
+    while offset < end_offset {
+        let len = ((end_offset - offset) as usize).min(buffer.len());
+        reader.read_exact_at(&mut buffer[..len], offset)?;
+        crc.update(&buffer[..len]);
+        offset += len as u64;
+    }
+    Ok(crc.checksum())
+}
+
+/// Returns true if the reader holds a byte at `offset`.
+fn has_data_at<R: ReaderAt>(reader: &R, offset: u64) -> Result<bool, Error> {
+    let mut byte = [0u8; 1];
+    Ok(reader.read_at(&mut byte, offset)? != 0)
+}
+
+impl ZipSnapshot {
+    /// Returns the number of entries in the snapshot.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the archive has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the size of the archive the snapshot was taken of, the same
+    /// as its [`ZipArchive::end_offset`].
+    pub fn archive_size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns an iterator over the entries in central directory order.
+    pub fn entries(&self) -> ZipSnapshotEntries<'_> {
+        ZipSnapshotEntries {
+            snapshot: self,
+            records: self.entries.iter(),
+        }
+    }
+
+    /// Creates a [`ZipArchive`] from the snapshot without locating the End of
+    /// Central Directory or iterating the central directory.
+    ///
+    /// Only the End of Central Directory region and the byte after the
+    /// archive are read, to verify that the reader holds the archive the
+    /// snapshot was taken of.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::InvalidInput`] if the End of Central Directory
+    /// region does not match the snapshot or the reader continues past an
+    /// archive that had no trailing data, and [`ErrorKind::IO`] if the reader
+    /// is shorter than the archive.
+    pub fn open<R: ReaderAt>(&self, reader: R) -> Result<ZipArchive<R>, Error> {
+        let crc = eocd_region_crc(&reader, &self.eocd, self.size)?;
+        if crc != self.eocd_crc || has_data_at(&reader, self.size)? != self.trailing_data {
+            return Err(Error::from(ErrorKind::InvalidInput {
+                msg: String::from("snapshot does not match the archive"),
+            }));
+        }
+
+        Ok(ZipArchive {
+            reader,
+            eocd: self.eocd.clone(),
+        })
+    }
+
+    /// Encodes the snapshot in a compact, versioned binary format.
+    ///
+    /// The encoding ends with a CRC32 of the preceding bytes, so that a
+    /// damaged snapshot is rejected rather than misread.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(
+            HEADER_SIZE
+                + EndOfCentralDirectory::SNAPSHOT_SIZE
+                + 8
+                + self.entries.len() * ENTRY_SIZE
+                + self.names.len()
+                + 4,
+        );
+
+        out.extend_from_slice(&MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        out.extend_from_slice(&self.size.to_le_bytes());
+        out.extend_from_slice(&self.eocd_crc.to_le_bytes());
+        out.push(u8::from(self.trailing_data));
+        self.eocd.write_snapshot(&mut out);
+        out.extend_from_slice(&(self.entries.len() as u64).to_le_bytes());
+
+        for record in &self.entries {
+            let wayfinder = &record.wayfinder;
+            out.extend_from_slice(&record.name_len.to_le_bytes());
+            out.extend_from_slice(&wayfinder.local_header_offset.to_le_bytes());
+            out.extend_from_slice(&wayfinder.compressed_size.to_le_bytes());
+            out.extend_from_slice(&wayfinder.uncompressed_size.to_le_bytes());
+            out.extend_from_slice(&wayfinder.crc.to_le_bytes());
+            out.extend_from_slice(&wayfinder.compression_method.as_u16().to_le_bytes());
+            out.extend_from_slice(&record.flags.bits().to_le_bytes());
+            out.extend_from_slice(&record.last_mod_time.to_le_bytes());
+            out.extend_from_slice(&record.last_mod_date.to_le_bytes());
+            out.extend_from_slice(&record.version_made_by.to_le_bytes());
+            out.extend_from_slice(&record.external_file_attrs.to_le_bytes());
+            out.push(u8::from(wayfinder.data_descriptor_uses_zip64_sizes));
+            out.extend_from_slice(&self.names[record.name_range()]);
+        }
+
+        let crc = crate::crc32(&out);
+        out.extend_from_slice(&crc.to_le_bytes());
+        out
+    }
+
+    /// Decodes a snapshot encoded by [`ZipSnapshot::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::InvalidInput`] if the data is not a snapshot, is
+    /// of another version, or is damaged.
+    pub fn from_bytes(data: &[u8]) -> Result<ZipSnapshot, Error> {
+        let invalid = |msg: &str| {
+            Error::from(ErrorKind::InvalidInput {
+                msg: format!("invalid snapshot: {msg}"),
+            })
+        };
+
+        if data.len() < HEADER_SIZE + 4 || data[..4] != MAGIC {
+            return Err(invalid("missing magic"));
+        }
+
+        let version = le_u16(&data[4..6]);
+        if version != VERSION {
+            return Err(invalid(&format!("unsupported version {version}")));
+        }
+
+        let (data, crc) = data.split_at(data.len() - 4);
+        if crate::crc32(data) != le_u32(crc) {
+            return Err(invalid("checksum mismatch"));
+        }
+
+        let mut reader = SnapshotReader {
+            data: &data[6..],
+            invalid: || invalid("truncated"),
+        };
+        let size = le_u64(reader.take(8)?);
+        let eocd_crc = le_u32(reader.take(4)?);
+        let trailing_data = reader.take(1)?[0] != 0;
+        let eocd = reader.take(EndOfCentralDirectory::SNAPSHOT_SIZE)?;
+        let eocd = EndOfCentralDirectory::read_snapshot(eocd.try_into().unwrap())?;
+        let count = le_u64(reader.take(8)?);
+
+        // Every entry takes at least ENTRY_SIZE bytes, which bounds the
+        // allocation by the input
+        if count > (reader.data.len() / ENTRY_SIZE) as u64 {
+            return Err(invalid("truncated"));
+        }
+
+        let mut entries = Vec::with_capacity(count as usize);
+        let mut names = Vec::new();
+        for _ in 0..count {
+            let fixed = reader.take(ENTRY_SIZE)?;
+            let name_len = le_u16(&fixed[0..2]);
+            let flags = EntryFlags::new(le_u16(&fixed[32..34]));
+            let wayfinder = ZipArchiveEntryWayfinder {
+                local_header_offset: le_u64(&fixed[2..10]),
+                compressed_size: le_u64(&fixed[10..18]),
+                uncompressed_size: le_u64(&fixed[18..26]),
+                crc: le_u32(&fixed[26..30]),
+                compression_method: CompressionMethod::from(le_u16(&fixed[30..32])),
+                flags,
+                data_descriptor_uses_zip64_sizes: fixed[44] != 0,
+            };
+
+            entries.push(SnapshotRecord {
+                name_offset: names.len(),
+                name_len,
+                wayfinder,
+                flags,
+                last_mod_time: le_u16(&fixed[34..36]),
+                last_mod_date: le_u16(&fixed[36..38]),
+                version_made_by: le_u16(&fixed[38..40]),
+                external_file_attrs: le_u32(&fixed[40..44]),
+            });
+            names.extend_from_slice(reader.take(usize::from(name_len))?);
+        }
+
+        if !reader.data.is_empty() {
+            return Err(invalid("trailing data"));
+        }
+
+        Ok(ZipSnapshot {
+            eocd,
+            size,
+            eocd_crc,
+            trailing_data,
+            entries,
+            names,
+        })
+    }
+}
+
+impl SnapshotRecord {
+    fn name_range(&self) -> core::ops::Range<usize> {
+        self.name_offset..self.name_offset + usize::from(self.name_len)
+    }
+}
+
+struct SnapshotReader<'a, F> {
+    data: &'a [u8],
+    invalid: F,
+}
+
+impl<'a, F: Fn() -> Error> SnapshotReader<'a, F> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.data.len() < len {
+            return Err((self.invalid)());
+        }
+
+        let (result, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(result)
+    }
+}
+
+/// An iterator over the entries of a [`ZipSnapshot`].
+#[derive(Debug, Clone)]
+pub struct ZipSnapshotEntries<'a> {
+    snapshot: &'a ZipSnapshot,
+    records: core::slice::Iter<'a, SnapshotRecord>,
+}
+
+impl<'a> Iterator for ZipSnapshotEntries<'a> {
+    type Item = ZipSnapshotEntry<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = self.records.next()?;
+        Some(ZipSnapshotEntry {
+            record,
+            name: &self.snapshot.names[record.name_range()],
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.records.size_hint()
+    }
+}
+
+impl ExactSizeIterator for ZipSnapshotEntries<'_> {}
+
+/// An entry of a [`ZipSnapshot`].
+///
+/// The accessors mirror those of [`ZipFileHeaderRecord`] for the fields
+/// kept in the snapshot.
+#[derive(Debug, Clone, Copy)]
+pub struct ZipSnapshotEntry<'a> {
+    record: &'a SnapshotRecord,
+    name: &'a [u8],
+}
+
+impl<'a> ZipSnapshotEntry<'a> {
+    /// Equivalent to [`ZipFileHeaderRecord::file_path`]
+    #[inline]
+    pub fn file_path(&self) -> ZipFilePath<RawPath<'a>> {
+        ZipFilePath::from_bytes(self.name)
+    }
+
+    /// Equivalent to [`ZipFileHeaderRecord::is_dir`]
+    #[inline]
+    pub fn is_dir(&self) -> bool {
+        self.file_path().is_dir()
+    }
+
+    /// Equivalent to [`ZipFileHeaderRecord::wayfinder`]
+    #[inline]
+    pub fn wayfinder(&self) -> ZipArchiveEntryWayfinder {
+        self.record.wayfinder
+    }
+
+    /// Equivalent to [`ZipFileHeaderRecord::flags`]
+    #[inline]
+    pub fn flags(&self) -> EntryFlags {
+        self.record.flags
+    }
+
+    /// Equivalent to [`ZipFileHeaderRecord::compression_method`]
+    #[inline]
+    pub fn compression_method(&self) -> CompressionMethod {
+        self.record.wayfinder.compression_method
+    }
+
+    /// Equivalent to [`ZipFileHeaderRecord::crc32`]
+    #[inline]
+    pub fn crc32(&self) -> u32 {
+        self.record.wayfinder.crc
+    }
+
+    /// Equivalent to [`ZipFileHeaderRecord::uncompressed_size_hint`]
+    #[inline]
+    pub fn uncompressed_size_hint(&self) -> u64 {
+        self.record.wayfinder.uncompressed_size
+    }
+
+    /// Equivalent to [`ZipFileHeaderRecord::compressed_size_hint`]
+    #[inline]
+    pub fn compressed_size_hint(&self) -> u64 {
+        self.record.wayfinder.compressed_size
+    }
+
+    /// Equivalent to [`ZipFileHeaderRecord::local_header_offset`]
+    #[inline]
+    pub fn local_header_offset(&self) -> u64 {
+        self.record.wayfinder.local_header_offset
+    }
+
+    /// Equivalent to [`ZipFileHeaderRecord::last_modified_dos`]
+    ///
+    /// Extra fields are not kept in the snapshot, so the higher-resolution
+    /// timestamp of [`ZipFileHeaderRecord::last_modified`] is unavailable.
+    #[inline]
+    pub fn last_modified_dos(&self) -> DosDateTime {
+        DosDateTime::new(self.record.last_mod_time, self.record.last_mod_date)
+    }
+
+    /// Equivalent to [`ZipFileHeaderRecord::mode`]
+    #[inline]
+    pub fn mode(&self) -> EntryMode {
+        entry_mode(
+            self.record.version_made_by,
+            self.record.external_file_attrs,
+            self.is_dir(),
+        )
+    }
+
+    /// Equivalent to [`ZipFileHeaderRecord::external_attributes`]
+    #[inline]
+    pub fn external_attributes(&self) -> u32 {
+        self.record.external_file_attrs
+    }
+
+    /// Equivalent to [`ZipFileHeaderRecord::creator_os`]
+    #[inline]
+    pub fn creator_os(&self) -> CreatorOs {
+        CreatorOs::new((self.record.version_made_by >> 8) as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> ZipSnapshot {
+        let data = std::fs::read("assets/test.zip").unwrap();
+        let archive = ZipArchive::from_slice(data).unwrap().into_reader();
+        archive.snapshot().unwrap()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let snapshot = snapshot();
+        let bytes = snapshot.to_bytes();
+        let reloaded = ZipSnapshot::from_bytes(&bytes).unwrap();
+        assert_eq!(reloaded.to_bytes(), bytes);
+        assert_eq!(reloaded.len(), 2);
+        assert_eq!(reloaded.archive_size(), snapshot.archive_size());
+    }
+
+    #[test]
+    fn test_rejects_damage() {
+        let bytes = snapshot().to_bytes();
+
+        for i in [0, 4, 20, bytes.len() - 10, bytes.len() - 1] {
+            let mut damaged = bytes.clone();
+            damaged[i] ^= 0x01;
+            let err = ZipSnapshot::from_bytes(&damaged).unwrap_err();
+            assert!(matches!(err.kind(), ErrorKind::InvalidInput { .. }));
+        }
+
+        for len in [0, 10, bytes.len() - 1] {
+            let err = ZipSnapshot::from_bytes(&bytes[..len]).unwrap_err();
+            assert!(matches!(err.kind(), ErrorKind::InvalidInput { .. }));
+        }
+    }
+
+    #[test]
+    fn test_rejects_other_version() {
+        let mut bytes = snapshot().to_bytes();
+        bytes[4..6].copy_from_slice(&2u16.to_le_bytes());
+        let len = bytes.len();
+        let crc = crate::crc32(&bytes[..len - 4]);
+        bytes[len - 4..].copy_from_slice(&crc.to_le_bytes());
+
+        let err = ZipSnapshot::from_bytes(&bytes).unwrap_err();
+        assert!(err.to_string().contains("unsupported version 2"), "{err}");
+    }
+}
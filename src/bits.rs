@@ -0,0 +1,253 @@
+//! Bit-level input and the sliding window shared by the built-in decoders.
+
+use std::io::{self, Read};
+
+const INPUT_BUFFER_SIZE: usize = 8 * 1024;
+
+/// A buffered reader of bits packed starting from the least significant bit
+/// of each byte, the order used by deflate and the PKWARE methods before it.
+#[derive(Debug)]
+pub(crate) struct BitReader<R> {
+    pub(crate) reader: R,
+    buffer: Box<[u8]>,
+    start: usize,
+    end: usize,
+    bits: u32,
+    bit_count: u8,
+    eof: bool,
+}
+
+impl<R: Read> BitReader<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        BitReader {
+            reader,
+            buffer: vec![0u8; INPUT_BUFFER_SIZE].into_boxed_slice(),
+            start: 0,
+            end: 0,
+            bits: 0,
+            bit_count: 0,
+            eof: false,
+        }
+    }
+
+    /// Buffers at least `count` bits, returning false if the input ends
+    /// first.
+    fn fill(&mut self, count: u8) -> io::Result<bool> {
+        while self.bit_count < count {
+            if self.start == self.end {
+                if self.eof {
+                    return Ok(false);
+                }
+
+                match self.reader.read(&mut self.buffer) {
+                    Ok(0) => {
+                        self.eof = true;
+                        continue;
+                    }
+                    Ok(read) => {
+                        self.start = 0;
+                        self.end = read;
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+
+            self.bits |= u32::from(self.buffer[self.start]) << self.bit_count;
+            self.start += 1;
+            self.bit_count += 8;
+        }
+
+        Ok(true)
+    }
+
+    /// Reads `count` bits for counts up to 16, or none if the input ends
+    /// first.
+    pub(crate) fn try_bits(&mut self, count: u8) -> io::Result<Option<u32>> {
+        if !self.fill(count)? {
+            return Ok(None);
+        }
+
+        let value = self.bits & ((1 << count) - 1);
+        self.bits >>= count;
+        self.bit_count -= count;
+        Ok(Some(value))
+    }
+
+    /// Reads `count` bits for counts up to 16.
+    pub(crate) fn bits(&mut self, count: u8) -> io::Result<u32> {
+        match self.try_bits(count)? {
+            Some(value) => Ok(value),
+            None => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "compressed stream ended unexpectedly",
+            )),
+        }
+    }
+
+    /// Decodes a canonical Huffman code a bit at a time, returning the index
+    /// of its symbol among the symbols ordered by code, or none if the bits
+    /// match no code.
+    ///
+    /// `counts` holds the number of codes of each bit length, starting from
+    /// zero. The PKWARE methods store codes with every bit inverted.
+    pub(crate) fn canonical_code(
+        &mut self,
+        counts: &[u16],
+        inverted: bool,
+    ) -> io::Result<Option<usize>> {
+        let invert = u32::from(inverted);
+        let mut code: i32 = 0;
+        let mut first: i32 = 0;
+        let mut index: i32 = 0;
+        for &count in &counts[1..] {
+            code |= (self.bits(1)? ^ invert) as i32;
+            let count = i32::from(count);
+            if code - first < count {
+                return Ok(Some((index + code - first) as usize));
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+
+        Ok(None)
+    }
+
+    /// Discards the bits remaining in the current byte.
+    #[cfg_attr(not(feature = "deflate64"), allow(dead_code))]
+    pub(crate) fn align(&mut self) {
+        let partial = self.bit_count % 8;
+        self.bits >>= partial;
+        self.bit_count -= partial;
+    }
+}
+
+/// The sliding window of an LZ77 decoder, which doubles as the buffer of
+/// decompressed bytes that have not been read yet.
+///
+/// Matches are queued and copied as output is requested, so that a long
+/// match never overwrites bytes that have not been read. Matches may reach
+/// before the start of the output, which reads as zeros.
+#[derive(Debug)]
+pub(crate) struct Window {
+    data: Box<[u8]>,
+
+    /// Where the next decompressed byte is written
+    position: usize,
+
+    /// Count of decompressed bytes that have not been read yet
+    pending: usize,
+
+    /// Count of bytes that matches may refer to, up to the window size
+    history: usize,
+
+    /// Count of bytes left to output before the stream is finished
+    remaining: u64,
+
+    /// The distance and the count of bytes left of the queued match
+    queued: (usize, usize),
+}
+
+impl Window {
+    /// Creates a window of `size` bytes, which must be a power of two, that
+    /// stops accepting output after `limit` bytes.
+    pub(crate) fn new(size: usize, limit: u64) -> Self {
+        debug_assert!(size.is_power_of_two());
+        Window {
+            data: vec![0u8; size].into_boxed_slice(),
+            position: 0,
+            pending: 0,
+            history: 0,
+            remaining: limit,
+            queued: (0, 0),
+        }
+    }
+
+    #[inline]
+    fn mask(&self) -> usize {
+        self.data.len() - 1
+    }
+
+    /// Count of bytes that matches may refer to.
+    #[cfg_attr(
+        not(any(feature = "deflate64", feature = "dcl-implode")),
+        allow(dead_code)
+    )]
+    pub(crate) fn history(&self) -> usize {
+        self.history
+    }
+
+    /// Returns true once the output limit is reached.
+    #[cfg_attr(not(feature = "legacy-methods"), allow(dead_code))]
+    pub(crate) fn is_finished(&self) -> bool {
+        self.remaining == 0
+    }
+
+    /// Outputs a byte. Output past the limit is dropped.
+    #[inline]
+    pub(crate) fn push(&mut self, byte: u8) {
+        if self.remaining == 0 {
+            return;
+        }
+
+        self.data[self.position] = byte;
+        self.position = (self.position + 1) & self.mask();
+        self.pending += 1;
+        self.history = (self.history + 1).min(self.data.len());
+        self.remaining -= 1;
+    }
+
+    /// Queues a match of `length` bytes starting `distance` bytes back, for
+    /// distances up to the window size.
+    pub(crate) fn copy(&mut self, distance: usize, length: usize) {
+        let length = u64::try_from(length).map_or(self.remaining, |x| x.min(self.remaining));
+        self.queued = (distance, length as usize);
+    }
+
+    /// Continues the queued match until `target` bytes are pending, returning
+    /// true if more must be decoded to reach the target.
+    fn fill(&mut self, target: usize) -> bool {
+        let (distance, length) = self.queued;
+        let count = length.min(target.saturating_sub(self.pending));
+        for _ in 0..count {
+            let byte = self.data[self.position.wrapping_sub(distance) & self.mask()];
+            self.push(byte);
+        }
+
+        self.queued.1 -= count;
+        self.queued.1 == 0 && self.pending < target
+    }
+
+    fn drain(&mut self, buf: &mut [u8]) -> usize {
+        let read = self.pending.min(buf.len());
+        let start = self.position.wrapping_sub(self.pending) & self.mask();
+        let head = read.min(self.data.len() - start);
+        buf[..head].copy_from_slice(&self.data[start..start + head]);
+        buf[head..read].copy_from_slice(&self.data[..read - head]);
+        self.pending -= read;
+        read
+    }
+}
+
+/// A decoder that outputs into a [`Window`] a literal or a match at a time.
+pub(crate) trait WindowDecoder {
+    fn window(&mut self) -> &mut Window;
+
+    /// Decodes the next step of the stream, returning false once the stream
+    /// has ended.
+    ///
+    /// A step may output at most one byte, plus a queued match.
+    fn step(&mut self) -> io::Result<bool>;
+
+    /// Implements [`Read::read`] on top of [`step`](Self::step).
+    fn read_window(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let target = buf.len().min(self.window().data.len());
+        while self.window().fill(target) && self.step()? {}
+        Ok(self.window().drain(buf))
+    }
+}
+
+pub(crate) fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
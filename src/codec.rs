@@ -0,0 +1,376 @@
+use crate::{CompressionMethod, DataDescriptorOutput, EntryFlags, Error, ErrorKind};
+use std::io::{self, Read, Write};
+
+type DecoderFactory = Box<
+    dyn for<'a> Fn(Box<dyn Read + 'a>, &DecoderContext) -> io::Result<Box<dyn Read + 'a>>
+        + Send
+        + Sync,
+>;
+
+/// The entry being decompressed, handed to the factories of a
+/// [`Decompressors`] registry alongside its compressed data.
+///
+/// Some methods need more than the compressed stream: Reduce and Implode
+/// lack an end marker, so their decoders take the uncompressed size, and
+/// Implode's variant is picked by the general purpose flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecoderContext {
+    compression_method: CompressionMethod,
+    flags: EntryFlags,
+    uncompressed_size: u64,
+}
+
+impl DecoderContext {
+    /// Describes an entry by its compression method, general purpose flags,
+    /// and uncompressed size.
+    pub fn new(
+        compression_method: CompressionMethod,
+        flags: EntryFlags,
+        uncompressed_size: u64,
+    ) -> Self {
+        DecoderContext {
+            compression_method,
+            flags,
+            uncompressed_size,
+        }
+    }
+
+    /// The compression method of the entry.
+    pub fn compression_method(&self) -> CompressionMethod {
+        self.compression_method
+    }
+
+    /// The general purpose flags of the entry.
+    pub fn flags(&self) -> EntryFlags {
+        self.flags
+    }
+
+    /// The uncompressed size of the entry, as recorded in the central
+    /// directory.
+    pub fn uncompressed_size(&self) -> u64 {
+        self.uncompressed_size
+    }
+}
+
+/// A registry of decompressors keyed by [`CompressionMethod`].
+///
+/// rawzip does not depend on any compression library. Instead, decompressors
+/// for the methods an application supports are registered here, and
+/// [`ZipEntry::decoded_reader`](crate::ZipEntry::decoded_reader) picks the one
+/// matching an entry. This is similar to Go's `zip.RegisterDecompressor`,
+/// except that the registry is an ordinary value instead of global state.
+///
+/// A new registry supports [`CompressionMethod::STORE`].
+///
+/// ```rust
+/// use rawzip::{CompressionMethod, Decompressors};
+///
+/// let mut decompressors = Decompressors::new();
+/// decompressors
+///     .register(CompressionMethod::DEFLATE, |reader, _| {
+///         Ok(Box::new(flate2::read::DeflateDecoder::new(reader)))
+///     })
+///     .register(CompressionMethod::ZSTD, |reader, _| {
+///         Ok(Box::new(zstd::Decoder::new(reader)?))
+///     });
+///
+/// assert!(decompressors.contains(CompressionMethod::STORE));
+/// assert!(!decompressors.contains(CompressionMethod::BZIP2));
+/// ```
+pub struct Decompressors {
+    factories: Vec<(CompressionMethod, DecoderFactory)>,
+}
+
+impl Decompressors {
+    /// Creates a registry that supports only [`CompressionMethod::STORE`].
+    pub fn new() -> Self {
+        let mut result = Decompressors {
+            factories: Vec::new(),
+        };
+        result.register(CompressionMethod::STORE, |reader, _| Ok(reader));
+        result
+    }
+
+    /// Registers the decompressor for a compression method, replacing any
+    /// previously registered for it.
+    ///
+    /// The factory receives the compressed data of an entry and its
+    /// [`DecoderContext`], and returns a reader of the decompressed data.
+    ///
+    /// ```rust
+    /// use rawzip::{CompressionMethod, Decompressors};
+    /// use std::io::Read;
+    ///
+    /// let mut decompressors = Decompressors::new();
+    /// decompressors.register(CompressionMethod::STORE, |reader, entry| {
+    ///     Ok(Box::new(reader.take(entry.uncompressed_size())))
+    /// });
+    /// ```
+    pub fn register<F>(&mut self, method: CompressionMethod, factory: F) -> &mut Self
+    where
+        F: for<'a> Fn(Box<dyn Read + 'a>, &DecoderContext) -> io::Result<Box<dyn Read + 'a>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let factory: DecoderFactory = Box::new(factory);
+        match self.factories.iter_mut().find(|(m, _)| *m == method) {
+            Some((_, existing)) => *existing = factory,
+            None => self.factories.push((method, factory)),
+        }
+        self
+    }
+
+    /// Removes the decompressor for a compression method.
+    pub fn unregister(&mut self, method: CompressionMethod) -> &mut Self {
+        self.factories.retain(|(m, _)| *m != method);
+        self
+    }
+
+    /// Returns true if a decompressor is registered for the method.
+    pub fn contains(&self, method: CompressionMethod) -> bool {
+        self.factories.iter().any(|(m, _)| *m == method)
+    }
+
+    /// Wraps compressed data with the decompressor registered for the
+    /// entry's compression method.
+    ///
+    /// The returned reader does not verify the decompressed data. Prefer
+    /// [`ZipEntry::decoded_reader`](crate::ZipEntry::decoded_reader), which
+    /// does.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::UnsupportedCompressionMethod`] if no decompressor
+    /// is registered for the method, or the error of the decompressor
+    /// factory.
+    pub fn decoder<'a>(
+        &self,
+        entry: &DecoderContext,
+        reader: Box<dyn Read + 'a>,
+    ) -> Result<Box<dyn Read + 'a>, Error> {
+        let method = entry.compression_method;
+        let Some((_, factory)) = self.factories.iter().find(|(m, _)| *m == method) else {
+            return Err(Error::from(ErrorKind::UnsupportedCompressionMethod(method)));
+        };
+
+        Ok(factory(reader, entry)?)
+    }
+}
+
+impl Default for Decompressors {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::fmt::Debug for Decompressors {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_set()
+            .entries(self.factories.iter().map(|(method, _)| method))
+            .finish()
+    }
+}
+
+type EncoderFactory = Box<dyn for<'a> Fn(EncoderSink<'a>) -> io::Result<Encoder<'a>> + Send + Sync>;
+
+/// A registry of compressors keyed by [`CompressionMethod`].
+///
+/// The write-side counterpart of [`Decompressors`]. Used with
+/// [`ZipFileBuilder::start_with`](crate::ZipFileBuilder::start_with), the
+/// registry guarantees that an entry's data is compressed with the method
+/// recorded in its headers.
+///
+/// A new registry supports [`CompressionMethod::STORE`].
+///
+/// ```rust
+/// use rawzip::{CompressionMethod, Compressors, Encoder};
+///
+/// let mut compressors = Compressors::new();
+/// compressors
+///     .register(CompressionMethod::DEFLATE, |sink| {
+///         let encoder = flate2::write::DeflateEncoder::new(sink, flate2::Compression::default());
+///         Ok(Encoder::new(encoder, |encoder| encoder.finish()))
+///     })
+///     .register(CompressionMethod::ZSTD, |sink| {
+///         let encoder = zstd::Encoder::new(sink, 3)?;
+///         Ok(Encoder::new(encoder, |encoder| encoder.finish()))
+///     });
+///
+/// assert!(compressors.contains(CompressionMethod::ZSTD));
+/// ```
+pub struct Compressors {
+    factories: Vec<(CompressionMethod, EncoderFactory)>,
+}
+
+impl Compressors {
+    /// Creates a registry that supports only [`CompressionMethod::STORE`].
+    pub fn new() -> Self {
+        let mut result = Compressors {
+            factories: Vec::new(),
+        };
+        result.register(CompressionMethod::STORE, |sink| Ok(Encoder::new(sink, Ok)));
+        result
+    }
+
+    /// Registers the compressor for a compression method, replacing any
+    /// previously registered for it.
+    ///
+    /// The factory receives the sink for an entry's compressed data and
+    /// returns an [`Encoder`] that writes to it.
+    pub fn register<F>(&mut self, method: CompressionMethod, factory: F) -> &mut Self
+    where
+        F: for<'a> Fn(EncoderSink<'a>) -> io::Result<Encoder<'a>> + Send + Sync + 'static,
+    {
+        let factory: EncoderFactory = Box::new(factory);
+        match self.factories.iter_mut().find(|(m, _)| *m == method) {
+            Some((_, existing)) => *existing = factory,
+            None => self.factories.push((method, factory)),
+        }
+        self
+    }
+
+    /// Removes the compressor for a compression method.
+    pub fn unregister(&mut self, method: CompressionMethod) -> &mut Self {
+        self.factories.retain(|(m, _)| *m != method);
+        self
+    }
+
+    /// Returns true if a compressor is registered for the method.
+    pub fn contains(&self, method: CompressionMethod) -> bool {
+        self.factories.iter().any(|(m, _)| *m == method)
+    }
+
+    pub(crate) fn factory(&self, method: CompressionMethod) -> Result<&EncoderFactory, Error> {
+        match self.factories.iter().find(|(m, _)| *m == method) {
+            Some((_, factory)) => Ok(factory),
+            None => Err(Error::from(ErrorKind::UnsupportedCompressionMethod(method))),
+        }
+    }
+}
+
+impl Default for Compressors {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::fmt::Debug for Compressors {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_set()
+            .entries(self.factories.iter().map(|(method, _)| method))
+            .finish()
+    }
+}
+
+/// The destination of an entry's compressed data, handed to the factories of
+/// a [`Compressors`] registry.
+///
+/// An [`Encoder`] must return the sink once its stream is finished.
+pub struct EncoderSink<'a>(Box<dyn EntrySink + 'a>);
+
+impl<'a> EncoderSink<'a> {
+    pub(crate) fn new<S>(sink: S) -> Self
+    where
+        S: EntrySink + 'a,
+    {
+        EncoderSink(Box::new(sink))
+    }
+
+    pub(crate) fn finish(self, output: DataDescriptorOutput) -> Result<u64, Error> {
+        self.0.finish(output)
+    }
+}
+
+impl Write for EncoderSink<'_> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl core::fmt::Debug for EncoderSink<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("EncoderSink").finish_non_exhaustive()
+    }
+}
+
+/// An entry writer that can be finished through a type-erased sink.
+pub(crate) trait EntrySink: Write {
+    fn finish(self: Box<Self>, output: DataDescriptorOutput) -> Result<u64, Error>;
+}
+
+/// A compressing writer paired with the function that finishes its stream.
+///
+/// Finishing writes the epilogue of the compressed stream (think deflate's
+/// final block or zstd's frame footer) and returns the [`EncoderSink`].
+pub struct Encoder<'a>(Box<dyn FinishEncoder<'a> + 'a>);
+
+impl<'a> Encoder<'a> {
+    /// Pairs an encoder with the function that finishes it.
+    pub fn new<E, F>(encoder: E, finish: F) -> Self
+    where
+        E: Write + 'a,
+        F: FnOnce(E) -> io::Result<EncoderSink<'a>> + 'a,
+    {
+        Encoder(Box::new(Finishing { encoder, finish }))
+    }
+
+    pub(crate) fn finish(self) -> io::Result<EncoderSink<'a>> {
+        self.0.finish()
+    }
+}
+
+impl Write for Encoder<'_> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl core::fmt::Debug for Encoder<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Encoder").finish_non_exhaustive()
+    }
+}
+
+trait FinishEncoder<'a>: Write {
+    fn finish(self: Box<Self>) -> io::Result<EncoderSink<'a>>;
+}
+
+struct Finishing<E, F> {
+    encoder: E,
+    finish: F,
+}
+
+impl<'a, E, F> FinishEncoder<'a> for Finishing<E, F>
+where
+    E: Write,
+    F: FnOnce(E) -> io::Result<EncoderSink<'a>>,
+{
+    fn finish(self: Box<Self>) -> io::Result<EncoderSink<'a>> {
+        (self.finish)(self.encoder)
+    }
+}
+
+impl<E: Write, F> Write for Finishing<E, F> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.encoder.write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.encoder.flush()
+    }
+}
@@ -0,0 +1,286 @@
+//! A streaming decoder for the PKWARE Data Compression Library's implode.
+//!
+//! Not to be confused with the Implode of early PKZIP releases, the DCL
+//! format is stored as
+//! [`CompressionMethod::DCL_IMPLODE`](crate::CompressionMethod::DCL_IMPLODE)
+//! and lives on in installers and game assets. A two byte header selects
+//! whether literals are stored as is (binary mode) or Huffman coded (ASCII
+//! mode) and whether the dictionary is 1, 2, or 4 KiB. The rest is LZ77 with
+//! fixed Huffman codes, ending with an end code.
+//!
+//! The decoder follows Mark Adler's "blast" from zlib's contrib directory.
+
+use crate::bits::{BitReader, Window, WindowDecoder, invalid_data};
+use std::io::{self, Read};
+
+const WINDOW_SIZE: usize = 1 << 12;
+const MAX_BITS: usize = 13;
+
+/// The length that marks the end of the stream.
+const END_LENGTH: usize = 519;
+
+const LENGTH_BASE: [u16; 16] = [3, 2, 4, 5, 6, 7, 8, 9, 10, 12, 16, 24, 40, 72, 136, 264];
+const LENGTH_EXTRA: [u8; 16] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8];
+
+// The bit lengths of the fixed codes, where each byte is a bit length in the
+// low nibble and a repeat count less one in the high nibble.
+const LITERAL_LENGTHS: [u8; 98] = [
+    11, 124, 8, 7, 28, 7, 188, 13, 76, 4, 10, 8, 12, 10, 12, 10, 8, 23, 8, 9, 7, 6, 7, 8, 7, 6, 55,
+    8, 23, 24, 12, 11, 7, 9, 11, 12, 6, 7, 22, 5, 7, 24, 6, 11, 9, 6, 7, 22, 7, 11, 38, 7, 9, 8,
+    25, 11, 8, 11, 9, 12, 8, 12, 5, 38, 5, 38, 5, 11, 7, 5, 6, 21, 6, 10, 53, 8, 7, 24, 10, 27, 44,
+    253, 253, 253, 252, 252, 252, 13, 12, 45, 12, 45, 12, 61, 12, 45, 44, 173,
+];
+const LENGTH_LENGTHS: [u8; 6] = [2, 35, 36, 53, 38, 23];
+const DISTANCE_LENGTHS: [u8; 7] = [2, 20, 53, 230, 247, 151, 248];
+
+const LITERALS: Huffman = Huffman::new(&LITERAL_LENGTHS);
+const LENGTHS: Huffman = Huffman::new(&LENGTH_LENGTHS);
+const DISTANCES: Huffman = Huffman::new(&DISTANCE_LENGTHS);
+
+/// A [`Read`] adapter that decompresses a PKWARE DCL implode stream.
+///
+/// The stream carries its own end code, so the decoder can be registered
+/// with [`Decompressors`](crate::Decompressors). The output is unverified, so
+/// check it against the entry's CRC32 with a verifying reader.
+///
+/// # Examples
+///
+/// ```rust
+/// use rawzip::{CompressionMethod, Decompressors, dcl_implode};
+/// use std::io::Read;
+///
+/// // The test vector of zlib's blast
+/// let data = [0x00, 0x04, 0x82, 0x24, 0x25, 0x8f, 0x80, 0x7f];
+/// let mut output = String::new();
+/// dcl_implode::Decoder::new(&data[..]).read_to_string(&mut output)?;
+/// assert_eq!(output, "AIAIAIAIAIAIA");
+///
+/// let mut decompressors = Decompressors::new();
+/// decompressors.register(CompressionMethod::DCL_IMPLODE, |reader, _| {
+///     Ok(Box::new(dcl_implode::Decoder::new(reader)))
+/// });
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Decoder<R> {
+    input: BitReader<R>,
+    window: Window,
+    coded_literals: bool,
+    dictionary_bits: u8,
+    state: State,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Header,
+    Codes,
+    Done,
+}
+
+impl<R: Read> Decoder<R> {
+    /// Wraps a reader of DCL implode compressed data.
+    pub fn new(reader: R) -> Self {
+        Decoder {
+            input: BitReader::new(reader),
+            window: Window::new(WINDOW_SIZE, u64::MAX),
+            coded_literals: false,
+            dictionary_bits: 0,
+            state: State::Header,
+        }
+    }
+
+    /// Returns a reference to the wrapped reader.
+    pub fn get_ref(&self) -> &R {
+        &self.input.reader
+    }
+
+    /// Consumes the decoder, returning the wrapped reader.
+    ///
+    /// The decoder buffers input, so the wrapped reader may be positioned
+    /// past the end of the compressed stream.
+    pub fn into_inner(self) -> R {
+        self.input.reader
+    }
+
+    fn header(&mut self) -> io::Result<()> {
+        self.coded_literals = match self.input.bits(8)? {
+            0 => false,
+            1 => true,
+            _ => return Err(invalid_data("invalid dcl implode literal mode")),
+        };
+
+        self.dictionary_bits = self.input.bits(8)? as u8;
+        if !(4..=6).contains(&self.dictionary_bits) {
+            return Err(invalid_data("invalid dcl implode dictionary size"));
+        }
+
+        self.state = State::Codes;
+        Ok(())
+    }
+
+    fn symbol(&mut self) -> io::Result<()> {
+        if self.input.bits(1)? == 0 {
+            let byte = match self.coded_literals {
+                true => LITERALS.decode(&mut self.input)?,
+                false => self.input.bits(8)? as u8,
+            };
+            self.window.push(byte);
+            return Ok(());
+        }
+
+        let code = usize::from(LENGTHS.decode(&mut self.input)?);
+        let length = usize::from(LENGTH_BASE[code]) + self.input.bits(LENGTH_EXTRA[code])? as usize;
+        if length == END_LENGTH {
+            self.state = State::Done;
+            return Ok(());
+        }
+
+        // Matches of two bytes are limited to the nearest 256 bytes
+        let low_bits = if length == 2 { 2 } else { self.dictionary_bits };
+        let high = usize::from(DISTANCES.decode(&mut self.input)?);
+        let distance = (high << low_bits | self.input.bits(low_bits)? as usize) + 1;
+        if distance > self.window.history() {
+            return Err(invalid_data("distance too far back"));
+        }
+
+        self.window.copy(distance, length);
+        Ok(())
+    }
+}
+
+impl<R: Read> WindowDecoder for Decoder<R> {
+    fn window(&mut self) -> &mut Window {
+        &mut self.window
+    }
+
+    fn step(&mut self) -> io::Result<bool> {
+        match self.state {
+            State::Header => self.header()?,
+            State::Codes => self.symbol()?,
+            State::Done => return Ok(false),
+        }
+
+        Ok(true)
+    }
+}
+
+impl<R: Read> Read for Decoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.read_window(buf)
+    }
+}
+
+/// One of the fixed Huffman codes. Like Implode's Shannon-Fano codes, the
+/// bits of each code are inverted.
+#[derive(Debug)]
+struct Huffman {
+    /// Number of symbols with a code of each bit length
+    counts: [u16; MAX_BITS + 1],
+
+    /// Symbols ordered by code
+    symbols: [u8; 256],
+}
+
+impl Huffman {
+    const fn new(compact: &[u8]) -> Self {
+        let mut lengths = [0u8; 256];
+        let mut len = 0;
+        let mut i = 0;
+        while i < compact.len() {
+            let mut repeat = (compact[i] >> 4) + 1;
+            while repeat > 0 {
+                lengths[len] = compact[i] & 0x0f;
+                len += 1;
+                repeat -= 1;
+            }
+            i += 1;
+        }
+
+        let mut counts = [0u16; MAX_BITS + 1];
+        let mut symbol = 0;
+        while symbol < len {
+            counts[lengths[symbol] as usize] += 1;
+            symbol += 1;
+        }
+
+        let mut offsets = [0u16; MAX_BITS + 1];
+        let mut bits = 1;
+        while bits < MAX_BITS {
+            offsets[bits + 1] = offsets[bits] + counts[bits];
+            bits += 1;
+        }
+
+        let mut symbols = [0u8; 256];
+        let mut symbol = 0;
+        while symbol < len {
+            let length = lengths[symbol] as usize;
+            symbols[offsets[length] as usize] = symbol as u8;
+            offsets[length] += 1;
+            symbol += 1;
+        }
+
+        Huffman { counts, symbols }
+    }
+
+    fn decode<R: Read>(&self, input: &mut BitReader<R>) -> io::Result<u8> {
+        match input.canonical_code(&self.counts, true)? {
+            Some(index) => Ok(self.symbols[index]),
+            None => Err(invalid_data("invalid dcl implode code")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn decode(data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut output = Vec::new();
+        Decoder::new(data).read_to_end(&mut output)?;
+        Ok(output)
+    }
+
+    #[test]
+    fn test_binary_mode() {
+        let data = [0x00, 0x04, 0x82, 0x24, 0x25, 0x8f, 0x80, 0x7f];
+        assert_eq!(decode(&data).unwrap(), b"AIAIAIAIAIAIA");
+    }
+
+    #[test]
+    fn test_ascii_mode() {
+        // Coded literals with a 4 KiB dictionary, a two byte match at a
+        // distance of 3, and a 300 byte match at a distance of 8
+        let data = [
+            0x01, 0x06, 0x02, 0x05, 0xb6, 0xea, 0x1d, 0xcf, 0x34, 0x1d, 0x55, 0xc1, 0xf7, 0xbb,
+            0x8e, 0x67, 0x9a, 0x8e, 0xaa, 0x80, 0x32, 0x80, 0xe4, 0x23, 0xe0, 0x1f,
+        ];
+        let mut expected = b"It's a blast, t,a blast!".to_vec();
+        for _ in 0..300 {
+            expected.push(expected[expected.len() - 8]);
+        }
+        assert_eq!(decode(&data).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_small_reads() {
+        let data = [0x00, 0x04, 0x82, 0x24, 0x25, 0x8f, 0x80, 0x7f];
+        let mut decoder = Decoder::new(&data[..]);
+        let mut output = Vec::new();
+        let mut byte = [0u8; 1];
+        while decoder.read(&mut byte).unwrap() == 1 {
+            output.push(byte[0]);
+        }
+        assert_eq!(output, b"AIAIAIAIAIAIA");
+    }
+
+    #[rstest]
+    #[case::literal_mode(&[0x02, 0x04], io::ErrorKind::InvalidData)]
+    #[case::dictionary_size(&[0x00, 0x07], io::ErrorKind::InvalidData)]
+    #[case::distance_too_far_back(&[0x00, 0x04, 0x82, 0x7e, 0x04, 0xfc, 0x03], io::ErrorKind::InvalidData)]
+    #[case::truncated(&[0x00, 0x04, 0x82, 0x24, 0x25], io::ErrorKind::UnexpectedEof)]
+    #[case::empty(&[], io::ErrorKind::UnexpectedEof)]
+    fn test_invalid_streams(#[case] data: &[u8], #[case] kind: io::ErrorKind) {
+        assert_eq!(decode(data).unwrap_err().kind(), kind);
+    }
+}
@@ -0,0 +1,385 @@
+//! A streaming decoder for Deflate64 ("Enhanced Deflate").
+//!
+//! Deflate64 is PKWARE's proprietary variant of deflate, stored as
+//! [`CompressionMethod::DEFLATE64`](crate::CompressionMethod::DEFLATE64). It
+//! is what Windows Explorer reaches for when it compresses files larger than
+//! 2 GiB. The bitstream is deflate's (RFC 1951) with three differences:
+//!
+//! - The sliding window is 64 KiB instead of 32 KiB.
+//! - Length code 285 has a base of 3 and 16 extra bits instead of standing
+//!   for a fixed length of 258.
+//! - Distance codes 30 and 31 are valid, with bases of 32769 and 49153 and
+//!   14 extra bits each.
+
+use crate::bits::{BitReader, Window, WindowDecoder, invalid_data};
+use std::io::{self, Read};
+
+const WINDOW_SIZE: usize = 1 << 16;
+const MAX_BITS: usize = 15;
+const MAX_LIT_CODES: usize = 286;
+const MAX_DIST_CODES: usize = 32;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 3,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 16,
+];
+const DIST_BASE: [u16; 32] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577, 32769, 49153,
+];
+const DIST_EXTRA: [u8; 32] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13, 14, 14,
+];
+
+/// The order in which code length code lengths are stored (RFC 1951 §3.2.7).
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// A [`Read`] adapter that decompresses a Deflate64 stream.
+///
+/// Wrap an entry's compressed data, such as the [`ZipReader`] returned by
+/// [`ZipEntry::reader`] or the slice from [`ZipSliceEntry::data`], and read
+/// the decompressed data out. The decoder does not verify the output, so
+/// pass it to a verifying reader to check the entry's CRC32 and size.
+///
+/// The decoder reads ahead of the end of the compressed stream, which is
+/// harmless when the wrapped reader is bounded to the entry.
+///
+/// [`ZipReader`]: crate::ZipReader
+/// [`ZipEntry::reader`]: crate::ZipEntry::reader
+/// [`ZipSliceEntry::data`]: crate::ZipSliceEntry::data
+///
+/// # Examples
+///
+/// ```rust
+/// # use rawzip::{CompressionMethod, ZipArchive, RECOMMENDED_BUFFER_SIZE};
+/// # use rawzip::deflate64::Decoder;
+/// # use std::io::Read;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = std::fs::File::open("assets/deflate64.zip")?;
+/// let mut buffer = vec![0u8; RECOMMENDED_BUFFER_SIZE];
+/// let archive = ZipArchive::from_file(file, &mut buffer)?;
+/// let mut entries = archive.entries(&mut buffer);
+/// let entry = entries.next_entry()?.unwrap();
+/// assert_eq!(entry.compression_method(), CompressionMethod::DEFLATE64);
+///
+/// let zip_entry = archive.get_entry(entry.wayfinder())?;
+/// let decoder = Decoder::new(zip_entry.reader());
+/// let mut output = Vec::new();
+/// zip_entry.verifying_reader(decoder).read_to_end(&mut output)?;
+/// assert_eq!(output.len(), 132400);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Or register it with [`Decompressors`](crate::Decompressors):
+///
+/// ```rust
+/// use rawzip::{CompressionMethod, Decompressors, deflate64};
+///
+/// let mut decompressors = Decompressors::new();
+/// decompressors.register(CompressionMethod::DEFLATE64, |reader, _| {
+///     Ok(Box::new(deflate64::Decoder::new(reader)))
+/// });
+/// ```
+#[derive(Debug)]
+pub struct Decoder<R> {
+    input: BitReader<R>,
+    window: Window,
+    state: State,
+    last_block: bool,
+    literals: Huffman,
+    distances: Huffman,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    BlockHeader,
+    Stored { remaining: u16 },
+    Codes,
+    Done,
+}
+
+impl<R: Read> Decoder<R> {
+    /// Wraps a reader of Deflate64 compressed data.
+    pub fn new(reader: R) -> Self {
+        Decoder {
+            input: BitReader::new(reader),
+            window: Window::new(WINDOW_SIZE, u64::MAX),
+            state: State::BlockHeader,
+            last_block: false,
+            literals: Huffman::new(),
+            distances: Huffman::new(),
+        }
+    }
+
+    /// Returns a reference to the wrapped reader.
+    pub fn get_ref(&self) -> &R {
+        &self.input.reader
+    }
+
+    /// Consumes the decoder, returning the wrapped reader.
+    ///
+    /// The decoder buffers input, so the wrapped reader may be positioned
+    /// past the end of the compressed stream.
+    pub fn into_inner(self) -> R {
+        self.input.reader
+    }
+
+    fn block_header(&mut self) -> io::Result<()> {
+        if self.last_block {
+            self.state = State::Done;
+            return Ok(());
+        }
+
+        self.last_block = self.input.bits(1)? == 1;
+        match self.input.bits(2)? {
+            0 => {
+                self.input.align();
+                let length = self.input.bits(16)? as u16;
+                let complement = self.input.bits(16)? as u16;
+                if length != !complement {
+                    return Err(invalid_data(
+                        "stored block length does not match complement",
+                    ));
+                }
+                self.state = State::Stored { remaining: length };
+            }
+            1 => {
+                let mut lengths = [0u8; 288];
+                lengths[..144].fill(8);
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                lengths[280..].fill(8);
+                self.literals.build(&lengths)?;
+                self.distances.build(&[5; MAX_DIST_CODES])?;
+                self.state = State::Codes;
+            }
+            2 => {
+                self.dynamic_tables()?;
+                self.state = State::Codes;
+            }
+            _ => return Err(invalid_data("invalid block type")),
+        }
+
+        Ok(())
+    }
+
+    fn dynamic_tables(&mut self) -> io::Result<()> {
+        let literal_count = self.input.bits(5)? as usize + 257;
+        let distance_count = self.input.bits(5)? as usize + 1;
+        let code_length_count = self.input.bits(4)? as usize + 4;
+        if literal_count > MAX_LIT_CODES {
+            return Err(invalid_data("too many length or literal codes"));
+        }
+
+        let mut code_length_lengths = [0u8; 19];
+        for &index in &CODE_LENGTH_ORDER[..code_length_count] {
+            code_length_lengths[index] = self.input.bits(3)? as u8;
+        }
+
+        let mut code_lengths = Huffman::new();
+        code_lengths.build(&code_length_lengths)?;
+
+        let mut lengths = [0u8; MAX_LIT_CODES + MAX_DIST_CODES];
+
+        let total = literal_count + distance_count;
+        let mut index = 0;
+        while index < total {
+            let symbol = code_lengths.decode(&mut self.input)?;
+            let (length, repeat) = match symbol {
+                0..=15 => (symbol as u8, 1),
+                16 => {
+                    let Some(previous) = index.checked_sub(1).map(|i| lengths[i]) else {
+                        return Err(invalid_data(
+                            "repeated code length without a previous length",
+                        ));
+                    };
+                    (previous, 3 + self.input.bits(2)? as usize)
+                }
+                17 => (0, 3 + self.input.bits(3)? as usize),
+                _ => (0, 11 + self.input.bits(7)? as usize),
+            };
+
+            if index + repeat > total {
+                return Err(invalid_data("too many code lengths"));
+            }
+            lengths[index..index + repeat].fill(length);
+            index += repeat;
+        }
+
+        if lengths[256] == 0 {
+            return Err(invalid_data("missing end-of-block code"));
+        }
+
+        self.literals.build(&lengths[..literal_count])?;
+        self.distances.build(&lengths[literal_count..total])?;
+        Ok(())
+    }
+
+    fn symbol(&mut self) -> io::Result<()> {
+        let symbol = self.literals.decode(&mut self.input)? as usize;
+        if symbol < 256 {
+            self.window.push(symbol as u8);
+            return Ok(());
+        } else if symbol == 256 {
+            self.state = State::BlockHeader;
+            return Ok(());
+        }
+
+        let code = symbol - 257;
+        if code >= LENGTH_BASE.len() {
+            return Err(invalid_data("invalid length code"));
+        }
+        let length = usize::from(LENGTH_BASE[code]) + self.input.bits(LENGTH_EXTRA[code])? as usize;
+
+        let code = self.distances.decode(&mut self.input)? as usize;
+        let distance = usize::from(DIST_BASE[code]) + self.input.bits(DIST_EXTRA[code])? as usize;
+        if distance > self.window.history() {
+            return Err(invalid_data("distance too far back"));
+        }
+
+        self.window.copy(distance, length);
+        Ok(())
+    }
+}
+
+impl<R: Read> WindowDecoder for Decoder<R> {
+    fn window(&mut self) -> &mut Window {
+        &mut self.window
+    }
+
+    fn step(&mut self) -> io::Result<bool> {
+        match self.state {
+            State::BlockHeader => self.block_header()?,
+            State::Stored { remaining: 0 } => self.state = State::BlockHeader,
+            State::Stored { remaining } => {
+                let byte = self.input.bits(8)? as u8;
+                self.window.push(byte);
+                self.state = State::Stored {
+                    remaining: remaining - 1,
+                };
+            }
+            State::Codes => self.symbol()?,
+            State::Done => return Ok(false),
+        }
+
+        Ok(true)
+    }
+}
+
+impl<R: Read> Read for Decoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.read_window(buf)
+    }
+}
+
+/// A canonical Huffman code, decoded a bit at a time in the manner of zlib's
+/// puff.
+#[derive(Debug)]
+struct Huffman {
+    /// Number of symbols with a code of each bit length
+    counts: [u16; MAX_BITS + 1],
+
+    /// Symbols ordered by code
+    symbols: [u16; 288],
+}
+
+impl Huffman {
+    fn new() -> Self {
+        Huffman {
+            counts: [0; MAX_BITS + 1],
+            symbols: [0; 288],
+        }
+    }
+
+    /// Builds the code from the bit length of each symbol, where zero means
+    /// the symbol is unused.
+    ///
+    /// Over-subscribed codes are rejected. Incomplete codes are only accepted
+    /// when they consist of a single code, which is how an encoder describes
+    /// a block with one distinct distance.
+    fn build(&mut self, lengths: &[u8]) -> io::Result<()> {
+        self.counts = [0; MAX_BITS + 1];
+        for &length in lengths {
+            self.counts[usize::from(length)] += 1;
+        }
+
+        let used = lengths.len() - usize::from(self.counts[0]);
+        let mut left: i32 = 1;
+        for &count in &self.counts[1..] {
+            left = (left << 1) - i32::from(count);
+            if left < 0 {
+                return Err(invalid_data("over-subscribed huffman code"));
+            }
+        }
+
+        if left > 0 && used > 1 {
+            return Err(invalid_data("incomplete huffman code"));
+        }
+
+        let mut offsets = [0u16; MAX_BITS + 1];
+        for bits in 1..MAX_BITS {
+            offsets[bits + 1] = offsets[bits] + self.counts[bits];
+        }
+
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                let offset = &mut offsets[usize::from(length)];
+                self.symbols[usize::from(*offset)] = symbol as u16;
+                *offset += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn decode<R: Read>(&self, input: &mut BitReader<R>) -> io::Result<u16> {
+        match input.canonical_code(&self.counts, false)? {
+            Some(index) => Ok(self.symbols[index]),
+            None => Err(invalid_data("invalid huffman code")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn decode(data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut output = Vec::new();
+        Decoder::new(data).read_to_end(&mut output)?;
+        Ok(output)
+    }
+
+    #[test]
+    fn test_stored_block() {
+        let data = [0x01, 0x03, 0x00, 0xfc, 0xff, b'a', b'b', b'c'];
+        assert_eq!(decode(&data).unwrap(), b"abc");
+    }
+
+    #[test]
+    fn test_long_length_code() {
+        // A fixed block of a literal followed by a match of 65538 bytes, only
+        // expressible with the 16 extra bits of length code 285, and a match
+        // of 3 bytes that wraps around the window.
+        let data = [0x4b, 0x1c, 0xfd, 0xff, 0x07, 0x40, 0x00, 0x00];
+        assert_eq!(decode(&data).unwrap(), vec![b'a'; 65542]);
+    }
+
+    #[rstest]
+    #[case::reserved_block_type(&[0x07], io::ErrorKind::InvalidData)]
+    #[case::stored_length_mismatch(&[0x01, 0x03, 0x00, 0x00, 0x00], io::ErrorKind::InvalidData)]
+    #[case::distance_too_far_back(&[0x03, 0x02], io::ErrorKind::InvalidData)]
+    #[case::truncated(&[0x01, 0x03, 0x00, 0xfc, 0xff, b'a'], io::ErrorKind::UnexpectedEof)]
+    #[case::empty(&[], io::ErrorKind::UnexpectedEof)]
+    fn test_invalid_streams(#[case] data: &[u8], #[case] kind: io::ErrorKind) {
+        assert_eq!(decode(data).unwrap_err().kind(), kind);
+    }
+}
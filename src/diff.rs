@@ -0,0 +1,533 @@
+//! Entry-by-entry comparison of two archives.
+//!
+//! A [`Differ`] walks the central directories of an old and a new archive,
+//! pairs up entries by name, and reports each one that was added, removed,
+//! or changed. Changes are recorded field by field in [`Changes`] and
+//! classified as:
+//!
+//! - content: the CRC32 or the uncompressed size differ, or the decompressed
+//!   data does when verifying content
+//! - metadata: the modification time, mode, extra fields, or comment differ
+//! - layout: only the compression method, compressed size, flags, or
+//!   position in the archive differ
+//!
+//! Metadata comes from the central directory alone. The data of entries is
+//! only read when content verification is enabled with
+//! [`Differ::verify_content`].
+//!
+//! ```rust
+//! use rawzip::diff::{ChangeClass, Differ, EntryChange};
+//! use rawzip::{ZipArchive, ZipArchiveWriter};
+//! use std::io::Write;
+//!
+//! fn build(files: &[(&str, &[u8])]) -> Vec<u8> {
+//!     let mut data = Vec::new();
+//!     let mut archive = ZipArchiveWriter::new(&mut data);
+//!     for (name, contents) in files {
+//!         let (mut entry, config) = archive.new_file(name).start().unwrap();
+//!         let mut writer = config.wrap(&mut entry);
+//!         writer.write_all(contents).unwrap();
+//!         let (_, output) = writer.finish().unwrap();
+//!         entry.finish(output).unwrap();
+//!     }
+//!     archive.finish().unwrap();
+//!     data
+//! }
+//!
+//! let old = build(&[("a.txt", b"one"), ("b.txt", b"two")]);
+//! let new = build(&[("b.txt", b"2"), ("c.txt", b"three")]);
+//!
+//! let old = ZipArchive::from_slice(&old)?.into_reader();
+//! let new = ZipArchive::from_slice(&new)?.into_reader();
+//! let diff = Differ::new().diff(&old, &new)?;
+//!
+//! let entries = diff.entries();
+//! assert_eq!(entries[0].name(), "a.txt");
+//! assert_eq!(entries[0].change(), &EntryChange::Removed);
+//! assert_eq!(entries[1].name(), "b.txt");
+//! assert_eq!(entries[1].class(), Some(ChangeClass::Content));
+//! assert_eq!(entries[2].name(), "c.txt");
+//! assert_eq!(entries[2].change(), &EntryChange::Added);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::extra_fields::ExtraFieldId;
+use crate::time::ZipDateTimeKind;
+use crate::{
+    CompressionMethod, Decompressors, EntryMode, Error, RECOMMENDED_BUFFER_SIZE, ReaderAt,
+    ZipArchive, ZipArchiveEntryWayfinder, ZipFileHeaderRecord,
+};
+use std::io::Read;
+
+/// Compares the entries of two archives.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Differ<'a> {
+    decompressors: Option<&'a Decompressors>,
+}
+
+impl<'a> Differ<'a> {
+    /// Creates a differ that compares central directory records only.
+    pub fn new() -> Self {
+        Differ {
+            decompressors: None,
+        }
+    }
+
+    /// Also compares the decompressed data of entries whose CRC32 and
+    /// uncompressed size agree, streaming both sides through the
+    /// decompressors.
+    ///
+    /// This catches entries that collide on CRC32 and confirms that entries
+    /// whose layout changed, like a different compression method, still hold
+    /// the same data. Encrypted entries are compared by their records only.
+    pub fn verify_content(mut self, decompressors: &'a Decompressors) -> Self {
+        self.decompressors = Some(decompressors);
+        self
+    }
+
+    /// Compares the entries of `old` with those of `new`.
+    ///
+    /// Entries are paired by their raw names. When a name occurs several
+    /// times, occurrences are paired in central directory order. Unchanged
+    /// entries are left out of the result.
+    ///
+    /// An error is returned on I/O errors, and when verifying content, for
+    /// entries without a registered decompressor or whose decompressed data
+    /// fails its CRC32 or size check.
+    pub fn diff<A, B>(&self, old: &ZipArchive<A>, new: &ZipArchive<B>) -> Result<ArchiveDiff, Error>
+    where
+        A: ReaderAt,
+        B: ReaderAt,
+    {
+        let old_records = records(old)?;
+        let new_records = records(new)?;
+
+        let mut entries = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < old_records.len() || j < new_records.len() {
+            let order = match (old_records.get(i), new_records.get(j)) {
+                (Some(x), Some(y)) => x.key().cmp(&y.key()),
+                (Some(_), None) => core::cmp::Ordering::Less,
+                _ => core::cmp::Ordering::Greater,
+            };
+
+            match order {
+                core::cmp::Ordering::Less => {
+                    entries.push(EntryDiff::new(&old_records[i], EntryChange::Removed));
+                    i += 1;
+                }
+                core::cmp::Ordering::Greater => {
+                    entries.push(EntryDiff::new(&new_records[j], EntryChange::Added));
+                    j += 1;
+                }
+                core::cmp::Ordering::Equal => {
+                    let (x, y) = (&old_records[i], &new_records[j]);
+                    let mut changes = x.compare(y);
+                    if let Some(decompressors) = self.decompressors {
+                        if !changes.intersects(Changes::CONTENT)
+                            && !x.encrypted
+                            && !y.encrypted
+                            && !same_data(old, x, new, y, decompressors)?
+                        {
+                            changes = changes | Changes::DATA;
+                        }
+                    }
+
+                    if !changes.is_empty() {
+                        entries.push(EntryDiff::new(y, EntryChange::Changed(changes)));
+                    }
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+
+        Ok(ArchiveDiff { entries })
+    }
+}
+
+/// The owned central directory fields of an entry that take part in a diff.
+#[derive(Debug)]
+struct Record {
+    raw_name: Vec<u8>,
+    name: String,
+    occurrence: usize,
+    crc32: u32,
+    uncompressed_size: u64,
+    compressed_size: u64,
+    compression_method: CompressionMethod,
+    flags: u16,
+    local_header_offset: u64,
+    last_modified: ZipDateTimeKind,
+    mode: EntryMode,
+    extra_fields: Vec<u8>,
+    comment: Vec<u8>,
+    encrypted: bool,
+    wayfinder: ZipArchiveEntryWayfinder,
+}
+
+impl Record {
+    fn new(entry: &ZipFileHeaderRecord<'_>) -> Self {
+        let name = match entry.decoded_file_name() {
+            Ok(name) => name.into_owned(),
+            Err(_) => String::from_utf8_lossy(entry.file_path().as_bytes()).into_owned(),
+        };
+
+        // The zip64 field only restates sizes and offsets, which are
+        // compared on their own
+        let mut extra_fields = Vec::new();
+        for (id, data) in entry.extra_fields() {
+            if id != ExtraFieldId::ZIP64 {
+                extra_fields.extend_from_slice(&id.as_u16().to_le_bytes());
+                extra_fields.extend_from_slice(&(data.len() as u16).to_le_bytes());
+                extra_fields.extend_from_slice(data);
+            }
+        }
+
+        Record {
+            raw_name: entry.file_path().as_bytes().to_vec(),
+            name,
+            occurrence: 0,
+            crc32: entry.crc32(),
+            uncompressed_size: entry.uncompressed_size_hint(),
+            compressed_size: entry.compressed_size_hint(),
+            compression_method: entry.compression_method(),
+            flags: entry.flags().bits(),
+            local_header_offset: entry.local_header_offset(),
+            last_modified: entry.last_modified(),
+            mode: entry.mode(),
+            extra_fields,
+            comment: entry.comment().as_bytes().to_vec(),
+            encrypted: entry.encryption().is_encrypted(),
+            wayfinder: entry.wayfinder(),
+        }
+    }
+
+    fn key(&self) -> (&[u8], usize) {
+        (&self.raw_name, self.occurrence)
+    }
+
+    fn compare(&self, other: &Record) -> Changes {
+        let fields = [
+            (self.crc32 != other.crc32, Changes::CRC32),
+            (
+                self.uncompressed_size != other.uncompressed_size,
+                Changes::UNCOMPRESSED_SIZE,
+            ),
+            (
+                self.last_modified != other.last_modified,
+                Changes::LAST_MODIFIED,
+            ),
+            (self.mode != other.mode, Changes::MODE),
+            (
+                self.extra_fields != other.extra_fields,
+                Changes::EXTRA_FIELDS,
+            ),
+            (self.comment != other.comment, Changes::COMMENT),
+            (
+                self.compression_method != other.compression_method,
+                Changes::COMPRESSION_METHOD,
+            ),
+            (
+                self.compressed_size != other.compressed_size,
+                Changes::COMPRESSED_SIZE,
+            ),
+            (self.flags != other.flags, Changes::FLAGS),
+            (
+                self.local_header_offset != other.local_header_offset,
+                Changes::OFFSET,
+            ),
+        ];
+
+        fields
+            .into_iter()
+            .filter(|(changed, _)| *changed)
+            .fold(Changes::default(), |acc, (_, change)| acc | change)
+    }
+}
+
+/// Returns the records of an archive sorted by raw name and occurrence.
+fn records<R: ReaderAt>(archive: &ZipArchive<R>) -> Result<Vec<Record>, Error> {
+    let mut result = Vec::new();
+    let mut buffer = vec![0u8; RECOMMENDED_BUFFER_SIZE];
+    let mut entries = archive.entries(&mut buffer);
+    while let Some(entry) = entries.next_entry()? {
+        result.push(Record::new(&entry));
+    }
+
+    // A stable sort keeps duplicate names in central directory order
+    result.sort_by(|a, b| a.raw_name.cmp(&b.raw_name));
+    for i in 1..result.len() {
+        if result[i].raw_name == result[i - 1].raw_name {
+            result[i].occurrence = result[i - 1].occurrence + 1;
+        }
+    }
+
+    Ok(result)
+}
+
+/// Streams the decompressed data of both entries, returning whether they are
+/// equal.
+fn same_data<A, B>(
+    old: &ZipArchive<A>,
+    x: &Record,
+    new: &ZipArchive<B>,
+    y: &Record,
+    decompressors: &Decompressors,
+) -> Result<bool, Error>
+where
+    A: ReaderAt,
+    B: ReaderAt,
+{
+    let old_entry = old.get_entry(x.wayfinder)?;
+    let new_entry = new.get_entry(y.wayfinder)?;
+    let mut old_reader = old_entry.decoded_reader(decompressors)?;
+    let mut new_reader = new_entry.decoded_reader(decompressors)?;
+
+    let mut old_buffer = vec![0u8; RECOMMENDED_BUFFER_SIZE];
+    let mut new_buffer = vec![0u8; RECOMMENDED_BUFFER_SIZE];
+    loop {
+        let read = fill(&mut old_reader, &mut old_buffer)?;
+        let new_read = fill(&mut new_reader, &mut new_buffer)?;
+        if old_buffer[..read] != new_buffer[..new_read] {
+            return Ok(false);
+        }
+
+        if read == 0 {
+            return Ok(true);
+        }
+    }
+}
+
+/// Reads until the buffer is full or the reader is exhausted.
+fn fill<R: Read>(reader: &mut R, buffer: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+/// The entries that differ between two archives.
+#[derive(Debug, Default)]
+pub struct ArchiveDiff {
+    entries: Vec<EntryDiff>,
+}
+
+impl ArchiveDiff {
+    /// The entries that were added, removed, or changed, ordered by raw name.
+    pub fn entries(&self) -> &[EntryDiff] {
+        &self.entries
+    }
+
+    /// Returns true if the archives hold the same entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The entries that were added.
+    pub fn added(&self) -> impl Iterator<Item = &EntryDiff> {
+        self.entries
+            .iter()
+            .filter(|x| matches!(x.change, EntryChange::Added))
+    }
+
+    /// The entries that were removed.
+    pub fn removed(&self) -> impl Iterator<Item = &EntryDiff> {
+        self.entries
+            .iter()
+            .filter(|x| matches!(x.change, EntryChange::Removed))
+    }
+
+    /// The entries that were changed.
+    pub fn changed(&self) -> impl Iterator<Item = &EntryDiff> {
+        self.entries
+            .iter()
+            .filter(|x| matches!(x.change, EntryChange::Changed(_)))
+    }
+}
+
+/// A single entry that differs between two archives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryDiff {
+    name: String,
+    raw_name: Vec<u8>,
+    change: EntryChange,
+}
+
+impl EntryDiff {
+    fn new(record: &Record, change: EntryChange) -> Self {
+        EntryDiff {
+            name: record.name.clone(),
+            raw_name: record.raw_name.clone(),
+            change,
+        }
+    }
+
+    /// The decoded name of the entry as it appears in the archive, before
+    /// normalization.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The raw bytes of the entry name, which entries are paired and
+    /// ordered by.
+    pub fn raw_name(&self) -> &[u8] {
+        &self.raw_name
+    }
+
+    /// How the entry differs.
+    pub fn change(&self) -> &EntryChange {
+        &self.change
+    }
+
+    /// The class of a changed entry, or `None` if it was added or removed.
+    pub fn class(&self) -> Option<ChangeClass> {
+        match self.change {
+            EntryChange::Changed(changes) => changes.class(),
+            _ => None,
+        }
+    }
+}
+
+/// How an entry differs between two archives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EntryChange {
+    /// The entry is only in the new archive
+    Added,
+    /// The entry is only in the old archive
+    Removed,
+    /// The entry is in both archives with the given differences
+    Changed(Changes),
+}
+
+/// The most significant kind of difference of a changed entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ChangeClass {
+    /// Only the way the data is stored differs
+    Layout,
+    /// Attributes of the entry differ, but not its data
+    Metadata,
+    /// The data of the entry differs
+    Content,
+}
+
+/// The set of fields that differ between two versions of an entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Changes(u16);
+
+impl Changes {
+    /// The CRC32 differs.
+    pub const CRC32: Self = Self(0x0001);
+    /// The uncompressed size differs.
+    pub const UNCOMPRESSED_SIZE: Self = Self(0x0002);
+    /// The decompressed data differs despite matching CRC32 and size. Only
+    /// detected with [`Differ::verify_content`].
+    pub const DATA: Self = Self(0x0004);
+    /// The modification time differs.
+    pub const LAST_MODIFIED: Self = Self(0x0008);
+    /// The mode differs.
+    pub const MODE: Self = Self(0x0010);
+    /// The extra fields, other than zip64, differ.
+    pub const EXTRA_FIELDS: Self = Self(0x0020);
+    /// The entry comment differs.
+    pub const COMMENT: Self = Self(0x0040);
+    /// The compression method differs.
+    pub const COMPRESSION_METHOD: Self = Self(0x0080);
+    /// The compressed size differs.
+    pub const COMPRESSED_SIZE: Self = Self(0x0100);
+    /// The general purpose flags differ.
+    pub const FLAGS: Self = Self(0x0200);
+    /// The local header offset differs.
+    pub const OFFSET: Self = Self(0x0400);
+
+    /// Changes to the data of the entry.
+    pub const CONTENT: Self = Self(Self::CRC32.0 | Self::UNCOMPRESSED_SIZE.0 | Self::DATA.0);
+    /// Changes to the attributes of the entry.
+    pub const METADATA: Self =
+        Self(Self::LAST_MODIFIED.0 | Self::MODE.0 | Self::EXTRA_FIELDS.0 | Self::COMMENT.0);
+    /// Changes to how the entry is stored.
+    pub const LAYOUT: Self =
+        Self(Self::COMPRESSION_METHOD.0 | Self::COMPRESSED_SIZE.0 | Self::FLAGS.0 | Self::OFFSET.0);
+
+    /// Wrap raw change bits.
+    #[inline]
+    pub const fn new(bits: u16) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw change bits.
+    #[inline]
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Returns true if no field differs.
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns true if every change of `other` is set.
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns true if any change of `other` is set.
+    #[inline]
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// The most significant class of the changes, or `None` if empty.
+    pub const fn class(self) -> Option<ChangeClass> {
+        if self.intersects(Self::CONTENT) {
+            Some(ChangeClass::Content)
+        } else if self.intersects(Self::METADATA) {
+            Some(ChangeClass::Metadata)
+        } else if self.intersects(Self::LAYOUT) {
+            Some(ChangeClass::Layout)
+        } else {
+            None
+        }
+    }
+}
+
+impl core::ops::BitOr for Changes {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Changes::default(), None)]
+    #[case(Changes::OFFSET, Some(ChangeClass::Layout))]
+    #[case(Changes::COMPRESSION_METHOD | Changes::COMPRESSED_SIZE, Some(ChangeClass::Layout))]
+    #[case(Changes::MODE | Changes::OFFSET, Some(ChangeClass::Metadata))]
+    #[case(Changes::COMMENT, Some(ChangeClass::Metadata))]
+    #[case(Changes::CRC32 | Changes::LAST_MODIFIED, Some(ChangeClass::Content))]
+    #[case(Changes::DATA, Some(ChangeClass::Content))]
+    fn test_change_class(#[case] changes: Changes, #[case] expected: Option<ChangeClass>) {
+        assert_eq!(changes.class(), expected);
+    }
+
+    #[test]
+    fn test_classes_are_disjoint() {
+        assert!(!Changes::CONTENT.intersects(Changes::METADATA));
+        assert!(!Changes::CONTENT.intersects(Changes::LAYOUT));
+        assert!(!Changes::METADATA.intersects(Changes::LAYOUT));
+    }
+}
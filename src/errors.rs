@@ -94,9 +94,16 @@ pub enum ErrorKind {
     #[cfg(feature = "alloc")]
     InvalidInput { msg: String },
 
+    /// A path rejected by strict normalization
+    #[cfg(feature = "alloc")]
+    UnsafePath(crate::path::UnsafePathKind),
+
     /// Could not construct an archive with the given end of central directory
     InvalidEndOfCentralDirectory,
 
+    /// No decompressor is registered for the entry's compression method
+    UnsupportedCompressionMethod(crate::CompressionMethod),
+
     /// An IO error
     #[cfg(feature = "std")]
     IO(std::io::Error),
@@ -161,9 +168,16 @@ impl core::fmt::Display for ErrorKind {
             ErrorKind::InvalidInput { ref msg } => {
                 write!(f, "Invalid input: {msg}")
             }
+            #[cfg(feature = "alloc")]
+            ErrorKind::UnsafePath(kind) => {
+                write!(f, "Unsafe path: {kind}")
+            }
             ErrorKind::InvalidEndOfCentralDirectory => {
                 write!(f, "Invalid end of central directory")
             }
+            ErrorKind::UnsupportedCompressionMethod(method) => {
+                write!(f, "Unsupported compression method: {method}")
+            }
         }
     }
 }
@@ -1,4 +1,6 @@
-use crate::utils::le_u16;
+use crate::CompressionMethod;
+use crate::time::UtcDateTime;
+use crate::utils::{le_u16, le_u32, le_u64};
 #[cfg(feature = "std")]
 use crate::{Error, ErrorKind, Header};
 #[cfg(feature = "std")]
@@ -93,6 +95,12 @@ impl<'a> ExtraFields<'a> {
         self.data
     }
 
+    /// Converts into an iterator of typed [`ExtraField`] views.
+    #[inline]
+    pub fn typed(self) -> TypedExtraFields<'a> {
+        TypedExtraFields { inner: self }
+    }
+
     #[inline]
     fn next_data(&mut self) -> Option<&'a [u8]> {
         let scratch = self.data;
@@ -126,6 +134,669 @@ impl<'a> Iterator for ExtraFields<'a> {
     }
 }
 
+/// A typed view over a well-known extra field.
+///
+/// Views borrow from the underlying extra field data and decode values on
+/// access. Fields with an unrecognized id, or a recognized id whose data is
+/// too short to hold its fixed layout, are returned as
+/// [`ExtraField::Unknown`] with the raw bytes.
+///
+/// ```rust
+/// use rawzip::extra_fields::{ExtraField, ExtraFields};
+///
+/// // An Extended Timestamp with a modification time and an unknown field
+/// let data = [
+///     0x55, 0x54, 0x05, 0x00, 0x01, 0x00, 0xf1, 0x53, 0x65,
+///     0xff, 0xff, 0x01, 0x00, 0x2a,
+/// ];
+///
+/// let mut fields = ExtraFields::new(&data).typed();
+/// let Some(ExtraField::ExtendedTimestamp(timestamp)) = fields.next() else {
+///     panic!("expected an extended timestamp");
+/// };
+/// assert_eq!(timestamp.flags(), 0x01);
+/// assert_eq!(timestamp.modified().unwrap().to_unix(), 1_700_000_000);
+///
+/// let Some(ExtraField::Unknown { id, data }) = fields.next() else {
+///     panic!("expected an unknown field");
+/// };
+/// assert_eq!(id.as_u16(), 0xffff);
+/// assert_eq!(data, &[0x2a]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ExtraField<'a> {
+    /// Zip64 extended information (0x0001)
+    Zip64(Zip64Field<'a>),
+    /// NTFS timestamps (0x000a)
+    Ntfs(NtfsField<'a>),
+    /// Extended Timestamp (0x5455)
+    ExtendedTimestamp(ExtendedTimestampField<'a>),
+    /// Info-ZIP Unix, original format (0x5855)
+    InfoZipUnixOriginal(InfoZipUnixOriginalField<'a>),
+    /// Info-ZIP Unix, second format (0x7855)
+    InfoZipUnix(InfoZipUnixField<'a>),
+    /// Info-ZIP Unix UID/GID with variable width ids (0x7875)
+    UnixUidGid(UnixUidGidField<'a>),
+    /// Info-ZIP Unicode Path (0x7075)
+    UnicodePath(UnicodeField<'a>),
+    /// Info-ZIP Unicode Comment (0x6375)
+    UnicodeComment(UnicodeField<'a>),
+    /// WinZip AES encryption (0x9901)
+    Aes(AesField<'a>),
+    /// Android zipalign padding (0xd935)
+    AndroidAlignment(AndroidAlignmentField<'a>),
+    /// Microsoft Open Packaging growth hint (0xa220)
+    GrowthHint(GrowthHintField<'a>),
+    /// A field that is not recognized or could not be decoded
+    Unknown {
+        /// The extra field id
+        id: ExtraFieldId,
+        /// The extra field data, excluding the id and size
+        data: &'a [u8],
+    },
+}
+
+impl<'a> ExtraField<'a> {
+    /// Decodes the data of an extra field with the given id.
+    pub fn parse(id: ExtraFieldId, data: &'a [u8]) -> Self {
+        match id {
+            ExtraFieldId::ZIP64 => ExtraField::Zip64(Zip64Field { data }),
+            ExtraFieldId::NTFS => {
+                NtfsField::parse(data).map_or(ExtraField::Unknown { id, data }, ExtraField::Ntfs)
+            }
+            ExtraFieldId::EXTENDED_TIMESTAMP if !data.is_empty() => {
+                ExtraField::ExtendedTimestamp(ExtendedTimestampField { data })
+            }
+            ExtraFieldId::INFO_ZIP_UNIX_ORIGINAL if data.len() >= 8 => {
+                ExtraField::InfoZipUnixOriginal(InfoZipUnixOriginalField { data })
+            }
+            ExtraFieldId::INFO_ZIP_UNIX if data.is_empty() || data.len() >= 4 => {
+                ExtraField::InfoZipUnix(InfoZipUnixField { data })
+            }
+            ExtraFieldId::INFO_ZIP_UNIX_UID_GID => UnixUidGidField::parse(data)
+                .map_or(ExtraField::Unknown { id, data }, ExtraField::UnixUidGid),
+            ExtraFieldId::INFO_ZIP_UNICODE_PATH if data.len() >= 5 => {
+                ExtraField::UnicodePath(UnicodeField { data })
+            }
+            ExtraFieldId::INFO_ZIP_UNICODE_COMMENT if data.len() >= 5 => {
+                ExtraField::UnicodeComment(UnicodeField { data })
+            }
+            ExtraFieldId::AES if data.len() >= 7 => ExtraField::Aes(AesField { data }),
+            ExtraFieldId::ANDROID_ZIP_ALIGNMENT if data.len() >= 2 => {
+                ExtraField::AndroidAlignment(AndroidAlignmentField { data })
+            }
+            ExtraFieldId::MICROSOFT_OPEN_PACKAGING_GROWTH_HINT if data.len() >= 4 => {
+                ExtraField::GrowthHint(GrowthHintField { data })
+            }
+            _ => ExtraField::Unknown { id, data },
+        }
+    }
+
+    /// Returns the id of the extra field.
+    pub fn id(&self) -> ExtraFieldId {
+        match self {
+            ExtraField::Zip64(_) => ExtraFieldId::ZIP64,
+            ExtraField::Ntfs(_) => ExtraFieldId::NTFS,
+            ExtraField::ExtendedTimestamp(_) => ExtraFieldId::EXTENDED_TIMESTAMP,
+            ExtraField::InfoZipUnixOriginal(_) => ExtraFieldId::INFO_ZIP_UNIX_ORIGINAL,
+            ExtraField::InfoZipUnix(_) => ExtraFieldId::INFO_ZIP_UNIX,
+            ExtraField::UnixUidGid(_) => ExtraFieldId::INFO_ZIP_UNIX_UID_GID,
+            ExtraField::UnicodePath(_) => ExtraFieldId::INFO_ZIP_UNICODE_PATH,
+            ExtraField::UnicodeComment(_) => ExtraFieldId::INFO_ZIP_UNICODE_COMMENT,
+            ExtraField::Aes(_) => ExtraFieldId::AES,
+            ExtraField::AndroidAlignment(_) => ExtraFieldId::ANDROID_ZIP_ALIGNMENT,
+            ExtraField::GrowthHint(_) => ExtraFieldId::MICROSOFT_OPEN_PACKAGING_GROWTH_HINT,
+            ExtraField::Unknown { id, .. } => *id,
+        }
+    }
+}
+
+/// An iterator over typed extra fields, created by [`ExtraFields::typed`].
+#[derive(Debug, Clone)]
+pub struct TypedExtraFields<'a> {
+    inner: ExtraFields<'a>,
+}
+
+impl<'a> TypedExtraFields<'a> {
+    /// Returns the remaining unparsed bytes in the extra field data.
+    #[inline]
+    pub fn remaining_bytes(&self) -> &'a [u8] {
+        self.inner.remaining_bytes()
+    }
+}
+
+impl<'a> Iterator for TypedExtraFields<'a> {
+    type Item = ExtraField<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (id, data) = self.inner.next()?;
+        Some(ExtraField::parse(id, data))
+    }
+}
+
+/// Returns the text of the Info-ZIP Unicode field with the given id when it
+/// supersedes `raw`: a version 1 field whose CRC-32 matches `raw` and whose
+/// text is valid UTF-8.
+pub(crate) fn find_unicode_field<'a>(
+    fields: ExtraFields<'a>,
+    id: ExtraFieldId,
+    raw: &[u8],
+) -> Option<&'a [u8]> {
+    let field = fields.typed().find_map(|field| match field {
+        ExtraField::UnicodePath(field) if id == ExtraFieldId::INFO_ZIP_UNICODE_PATH => Some(field),
+        ExtraField::UnicodeComment(field) if id == ExtraFieldId::INFO_ZIP_UNICODE_COMMENT => {
+            Some(field)
+        }
+        _ => None,
+    })?;
+
+    let text = field.as_bytes();
+    let valid = field.version() == 1
+        && field.crc32() == crate::crc32(raw)
+        && core::str::from_utf8(text).is_ok();
+    valid.then_some(text)
+}
+
+/// Zip64 extended information extra field (0x0001).
+///
+/// The field only stores the values whose 32-bit (or 16-bit) counterparts in
+/// the header are saturated, in the order: uncompressed size, compressed size,
+/// local header offset, and disk number. Use [`Zip64Field::resolve`] to
+/// assign them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Zip64Field<'a> {
+    data: &'a [u8],
+}
+
+/// Values resolved from a [`Zip64Field`].
+///
+/// A value is `None` when the header did not defer to the Zip64 field, or
+/// the field was too short to contain it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Zip64Values {
+    /// The uncompressed size of the entry
+    pub uncompressed_size: Option<u64>,
+    /// The compressed size of the entry
+    pub compressed_size: Option<u64>,
+    /// The offset of the entry's local file header
+    pub local_header_offset: Option<u64>,
+    /// The disk number the entry starts on
+    pub disk_number_start: Option<u32>,
+}
+
+impl<'a> Zip64Field<'a> {
+    /// Returns the raw field data.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Resolves the Zip64 values given the values from the header that
+    /// contains this field.
+    ///
+    /// Local file headers have no local header offset or disk number, so
+    /// pass `0` for those.
+    pub fn resolve(
+        &self,
+        uncompressed_size: u32,
+        compressed_size: u32,
+        local_header_offset: u32,
+        disk_number_start: u16,
+    ) -> Zip64Values {
+        let mut field = self.data;
+        let mut take = |present: bool, width: usize| {
+            if !present || field.len() < width {
+                return None;
+            }
+            let (value, rest) = field.split_at(width);
+            field = rest;
+            Some(value)
+        };
+
+        let uncompressed_size = take(uncompressed_size == u32::MAX, 8).map(le_u64);
+        let compressed_size = take(compressed_size == u32::MAX, 8).map(le_u64);
+        let local_header_offset = take(local_header_offset == u32::MAX, 8).map(le_u64);
+        let disk_number_start = take(disk_number_start == u16::MAX, 4).map(le_u32);
+        Zip64Values {
+            uncompressed_size,
+            compressed_size,
+            local_header_offset,
+            disk_number_start,
+        }
+    }
+}
+
+/// NTFS extra field (0x000a) with the timestamp attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NtfsField<'a> {
+    // The 24 bytes of the timestamp attribute (tag 0x0001)
+    times: &'a [u8],
+}
+
+impl<'a> NtfsField<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        // 4 reserved bytes followed by tag / size / value attributes
+        let mut attributes = data.get(4..)?;
+        while attributes.len() >= 4 {
+            let tag = le_u16(&attributes[0..2]);
+            let size = le_u16(&attributes[2..4]) as usize;
+            let value = attributes.get(4..4 + size)?;
+            if tag == 0x0001 && size >= 24 {
+                return Some(NtfsField {
+                    times: &value[..24],
+                });
+            }
+            attributes = &attributes[4 + size..];
+        }
+        None
+    }
+
+    /// Returns the modification time in 100ns ticks since 1601-01-01.
+    pub fn modified_ticks(&self) -> u64 {
+        le_u64(&self.times[0..8])
+    }
+
+    /// Returns the last access time in 100ns ticks since 1601-01-01.
+    pub fn accessed_ticks(&self) -> u64 {
+        le_u64(&self.times[8..16])
+    }
+
+    /// Returns the creation time in 100ns ticks since 1601-01-01.
+    pub fn created_ticks(&self) -> u64 {
+        le_u64(&self.times[16..24])
+    }
+
+    /// Returns the modification time.
+    pub fn modified(&self) -> UtcDateTime {
+        UtcDateTime::from_ntfs(self.modified_ticks())
+    }
+
+    /// Returns the last access time.
+    pub fn accessed(&self) -> UtcDateTime {
+        UtcDateTime::from_ntfs(self.accessed_ticks())
+    }
+
+    /// Returns the creation time.
+    pub fn created(&self) -> UtcDateTime {
+        UtcDateTime::from_ntfs(self.created_ticks())
+    }
+}
+
+/// Extended Timestamp extra field (0x5455).
+///
+/// The flags describe which times were recorded. The local header form
+/// stores each flagged time, while the central directory form keeps the
+/// flags but stores at most the modification time, so a flagged time may
+/// still be absent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtendedTimestampField<'a> {
+    data: &'a [u8],
+}
+
+impl ExtendedTimestampField<'_> {
+    /// Returns the flags byte: bit 0 modification, bit 1 access, and bit 2
+    /// creation time.
+    pub fn flags(&self) -> u8 {
+        self.data[0]
+    }
+
+    fn time(&self, bit: u8) -> Option<UtcDateTime> {
+        let flags = self.flags();
+        if flags & (1 << bit) == 0 {
+            return None;
+        }
+
+        let preceding = (flags & ((1 << bit) - 1)).count_ones() as usize;
+        let start = 1 + preceding * 4;
+        let seconds = self.data.get(start..start + 4).map(le_u32)?;
+        Some(UtcDateTime::from_unix(i64::from(seconds)))
+    }
+
+    /// Returns the modification time, if present.
+    pub fn modified(&self) -> Option<UtcDateTime> {
+        self.time(0)
+    }
+
+    /// Returns the last access time, if present.
+    pub fn accessed(&self) -> Option<UtcDateTime> {
+        self.time(1)
+    }
+
+    /// Returns the creation time, if present.
+    pub fn created(&self) -> Option<UtcDateTime> {
+        self.time(2)
+    }
+}
+
+/// Info-ZIP Unix extra field, original format (0x5855).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InfoZipUnixOriginalField<'a> {
+    data: &'a [u8],
+}
+
+impl InfoZipUnixOriginalField<'_> {
+    /// Returns the last access time.
+    pub fn accessed(&self) -> UtcDateTime {
+        UtcDateTime::from_unix(i64::from(le_u32(&self.data[0..4])))
+    }
+
+    /// Returns the modification time.
+    pub fn modified(&self) -> UtcDateTime {
+        UtcDateTime::from_unix(i64::from(le_u32(&self.data[4..8])))
+    }
+
+    /// Returns the user id, only present in the local header.
+    pub fn uid(&self) -> Option<u16> {
+        self.data.get(8..10).map(le_u16)
+    }
+
+    /// Returns the group id, only present in the local header.
+    pub fn gid(&self) -> Option<u16> {
+        self.data.get(10..12).map(le_u16)
+    }
+}
+
+/// Info-ZIP Unix extra field, second format (0x7855).
+///
+/// The central directory form is empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InfoZipUnixField<'a> {
+    data: &'a [u8],
+}
+
+impl InfoZipUnixField<'_> {
+    /// Returns the user id, only present in the local header.
+    pub fn uid(&self) -> Option<u16> {
+        self.data.get(0..2).map(le_u16)
+    }
+
+    /// Returns the group id, only present in the local header.
+    pub fn gid(&self) -> Option<u16> {
+        self.data.get(2..4).map(le_u16)
+    }
+}
+
+/// Info-ZIP Unix UID/GID extra field (0x7875).
+///
+/// The ids are stored as variable width little endian integers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnixUidGidField<'a> {
+    version: u8,
+    uid: &'a [u8],
+    gid: &'a [u8],
+}
+
+impl<'a> UnixUidGidField<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let (&version, rest) = data.split_first()?;
+        let (&uid_size, rest) = rest.split_first()?;
+        let (uid, rest) = rest.split_at_checked(uid_size as usize)?;
+        let (&gid_size, rest) = rest.split_first()?;
+        let gid = rest.get(..gid_size as usize)?;
+        Some(UnixUidGidField { version, uid, gid })
+    }
+
+    /// Returns the version of the field (currently always 1).
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Returns the raw little endian bytes of the user id.
+    pub fn uid_bytes(&self) -> &'a [u8] {
+        self.uid
+    }
+
+    /// Returns the raw little endian bytes of the group id.
+    pub fn gid_bytes(&self) -> &'a [u8] {
+        self.gid
+    }
+
+    /// Returns the user id, or `None` if it does not fit in a `u64`.
+    pub fn uid(&self) -> Option<u64> {
+        variable_le(self.uid)
+    }
+
+    /// Returns the group id, or `None` if it does not fit in a `u64`.
+    pub fn gid(&self) -> Option<u64> {
+        variable_le(self.gid)
+    }
+}
+
+fn variable_le(data: &[u8]) -> Option<u64> {
+    let mut result = 0u64;
+    for (i, &byte) in data.iter().enumerate() {
+        if i >= 8 {
+            if byte != 0 {
+                return None;
+            }
+            continue;
+        }
+        result |= u64::from(byte) << (i * 8);
+    }
+    Some(result)
+}
+
+/// Info-ZIP Unicode Path (0x7075) or Unicode Comment (0x6375) extra field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnicodeField<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> UnicodeField<'a> {
+    /// Returns the version of the field (currently always 1).
+    pub fn version(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// Returns the CRC-32 of the header's file name or comment that this
+    /// field supersedes.
+    pub fn crc32(&self) -> u32 {
+        le_u32(&self.data[1..5])
+    }
+
+    /// Returns the UTF-8 encoded file name or comment.
+    ///
+    /// The bytes are not validated as UTF-8.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        &self.data[5..]
+    }
+}
+
+/// WinZip AES encryption extra field (0x9901).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AesField<'a> {
+    data: &'a [u8],
+}
+
+impl AesField<'_> {
+    /// Returns the vendor version: 1 for AE-1, 2 for AE-2.
+    pub fn vendor_version(&self) -> u16 {
+        le_u16(&self.data[0..2])
+    }
+
+    /// Returns the vendor id, which is `b"AE"`.
+    pub fn vendor_id(&self) -> [u8; 2] {
+        [self.data[2], self.data[3]]
+    }
+
+    /// Returns the AES strength: 1 (128-bit), 2 (192-bit), or 3 (256-bit).
+    pub fn strength(&self) -> u8 {
+        self.data[4]
+    }
+
+    /// Returns the compression method of the data before encryption.
+    pub fn compression_method(&self) -> CompressionMethod {
+        CompressionMethod::from(le_u16(&self.data[5..7]))
+    }
+}
+
+/// Android zipalign extra field (0xd935).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AndroidAlignmentField<'a> {
+    data: &'a [u8],
+}
+
+impl AndroidAlignmentField<'_> {
+    /// Returns the alignment in bytes the entry data was aligned to.
+    pub fn alignment(&self) -> u16 {
+        le_u16(&self.data[0..2])
+    }
+
+    /// Returns the number of padding bytes following the alignment.
+    pub fn padding_len(&self) -> usize {
+        self.data.len() - 2
+    }
+}
+
+/// Microsoft Open Packaging growth hint extra field (0xa220).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrowthHintField<'a> {
+    data: &'a [u8],
+}
+
+impl GrowthHintField<'_> {
+    /// Returns the signature, which is `0xa028`.
+    pub fn signature(&self) -> u16 {
+        le_u16(&self.data[0..2])
+    }
+
+    /// Returns the value used to fill the padding.
+    pub fn padding_value(&self) -> u16 {
+        le_u16(&self.data[2..4])
+    }
+
+    /// Returns the number of padding bytes reserved for growth.
+    pub fn padding_len(&self) -> usize {
+        self.data.len() - 4
+    }
+}
+
+/// A well-known extra field to encode when writing an entry.
+///
+/// Passed to [`ZipFileBuilder::typed_extra_field`](crate::ZipFileBuilder::typed_extra_field)
+/// and [`ZipDirBuilder::typed_extra_field`](crate::ZipDirBuilder::typed_extra_field),
+/// which encode the field and place it in the local and central headers as
+/// the specification expects.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ExtraFieldValue<'a> {
+    /// Info-ZIP Unix UID/GID (0x7875), written to both headers.
+    ///
+    /// Ids are written as 4 bytes, or 8 bytes when they exceed `u32`.
+    UnixUidGid {
+        /// The user id
+        uid: u64,
+        /// The group id
+        gid: u64,
+    },
+
+    /// Info-ZIP Unix (0x7855) with the ids in the local header and an empty
+    /// central header form.
+    InfoZipUnix {
+        /// The user id
+        uid: u16,
+        /// The group id
+        gid: u16,
+    },
+
+    /// Info-ZIP Unicode Path (0x7075), written to both headers.
+    UnicodePath {
+        /// The file name as written in the header, used for the CRC-32
+        original: &'a [u8],
+        /// The UTF-8 file name
+        path: &'a str,
+    },
+
+    /// Info-ZIP Unicode Comment (0x6375), written to the central header.
+    UnicodeComment {
+        /// The comment as written in the header, used for the CRC-32
+        original: &'a [u8],
+        /// The UTF-8 comment
+        comment: &'a str,
+    },
+
+    /// WinZip AES (0x9901), written to both headers.
+    Aes {
+        /// 1 for AE-1, 2 for AE-2
+        vendor_version: u16,
+        /// 1 (128-bit), 2 (192-bit), or 3 (256-bit)
+        strength: u8,
+        /// The compression method of the data before encryption
+        compression_method: CompressionMethod,
+    },
+}
+
+#[cfg(feature = "std")]
+impl ExtraFieldValue<'_> {
+    /// Returns the id of the encoded extra field.
+    pub fn id(&self) -> ExtraFieldId {
+        match self {
+            ExtraFieldValue::UnixUidGid { .. } => ExtraFieldId::INFO_ZIP_UNIX_UID_GID,
+            ExtraFieldValue::InfoZipUnix { .. } => ExtraFieldId::INFO_ZIP_UNIX,
+            ExtraFieldValue::UnicodePath { .. } => ExtraFieldId::INFO_ZIP_UNICODE_PATH,
+            ExtraFieldValue::UnicodeComment { .. } => ExtraFieldId::INFO_ZIP_UNICODE_COMMENT,
+            ExtraFieldValue::Aes { .. } => ExtraFieldId::AES,
+        }
+    }
+
+    /// Encodes the field into the container.
+    pub(crate) fn encode(&self, container: &mut ExtraFieldsContainer) -> Result<(), Error> {
+        let id = self.id();
+        match *self {
+            ExtraFieldValue::UnixUidGid { uid, gid } => {
+                let mut data = Vec::with_capacity(19);
+                data.push(1);
+                for value in [uid, gid] {
+                    let width = if value > u64::from(u32::MAX) { 8 } else { 4 };
+                    data.push(width as u8);
+                    data.extend_from_slice(&value.to_le_bytes()[..width]);
+                }
+                container.add_field(id, &data, Header::default())
+            }
+            ExtraFieldValue::InfoZipUnix { uid, gid } => {
+                let mut data = [0u8; 4];
+                data[0..2].copy_from_slice(&uid.to_le_bytes());
+                data[2..4].copy_from_slice(&gid.to_le_bytes());
+                container.add_field(id, &data, Header::LOCAL)?;
+                container.add_field(id, &[], Header::CENTRAL)
+            }
+            ExtraFieldValue::UnicodePath {
+                original,
+                path: text,
+            }
+            | ExtraFieldValue::UnicodeComment {
+                original,
+                comment: text,
+            } => {
+                let mut data = Vec::with_capacity(5 + text.len());
+                data.push(1);
+                data.extend_from_slice(&crate::crc32(original).to_le_bytes());
+                data.extend_from_slice(text.as_bytes());
+                let location = if id == ExtraFieldId::INFO_ZIP_UNICODE_PATH {
+                    Header::default()
+                } else {
+                    Header::CENTRAL
+                };
+                container.add_field(id, &data, location)
+            }
+            ExtraFieldValue::Aes {
+                vendor_version,
+                strength,
+                compression_method,
+            } => {
+                let mut data = [0u8; 7];
+                data[0..2].copy_from_slice(&vendor_version.to_le_bytes());
+                data[2..4].copy_from_slice(b"AE");
+                data[4] = strength;
+                data[5..7].copy_from_slice(&compression_method.as_u16().to_le_bytes());
+                container.add_field(id, &data, Header::default())
+            }
+        }
+    }
+}
+
 /// Container for extra fields with a shared data buffer and cached sizes.
 #[cfg(feature = "std")]
 #[derive(Debug, Clone)]
@@ -184,6 +855,26 @@ impl ExtraFieldsContainer {
         Ok(())
     }
 
+    /// Returns true if a field with the given id has been added.
+    pub fn contains(&self, id: ExtraFieldId) -> bool {
+        ExtraFields::new(self.data_buffer.as_slice()).any(|(x, _)| x == id)
+    }
+
+    /// Keeps only the fields whose id satisfies the predicate.
+    pub fn retain(&mut self, mut keep: impl FnMut(ExtraFieldId) -> bool) {
+        let mut result = Self::new();
+        let mut extra_fields = ExtraFields::new(self.data_buffer.as_slice());
+        for &location in self.entries.as_slice() {
+            let (id, data) = extra_fields.next().expect("Entry should have data");
+            if keep(id) {
+                result
+                    .add_field(id, data, location)
+                    .expect("retained fields fit");
+            }
+        }
+        *self = result;
+    }
+
     fn write_extra_fields_iter(
         &self,
         writer: &mut impl Write,
@@ -425,4 +1116,168 @@ mod tests {
             (ExtraFieldId::new(1), &[1u8; 16], Header::LOCAL),
         ]);
     }
+
+    #[test]
+    fn test_typed_ntfs() {
+        let mut data = vec![0u8; 4];
+        // An unrelated attribute before the timestamps
+        data.extend_from_slice(&[0x02, 0x00, 0x02, 0x00, 0xaa, 0xbb]);
+        data.extend_from_slice(&[0x01, 0x00, 0x18, 0x00]);
+        data.extend_from_slice(&133_000_000_000_000_000u64.to_le_bytes());
+        data.extend_from_slice(&133_000_000_010_000_000u64.to_le_bytes());
+        data.extend_from_slice(&116_444_736_000_000_000u64.to_le_bytes());
+
+        let ExtraField::Ntfs(ntfs) = ExtraField::parse(ExtraFieldId::NTFS, &data) else {
+            panic!("expected ntfs field");
+        };
+        assert_eq!(ntfs.modified_ticks(), 133_000_000_000_000_000);
+        assert_eq!(ntfs.accessed().to_unix(), ntfs.modified().to_unix() + 1);
+        assert_eq!(ntfs.created().to_unix(), 0);
+
+        // Missing the timestamp attribute
+        assert!(matches!(
+            ExtraField::parse(ExtraFieldId::NTFS, &data[..10]),
+            ExtraField::Unknown { .. }
+        ));
+    }
+
+    #[test]
+    fn test_typed_extended_timestamp() {
+        let mut local = vec![0b101];
+        local.extend_from_slice(&100u32.to_le_bytes());
+        local.extend_from_slice(&300u32.to_le_bytes());
+        let ExtraField::ExtendedTimestamp(ts) =
+            ExtraField::parse(ExtraFieldId::EXTENDED_TIMESTAMP, &local)
+        else {
+            panic!("expected extended timestamp");
+        };
+        assert_eq!(ts.modified().map(|x| x.to_unix()), Some(100));
+        assert_eq!(ts.accessed(), None);
+        assert_eq!(ts.created().map(|x| x.to_unix()), Some(300));
+
+        // Central form keeps the flags but only the modification time
+        let ExtraField::ExtendedTimestamp(ts) =
+            ExtraField::parse(ExtraFieldId::EXTENDED_TIMESTAMP, &local[..5])
+        else {
+            panic!("expected extended timestamp");
+        };
+        assert_eq!(ts.flags(), 0b101);
+        assert_eq!(ts.modified().map(|x| x.to_unix()), Some(100));
+        assert_eq!(ts.created(), None);
+    }
+
+    #[test]
+    fn test_typed_unix_fields() {
+        let data = [1, 4, 0xe8, 0x03, 0, 0, 2, 0x64, 0x00];
+        let ExtraField::UnixUidGid(ids) =
+            ExtraField::parse(ExtraFieldId::INFO_ZIP_UNIX_UID_GID, &data)
+        else {
+            panic!("expected uid/gid field");
+        };
+        assert_eq!(ids.version(), 1);
+        assert_eq!(ids.uid(), Some(1000));
+        assert_eq!(ids.gid(), Some(100));
+        assert_eq!(ids.gid_bytes(), &[0x64, 0x00]);
+
+        let wide = [1, 9, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0];
+        let ExtraField::UnixUidGid(ids) =
+            ExtraField::parse(ExtraFieldId::INFO_ZIP_UNIX_UID_GID, &wide)
+        else {
+            panic!("expected uid/gid field");
+        };
+        assert_eq!(ids.uid(), None);
+        assert_eq!(ids.gid(), Some(0));
+
+        // Truncated gid
+        assert!(matches!(
+            ExtraField::parse(ExtraFieldId::INFO_ZIP_UNIX_UID_GID, &data[..7]),
+            ExtraField::Unknown { .. }
+        ));
+
+        let ExtraField::InfoZipUnix(unix) = ExtraField::parse(ExtraFieldId::INFO_ZIP_UNIX, &[])
+        else {
+            panic!("expected unix field");
+        };
+        assert_eq!(unix.uid(), None);
+
+        let data = [10, 0, 0, 0, 20, 0, 0, 0, 5, 0, 6, 0];
+        let ExtraField::InfoZipUnixOriginal(unix) =
+            ExtraField::parse(ExtraFieldId::INFO_ZIP_UNIX_ORIGINAL, &data)
+        else {
+            panic!("expected original unix field");
+        };
+        assert_eq!(unix.accessed().to_unix(), 10);
+        assert_eq!(unix.modified().to_unix(), 20);
+        assert_eq!((unix.uid(), unix.gid()), (Some(5), Some(6)));
+    }
+
+    #[test]
+    fn test_typed_zip64_resolve() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&(1u64 << 33).to_le_bytes());
+        data.extend_from_slice(&(1u64 << 34).to_le_bytes());
+        let ExtraField::Zip64(zip64) = ExtraField::parse(ExtraFieldId::ZIP64, &data) else {
+            panic!("expected zip64 field");
+        };
+
+        // Only the compressed size and offset deferred to the field
+        let values = zip64.resolve(10, u32::MAX, u32::MAX, 0);
+        assert_eq!(values.uncompressed_size, None);
+        assert_eq!(values.compressed_size, Some(1 << 33));
+        assert_eq!(values.local_header_offset, Some(1 << 34));
+        assert_eq!(values.disk_number_start, None);
+
+        // Field is too short for every value
+        let values = zip64.resolve(u32::MAX, u32::MAX, u32::MAX, u16::MAX);
+        assert_eq!(values.local_header_offset, None);
+    }
+
+    #[test]
+    fn test_typed_misc_fields() {
+        let data = [1, 0x78, 0x56, 0x34, 0x12, b'h', b'i'];
+        let ExtraField::UnicodePath(path) =
+            ExtraField::parse(ExtraFieldId::INFO_ZIP_UNICODE_PATH, &data)
+        else {
+            panic!("expected unicode path");
+        };
+        assert_eq!(path.version(), 1);
+        assert_eq!(path.crc32(), 0x12345678);
+        assert_eq!(path.as_bytes(), b"hi");
+
+        let data = [2, 0, b'A', b'E', 3, 8, 0];
+        let ExtraField::Aes(aes) = ExtraField::parse(ExtraFieldId::AES, &data) else {
+            panic!("expected aes");
+        };
+        assert_eq!(aes.vendor_version(), 2);
+        assert_eq!(&aes.vendor_id(), b"AE");
+        assert_eq!(aes.strength(), 3);
+        assert_eq!(aes.compression_method(), CompressionMethod::DEFLATE);
+
+        let data = [0x04, 0x00, 0, 0, 0];
+        let ExtraField::AndroidAlignment(align) =
+            ExtraField::parse(ExtraFieldId::ANDROID_ZIP_ALIGNMENT, &data)
+        else {
+            panic!("expected alignment");
+        };
+        assert_eq!((align.alignment(), align.padding_len()), (4, 3));
+
+        let data = [0x28, 0xa0, 0x00, 0x00, 0, 0];
+        let ExtraField::GrowthHint(hint) =
+            ExtraField::parse(ExtraFieldId::MICROSOFT_OPEN_PACKAGING_GROWTH_HINT, &data)
+        else {
+            panic!("expected growth hint");
+        };
+        assert_eq!(hint.signature(), 0xa028);
+        assert_eq!(hint.padding_len(), 2);
+
+        let field = ExtraField::parse(ExtraFieldId::AES, &[1, 0]);
+        assert_eq!(
+            field,
+            ExtraField::Unknown {
+                id: ExtraFieldId::AES,
+                data: &[1, 0]
+            }
+        );
+        assert_eq!(field.id(), ExtraFieldId::AES);
+    }
 }
@@ -0,0 +1,798 @@
+//! Safe extraction of archives to the filesystem.
+//!
+//! An [`Extractor`] writes the entries of a [`ZipArchive`] under a root
+//! directory while defending against the common zip attacks:
+//!
+//! - Entries with unsafe names (see [`ZipFilePath::from_str_strict`]) are
+//!   skipped rather than rewritten.
+//! - Nothing is created through a symbolic link, whether extracted earlier
+//!   or already present under the root.
+//! - Symbolic links that resolve outside of the root are skipped.
+//! - Entries whose compressed data overlaps another's are skipped, which
+//!   defuses [overlapping zip bombs](https://www.bamsoftware.com/hacks/zipbomb/).
+//! - Decompressed sizes are enforced while writing, so an entry cannot
+//!   expand past its declared size or the configured limits.
+//!
+//! Entries are decompressed through a [`Decompressors`] registry, and those
+//! with a compression method missing from it are skipped.
+//!
+//! ```rust
+//! use rawzip::extract::{ExtractOutcome, Extractor};
+//! use rawzip::{Decompressors, ZipArchive, ZipArchiveWriter};
+//! use std::io::Write;
+//!
+//! let mut data = Vec::new();
+//! let mut archive = ZipArchiveWriter::new(&mut data);
+//! let (mut entry, config) = archive.new_file("docs/readme.txt").start()?;
+//! let mut writer = config.wrap(&mut entry);
+//! writer.write_all(b"Hello")?;
+//! let (_, output) = writer.finish()?;
+//! entry.finish(output)?;
+//! archive.finish()?;
+//!
+//! let root = std::env::temp_dir().join("rawzip-extract-doctest");
+//! # let _ = std::fs::remove_dir_all(&root);
+//! let archive = ZipArchive::from_slice(&data)?.into_reader();
+//! let report = Extractor::new(&root).extract(&archive, &Decompressors::new())?;
+//!
+//! assert_eq!(
+//!     report.entries()[0].outcome(),
+//!     &ExtractOutcome::File(root.join("docs").join("readme.txt"))
+//! );
+//! assert_eq!(std::fs::read(root.join("docs/readme.txt"))?, b"Hello");
+//! # std::fs::remove_dir_all(&root)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::mode::{S_IFDIR, S_IFLNK, S_IFMT, S_IFREG};
+use crate::path::{
+    MAX_SYMLINK_TARGET_LEN, NormalizedPath, SymlinkTarget, UnsafePathKind, ZipFilePath,
+};
+use crate::time::{LocalDateTime, UtcDateTime, ZipDateTimeKind};
+use crate::{
+    CompressionMethod, Crc32, DecoderContext, Decompressors, EntryMode, Error,
+    RECOMMENDED_BUFFER_SIZE, ReaderAt, ZipArchive, ZipEntry, ZipFileHeaderRecord, ZipVerification,
+};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// What to do when a file or symbolic link already exists at an entry's
+/// path.
+///
+/// Existing directories are always reused, and an existing directory is
+/// never replaced by a file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Overwrite {
+    /// Abort extraction with an error
+    #[default]
+    Error,
+    /// Leave the existing file in place and skip the entry
+    Skip,
+    /// Remove the existing file or symbolic link and extract the entry
+    Replace,
+}
+
+/// Extracts archives under a root directory.
+///
+/// See the [module documentation](self) for the protections in place.
+#[derive(Debug, Clone)]
+pub struct Extractor {
+    root: PathBuf,
+    overwrite: Overwrite,
+    max_entry_size: u64,
+    max_total_size: u64,
+    symlinks: bool,
+    permissions: bool,
+    modification_times: bool,
+}
+
+impl Extractor {
+    /// Creates an extractor that writes under `root`, which is created if it
+    /// does not exist.
+    ///
+    /// The root itself is trusted: it may be a symbolic link.
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Extractor {
+            root: root.as_ref().to_path_buf(),
+            overwrite: Overwrite::default(),
+            max_entry_size: u64::MAX,
+            max_total_size: u64::MAX,
+            symlinks: true,
+            permissions: true,
+            modification_times: true,
+        }
+    }
+
+    /// Sets the policy for entries whose path already exists. Defaults to
+    /// [`Overwrite::Error`].
+    #[must_use]
+    pub fn overwrite(mut self, overwrite: Overwrite) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Skips entries that declare a decompressed size larger than `size`
+    /// bytes.
+    #[must_use]
+    pub fn max_entry_size(mut self, size: u64) -> Self {
+        self.max_entry_size = size;
+        self
+    }
+
+    /// Skips entries once the decompressed sizes of the extracted entries
+    /// would exceed `size` bytes.
+    #[must_use]
+    pub fn max_total_size(mut self, size: u64) -> Self {
+        self.max_total_size = size;
+        self
+    }
+
+    /// Sets whether symbolic link entries are extracted. Defaults to true.
+    ///
+    /// Symbolic links are only extracted on Unix and only when their target
+    /// stays under the root.
+    #[must_use]
+    pub fn symlinks(mut self, enabled: bool) -> Self {
+        self.symlinks = enabled;
+        self
+    }
+
+    /// Sets whether the permissions of [`ZipFileHeaderRecord::mode`] are
+    /// applied. Defaults to true.
+    ///
+    /// On Unix, only the permission bits (`0o777`) are applied, never setuid,
+    /// setgid, or sticky bits. On Windows, files without the owner write bit
+    /// are marked read-only. Directory permissions are applied after all
+    /// entries are extracted, so that a read-only directory does not prevent
+    /// extracting its contents, and only to directories that extraction
+    /// created.
+    #[must_use]
+    pub fn permissions(mut self, enabled: bool) -> Self {
+        self.permissions = enabled;
+        self
+    }
+
+    /// Sets whether modification times are set from
+    /// [`ZipFileHeaderRecord::last_modified`]. Defaults to true.
+    ///
+    /// MS-DOS timestamps lack a time zone and are interpreted as UTC. Like
+    /// permissions, directory times are applied after all entries are
+    /// extracted, and only to directories that extraction created.
+    #[must_use]
+    pub fn modification_times(mut self, enabled: bool) -> Self {
+        self.modification_times = enabled;
+        self
+    }
+
+    /// Extracts every entry of the archive.
+    ///
+    /// Entries are decompressed with the decoder that `decompressors` has
+    /// registered for their compression method, and the size and CRC of the
+    /// written data are verified.
+    ///
+    /// # Errors
+    ///
+    /// Entries that cannot be extracted safely are reported as skipped in the
+    /// returned [`ExtractReport`]. An error is returned, and extraction
+    /// stops, on I/O errors, on decompression errors, when the decompressed
+    /// data does not match its declared size or CRC, and for existing files
+    /// under [`Overwrite::Error`]. A partially written file is removed.
+    pub fn extract<R>(
+        &self,
+        archive: &ZipArchive<R>,
+        decompressors: &Decompressors,
+    ) -> Result<ExtractReport, Error>
+    where
+        R: ReaderAt,
+    {
+        std::fs::create_dir_all(&self.root)?;
+
+        let mut state = ExtractState::default();
+        let mut report = ExtractReport::default();
+        let mut buffer = vec![0u8; RECOMMENDED_BUFFER_SIZE];
+        let mut entries = archive.entries(&mut buffer);
+        while let Some(entry) = entries.next_entry()? {
+            let name = match entry.decoded_file_name() {
+                Ok(name) => name.into_owned(),
+                Err(_) => String::from_utf8_lossy(entry.file_path().as_bytes()).into_owned(),
+            };
+
+            let outcome = self.extract_entry(archive, &entry, &mut state, decompressors)?;
+            report.entries.push(ExtractedEntry { name, outcome });
+        }
+
+        // Deepest directories first, as they were created last. Times are
+        // set before permissions, which may make a directory unreadable.
+        let created = state.directories.iter().rev();
+        for (path, mode, time) in created.filter(|x| state.created.contains(&x.0)) {
+            if self.modification_times {
+                if let Some(time) = system_time(time.clone()) {
+                    set_dir_modified(path, time)?;
+                }
+            }
+
+            if self.permissions {
+                set_permissions(path, *mode)?;
+            }
+        }
+
+        Ok(report)
+    }
+
+    fn extract_entry<R>(
+        &self,
+        archive: &ZipArchive<R>,
+        entry: &ZipFileHeaderRecord<'_>,
+        state: &mut ExtractState,
+        decompressors: &Decompressors,
+    ) -> Result<ExtractOutcome, Error>
+    where
+        R: ReaderAt,
+    {
+        let Ok(name) = entry.decoded_file_name() else {
+            return Ok(ExtractOutcome::Skipped(SkipReason::InvalidName));
+        };
+
+        if let Err(kind) = ZipFilePath::check_strict(&name) {
+            return Ok(ExtractOutcome::Skipped(SkipReason::UnsafePath(kind)));
+        }
+
+        let path = ZipFilePath::from_str(&name);
+
+        let target = path.join_under(&self.root);
+        let mode = entry.mode();
+        let file_type = mode.value() & S_IFMT;
+        if entry.is_dir() || file_type == S_IFDIR {
+            if !self.create_dirs(&target, true, state)? {
+                return Ok(ExtractOutcome::Skipped(SkipReason::PathConflict));
+            }
+
+            let time = entry.last_modified();
+            state.directories.push((target.clone(), mode, time));
+            return Ok(ExtractOutcome::Directory(target));
+        }
+
+        let is_symlink = file_type == S_IFLNK;
+        if !is_symlink && file_type != S_IFREG && file_type != 0 {
+            return Ok(ExtractOutcome::Skipped(SkipReason::UnsupportedFileType));
+        }
+
+        if is_symlink && (!self.symlinks || cfg!(not(unix))) {
+            return Ok(ExtractOutcome::Skipped(SkipReason::Symlink));
+        }
+
+        if entry.flags().is_encrypted() {
+            return Ok(ExtractOutcome::Skipped(SkipReason::Encrypted));
+        }
+
+        let method = entry.compression_method();
+        if !decompressors.contains(method) {
+            return Ok(ExtractOutcome::Skipped(SkipReason::UnsupportedMethod(
+                method,
+            )));
+        }
+
+        let size = entry.uncompressed_size_hint();
+        if size > self.max_entry_size {
+            return Ok(ExtractOutcome::Skipped(SkipReason::EntryTooLarge));
+        }
+
+        if state.total_size.saturating_add(size) > self.max_total_size {
+            return Ok(ExtractOutcome::Skipped(SkipReason::TotalSizeExceeded));
+        }
+
+        let zip_entry = archive.get_entry(entry.wayfinder())?;
+        if !state.claim_range(zip_entry.compressed_data_range()) {
+            return Ok(ExtractOutcome::Skipped(SkipReason::OverlappingData));
+        }
+
+        if !self.create_dirs(&target, false, state)? {
+            return Ok(ExtractOutcome::Skipped(SkipReason::PathConflict));
+        }
+
+        if let Some(reason) = self.clear_existing(&target)? {
+            return Ok(ExtractOutcome::Skipped(reason));
+        }
+
+        let context = DecoderContext::new(method, entry.flags(), size);
+        let expected = ZipVerification {
+            crc: entry.crc32(),
+            uncompressed_size: size,
+        };
+
+        if is_symlink {
+            if size > MAX_SYMLINK_TARGET_LEN as u64 {
+                return Ok(ExtractOutcome::Skipped(SkipReason::UnsafeSymlink));
+            }
+
+            let mut data = Vec::new();
+            decode(decompressors, &zip_entry, &context, &mut data, expected)?;
+
+            let link = match SymlinkTarget::new(&path, &data) {
+                Ok(link) if is_safe_symlink(&path, &link) => link,
+                _ => return Ok(ExtractOutcome::Skipped(SkipReason::UnsafeSymlink)),
+            };
+
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(link.as_str(), &target)?;
+            #[cfg(not(unix))]
+            let _ = link;
+
+            state.total_size += size;
+            return Ok(ExtractOutcome::Symlink(target));
+        }
+
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&target)?;
+
+        let mut output = std::io::BufWriter::new(file);
+        let decoded = decode(decompressors, &zip_entry, &context, &mut output, expected);
+        let file =
+            decoded.and_then(|_| output.into_inner().map_err(|e| Error::from(e.into_error())));
+
+        let file = match file {
+            Ok(file) => file,
+            Err(e) => {
+                let _ = std::fs::remove_file(&target);
+                return Err(e);
+            }
+        };
+
+        if self.modification_times {
+            if let Some(time) = system_time(entry.last_modified()) {
+                file.set_modified(time)?;
+            }
+        }
+
+        drop(file);
+        if self.permissions {
+            set_permissions(&target, mode)?;
+        }
+
+        state.total_size += size;
+        Ok(ExtractOutcome::File(target))
+    }
+
+    /// Creates the directories between the root and `target`, including
+    /// `target` itself when `include_target` is set.
+    ///
+    /// Returns false if one of them exists as something other than a
+    /// directory, like a file or a symbolic link. The directories that did
+    /// not exist yet are recorded as created.
+    fn create_dirs(
+        &self,
+        target: &Path,
+        include_target: bool,
+        state: &mut ExtractState,
+    ) -> Result<bool, Error> {
+        let Ok(relative) = target.strip_prefix(&self.root) else {
+            return Ok(false);
+        };
+
+        let mut components = relative.components();
+        if !include_target {
+            components.next_back();
+        }
+
+        let mut current = self.root.clone();
+        for component in components {
+            current.push(component);
+            match std::fs::symlink_metadata(&current) {
+                Ok(metadata) if metadata.is_dir() => {}
+                Ok(_) => return Ok(false),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    std::fs::create_dir(&current)?;
+                    state.created.insert(current.clone());
+                }
+                Err(e) => return Err(Error::from(e)),
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Applies the overwrite policy to an existing file or symbolic link at
+    /// `target`, without following it.
+    fn clear_existing(&self, target: &Path) -> Result<Option<SkipReason>, Error> {
+        let metadata = match std::fs::symlink_metadata(target) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(Error::from(e)),
+        };
+
+        if metadata.is_dir() {
+            return Ok(Some(SkipReason::PathConflict));
+        }
+
+        match self.overwrite {
+            Overwrite::Error => Err(Error::from(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("{} already exists", target.display()),
+            ))),
+            Overwrite::Skip => Ok(Some(SkipReason::Exists)),
+            Overwrite::Replace => {
+                std::fs::remove_file(target)?;
+                Ok(None)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ExtractState {
+    /// Sorted, non-empty compressed data ranges of extracted entries
+    ranges: Vec<(u64, u64)>,
+    total_size: u64,
+
+    /// Directory entries, in the order they were extracted
+    directories: Vec<(PathBuf, EntryMode, ZipDateTimeKind)>,
+
+    /// Directories that did not exist before extraction
+    created: HashSet<PathBuf>,
+}
+
+impl ExtractState {
+    /// Records the compressed data range of an entry, returning false if it
+    /// overlaps a previously recorded range.
+    fn claim_range(&mut self, (start, end): (u64, u64)) -> bool {
+        if start >= end {
+            return true;
+        }
+
+        let pos = self.ranges.partition_point(|&(s, _)| s < start);
+        let overlaps_prev = pos > 0 && self.ranges[pos - 1].1 > start;
+        let overlaps_next = pos < self.ranges.len() && self.ranges[pos].0 < end;
+        if overlaps_prev || overlaps_next {
+            return false;
+        }
+
+        self.ranges.insert(pos, (start, end));
+        true
+    }
+}
+
+/// A writer that tracks the CRC and size of written data, refusing to write
+/// past the declared size.
+struct VerifyingWriter<'a> {
+    inner: &'a mut dyn Write,
+    crc: Crc32,
+    size: u64,
+    limit: u64,
+}
+
+impl Write for VerifyingWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.size.saturating_add(buf.len() as u64) > self.limit {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "decompressed data exceeds the declared size",
+            ));
+        }
+
+        let written = self.inner.write(buf)?;
+        self.crc.update(&buf[..written]);
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Decompresses an entry into the output and verifies the written data.
+fn decode<R>(
+    decompressors: &Decompressors,
+    entry: &ZipEntry<'_, R>,
+    context: &DecoderContext,
+    output: &mut dyn Write,
+    expected: ZipVerification,
+) -> Result<(), Error>
+where
+    R: ReaderAt,
+{
+    let mut writer = VerifyingWriter {
+        inner: output,
+        crc: Crc32::new(),
+        size: 0,
+        limit: expected.uncompressed_size,
+    };
+
+    let mut reader = decompressors.decoder(context, Box::new(entry.reader()))?;
+    std::io::copy(&mut reader, &mut writer)?;
+
+    writer.flush()?;
+    expected.valid(ZipVerification {
+        crc: writer.crc.checksum(),
+        uncompressed_size: writer.size,
+    })?;
+    Ok(())
+}
+
+/// Returns true if the symbolic link target stays under the root.
+///
+/// On top of [`SymlinkTarget::escapes_root`], parent components are only
+/// allowed at the start of the target. After a named component, which may
+/// itself be a symbolic link, a parent component could climb out of wherever
+/// that link points.
+fn is_safe_symlink(link: &ZipFilePath<NormalizedPath<'_>>, target: &SymlinkTarget<'_>) -> bool {
+    if target.escapes_root() {
+        return false;
+    }
+
+    let mut depth = link.components().count().saturating_sub(1);
+    let mut descended = false;
+    for component in target.as_str().split(['/', '\\']) {
+        match component {
+            "" | "." => {}
+            ".." if descended => return false,
+            ".." => match depth.checked_sub(1) {
+                Some(x) => depth = x,
+                None => return false,
+            },
+
+            // Windows strips trailing dots and spaces, so `.. ` is `..`
+            _ if component.trim_end_matches(['.', ' ']).is_empty() => return false,
+            _ if component.trim_end_matches(['.', ' ']) == ".." => return false,
+            _ => descended = true,
+        }
+    }
+
+    true
+}
+
+fn set_permissions(path: &Path, mode: EntryMode) -> Result<(), Error> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let permissions = std::fs::Permissions::from_mode(mode.permissions());
+        std::fs::set_permissions(path, permissions)?;
+    }
+
+    #[cfg(windows)]
+    {
+        if mode.permissions() & 0o200 == 0 {
+            let mut permissions = std::fs::metadata(path)?.permissions();
+            permissions.set_readonly(true);
+            std::fs::set_permissions(path, permissions)?;
+        }
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    let _ = (path, mode);
+
+    Ok(())
+}
+
+/// Sets the modification time of a directory.
+fn set_dir_modified(path: &Path, time: SystemTime) -> Result<(), Error> {
+    #[cfg(unix)]
+    std::fs::File::open(path)?.set_modified(time)?;
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+
+        // Directories can only be opened with backup semantics
+        const FILE_FLAG_BACKUP_SEMANTICS: u32 = 0x0200_0000;
+        std::fs::OpenOptions::new()
+            .write(true)
+            .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+            .open(path)?
+            .set_modified(time)?;
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    let _ = (path, time);
+
+    Ok(())
+}
+
+/// Converts an entry timestamp to a [`SystemTime`]. MS-DOS timestamps at
+/// or before the format's epoch, 1980-01-01 00:00:00, are treated as unset.
+fn system_time(time: ZipDateTimeKind) -> Option<SystemTime> {
+    let is_after_dos_epoch = |local: &LocalDateTime| {
+        let parts = (local.year(), local.month(), local.day());
+        let time = (local.hour(), local.minute(), local.second());
+        (parts, time) > ((1980, 1, 1), (0, 0, 0))
+    };
+
+    let utc = match time {
+        ZipDateTimeKind::Utc(utc) => utc,
+        ZipDateTimeKind::Local(local) if is_after_dos_epoch(&local) => {
+            UtcDateTime::from_components(
+                local.year(),
+                local.month(),
+                local.day(),
+                local.hour(),
+                local.minute(),
+                local.second(),
+                local.nanosecond(),
+            )?
+        }
+        ZipDateTimeKind::Local(_) => return None,
+    };
+
+    let seconds = utc.to_unix();
+    let nanos = Duration::from_nanos(u64::from(utc.nanosecond()));
+    let time = if seconds >= 0 {
+        SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(seconds.unsigned_abs()))
+    } else {
+        SystemTime::UNIX_EPOCH.checked_sub(Duration::from_secs(seconds.unsigned_abs()))
+    };
+    time?.checked_add(nanos)
+}
+
+/// The outcome of every entry of an extracted archive.
+#[derive(Debug, Default)]
+pub struct ExtractReport {
+    entries: Vec<ExtractedEntry>,
+}
+
+impl ExtractReport {
+    /// The outcome of each entry, in central directory order.
+    pub fn entries(&self) -> &[ExtractedEntry] {
+        &self.entries
+    }
+
+    /// The entries that were skipped.
+    pub fn skipped(&self) -> impl Iterator<Item = &ExtractedEntry> {
+        self.entries
+            .iter()
+            .filter(|x| matches!(x.outcome, ExtractOutcome::Skipped(_)))
+    }
+}
+
+/// The outcome of extracting a single entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedEntry {
+    name: String,
+    outcome: ExtractOutcome,
+}
+
+impl ExtractedEntry {
+    /// The decoded name of the entry as it appears in the archive, before
+    /// normalization.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// What happened to the entry.
+    pub fn outcome(&self) -> &ExtractOutcome {
+        &self.outcome
+    }
+}
+
+/// What happened to an entry during extraction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ExtractOutcome {
+    /// A file was written to the path
+    File(PathBuf),
+    /// A directory was created, or already existed, at the path
+    Directory(PathBuf),
+    /// A symbolic link was created at the path
+    Symlink(PathBuf),
+    /// The entry was not extracted
+    Skipped(SkipReason),
+}
+
+/// The reason an entry was not extracted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SkipReason {
+    /// The name is flagged as UTF-8 but is not valid UTF-8
+    InvalidName,
+    /// The name is rejected by strict normalization
+    UnsafePath(UnsafePathKind),
+    /// A component of the path exists as something other than a directory,
+    /// like a file or a symbolic link, or a file entry names an existing
+    /// directory
+    PathConflict,
+    /// A file exists at the path and the overwrite policy is
+    /// [`Overwrite::Skip`]
+    Exists,
+    /// The entry is a device, FIFO, or socket
+    UnsupportedFileType,
+    /// The entry is encrypted
+    Encrypted,
+    /// No decompressor is registered for the compression method
+    UnsupportedMethod(CompressionMethod),
+    /// The declared size exceeds [`Extractor::max_entry_size`]
+    EntryTooLarge,
+    /// The declared size exceeds what remains of
+    /// [`Extractor::max_total_size`]
+    TotalSizeExceeded,
+    /// The compressed data overlaps that of an extracted entry
+    OverlappingData,
+    /// The entry is a symbolic link and symbolic links are disabled or
+    /// unsupported on this platform
+    Symlink,
+    /// The symbolic link target is too long, not UTF-8, or resolves outside
+    /// of the root
+    UnsafeSymlink,
+}
+
+impl core::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SkipReason::InvalidName => write!(f, "invalid name"),
+            SkipReason::UnsafePath(kind) => write!(f, "unsafe path: {kind}"),
+            SkipReason::PathConflict => write!(f, "path conflicts with an existing entry"),
+            SkipReason::Exists => write!(f, "file exists"),
+            SkipReason::UnsupportedFileType => write!(f, "unsupported file type"),
+            SkipReason::Encrypted => write!(f, "encrypted"),
+            SkipReason::UnsupportedMethod(method) => {
+                write!(f, "unsupported compression method: {method}")
+            }
+            SkipReason::EntryTooLarge => write!(f, "entry exceeds the size limit"),
+            SkipReason::TotalSizeExceeded => write!(f, "archive exceeds the size limit"),
+            SkipReason::OverlappingData => write!(f, "overlapping compressed data"),
+            SkipReason::Symlink => write!(f, "symbolic links are not extracted"),
+            SkipReason::UnsafeSymlink => write!(f, "unsafe symbolic link"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("link", "target", true)]
+    #[case("dir/link", "../target", true)]
+    #[case("dir/link", "./sibling/file", true)]
+    #[case("a/b/link", "../../x", true)]
+    #[case("link", "../target", false)]
+    #[case("a/b/link", "../../../x", false)]
+    #[case("dir/link", "sub/../../x", false)]
+    #[case("link", "/etc/passwd", false)]
+    #[case("link", "\\Windows", false)]
+    #[case("link", "C:\\Windows", false)]
+    #[case("dir/link", ".. /x", false)]
+    #[case("link", "", false)]
+    fn test_is_safe_symlink(#[case] link: &str, #[case] target: &str, #[case] expected: bool) {
+        let link = ZipFilePath::from_str(link);
+        let safe = SymlinkTarget::new(&link, target.as_bytes())
+            .is_ok_and(|target| is_safe_symlink(&link, &target));
+        assert_eq!(safe, expected);
+    }
+
+    #[rstest]
+    #[case(1980, 1, 1, 0, 0, 0, false)]
+    #[case(1980, 1, 1, 0, 0, 2, true)]
+    #[case(1980, 6, 15, 12, 0, 0, true)]
+    #[case(1981, 1, 1, 0, 0, 0, true)]
+    fn test_system_time_dos_epoch(
+        #[case] year: u16,
+        #[case] month: u8,
+        #[case] day: u8,
+        #[case] hour: u8,
+        #[case] minute: u8,
+        #[case] second: u8,
+        #[case] expected: bool,
+    ) {
+        let local = LocalDateTime::from_components(year, month, day, hour, minute, second, 0);
+        let time = system_time(ZipDateTimeKind::Local(local.unwrap()));
+        assert_eq!(time.is_some(), expected);
+    }
+
+    #[test]
+    fn test_claim_range() {
+        let mut state = ExtractState::default();
+        assert!(state.claim_range((10, 20)));
+        assert!(state.claim_range((20, 30)));
+        assert!(state.claim_range((0, 10)));
+        assert!(!state.claim_range((15, 25)));
+        assert!(!state.claim_range((5, 11)));
+        assert!(!state.claim_range((0, 40)));
+        assert!(state.claim_range((15, 15)));
+        assert!(state.claim_range((30, 31)));
+    }
+}
@@ -86,14 +86,16 @@ impl core::ops::BitAndAssign for Header {
 
 /// The general purpose bit flags of a ZIP entry.
 ///
-/// (§ 4.4.4). Only the bits with a fixed, method-independent meaning are
-/// surfaced as named accessors. Use [`EntryFlags::bits`] to inspect the raw
-/// value.
+/// (§ 4.4.4). The bits with a fixed, method-independent meaning are surfaced
+/// as named accessors, as are the Implode options of bits 1 and 2. Use
+/// [`EntryFlags::bits`] to inspect the raw value.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct EntryFlags(u16);
 
 impl EntryFlags {
     const ENCRYPTED: u16 = 1 << 0;
+    const IMPLODE_8K_DICTIONARY: u16 = 1 << 1;
+    const IMPLODE_THREE_TREES: u16 = 1 << 2;
     const DATA_DESCRIPTOR: u16 = 1 << 3;
     const STRONG_ENCRYPTION: u16 = 1 << 6;
     const LANGUAGE_ENCODING: u16 = 1 << 11;
@@ -106,8 +108,9 @@ impl EntryFlags {
 
     /// The raw 16-bit general purpose bit flag value.
     ///
-    /// Use this to inspect the method-dependent bits 1 and 2, or any of the
-    /// reserved bits not surfaced by the named accessors.
+    /// Use this to inspect the method-dependent bits 1 and 2 of methods other
+    /// than Implode, or any of the reserved bits not surfaced by the named
+    /// accessors.
     #[inline]
     pub const fn bits(self) -> u16 {
         self.0
@@ -119,6 +122,23 @@ impl EntryFlags {
         self.0 & Self::ENCRYPTED != 0
     }
 
+    /// Bit 1 of an imploded entry: the sliding dictionary is 8K instead of 4K.
+    ///
+    /// Other methods give this bit a different meaning.
+    #[inline]
+    pub const fn has_implode_8k_dictionary(self) -> bool {
+        self.0 & Self::IMPLODE_8K_DICTIONARY != 0
+    }
+
+    /// Bit 2 of an imploded entry: three Shannon-Fano trees code the data,
+    /// with the third coding literals, instead of two.
+    ///
+    /// Other methods give this bit a different meaning.
+    #[inline]
+    pub const fn has_implode_three_trees(self) -> bool {
+        self.0 & Self::IMPLODE_THREE_TREES != 0
+    }
+
     /// Bit 3: the crc-32, compressed size, and uncompressed size are zeroed in
     /// the local header, with the correct values stored in a data descriptor
     /// that follows the compressed data.
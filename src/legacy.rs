@@ -0,0 +1,75 @@
+//! Streaming decoders for the compression methods of early PKZIP releases.
+//!
+//! Before deflate took over in PKZIP 2.0, archives were compressed with:
+//!
+//! - [`CompressionMethod::SHRUNK`]: dynamic LZW with partial clearing,
+//!   decoded by [`ShrinkDecoder`].
+//! - [`CompressionMethod::REDUCE1`] through [`CompressionMethod::REDUCE4`]:
+//!   follower sets over a probabilistic LZ77 stage, decoded by
+//!   [`ReduceDecoder`].
+//! - [`CompressionMethod::IMPLODED`]: LZ77 with Shannon-Fano coding, decoded
+//!   by [`ImplodeDecoder`].
+//!
+//! Reduce and Implode streams lack an end marker, so their decoders take the
+//! entry's uncompressed size.
+//! Like every decoder, the output is unverified, so check it against the
+//! entry's CRC32 with a verifying reader.
+//!
+//! [`CompressionMethod::SHRUNK`]: crate::CompressionMethod::SHRUNK
+//! [`CompressionMethod::REDUCE1`]: crate::CompressionMethod::REDUCE1
+//! [`CompressionMethod::REDUCE4`]: crate::CompressionMethod::REDUCE4
+//! [`CompressionMethod::IMPLODED`]: crate::CompressionMethod::IMPLODED
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use rawzip::{CompressionMethod, Decompressors, ZipArchive};
+//! # use rawzip::legacy::{ImplodeDecoder, ReduceDecoder, ShrinkDecoder};
+//! # use std::io::Read;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut decompressors = Decompressors::new();
+//! decompressors.register(CompressionMethod::SHRUNK, |reader, _| {
+//!     Ok(Box::new(ShrinkDecoder::new(reader)))
+//! });
+//! for method in [
+//!     CompressionMethod::REDUCE1,
+//!     CompressionMethod::REDUCE2,
+//!     CompressionMethod::REDUCE3,
+//!     CompressionMethod::REDUCE4,
+//! ] {
+//!     decompressors.register(method, |reader, entry| {
+//!         let size = entry.uncompressed_size();
+//!         let decoder = ReduceDecoder::new(reader, entry.compression_method(), size)
+//!             .map_err(std::io::Error::other)?;
+//!         Ok(Box::new(decoder))
+//!     });
+//! }
+//! decompressors.register(CompressionMethod::IMPLODED, |reader, entry| {
+//!     let size = entry.uncompressed_size();
+//!     Ok(Box::new(ImplodeDecoder::new(reader, entry.flags(), size)))
+//! });
+//!
+//! let data = std::fs::read("assets/legacy.zip")?;
+//! let archive = ZipArchive::from_slice(&data)?;
+//! for entry in archive.entries() {
+//!     let entry = entry?;
+//!     let zip_entry = archive.get_entry(entry.wayfinder())?;
+//!     let mut output = Vec::new();
+//!     zip_entry.decoded_reader(&decompressors)?.read_to_end(&mut output)?;
+//!     assert_eq!(output.len() as u64, entry.uncompressed_size_hint());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod implode;
+mod reduce;
+mod shrink;
+
+pub use implode::ImplodeDecoder;
+pub use reduce::ReduceDecoder;
+pub use shrink::ShrinkDecoder;
+
+/// Size of the sliding window shared by Reduce and Implode, large enough for
+/// Implode's 8K dictionary.
+const WINDOW_SIZE: usize = 1 << 13;
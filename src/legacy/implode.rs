@@ -0,0 +1,252 @@
+use super::WINDOW_SIZE;
+use crate::EntryFlags;
+use crate::bits::{BitReader, Window, WindowDecoder, invalid_data};
+use std::io::{self, Read};
+
+const MAX_BITS: usize = 16;
+
+/// A [`Read`] adapter that decompresses an Implode
+/// ([`CompressionMethod::IMPLODED`]) stream.
+///
+/// Implode is LZ77 with Shannon-Fano coded lengths and distances, and
+/// optionally literals. The general purpose flags of the entry select the
+/// variant: bit 1 for an 8K instead of a 4K dictionary
+/// ([`EntryFlags::has_implode_8k_dictionary`]) and bit 2 for a third tree
+/// coding literals ([`EntryFlags::has_implode_three_trees`]).
+///
+/// [`CompressionMethod::IMPLODED`]: crate::CompressionMethod::IMPLODED
+#[derive(Debug)]
+pub struct ImplodeDecoder<R> {
+    input: BitReader<R>,
+    window: Window,
+    large_dictionary: bool,
+    literal_tree: bool,
+    trees: Option<Box<Trees>>,
+}
+
+#[derive(Debug)]
+struct Trees {
+    literals: Option<ShannonFano>,
+    lengths: ShannonFano,
+    distances: ShannonFano,
+}
+
+impl<R: Read> ImplodeDecoder<R> {
+    /// Wraps a reader of Implode compressed data.
+    ///
+    /// The variant is taken from the entry's flags, and decoding stops once
+    /// `uncompressed_size` bytes are output.
+    pub fn new(reader: R, flags: EntryFlags, uncompressed_size: u64) -> Self {
+        ImplodeDecoder {
+            input: BitReader::new(reader),
+            window: Window::new(WINDOW_SIZE, uncompressed_size),
+            large_dictionary: flags.has_implode_8k_dictionary(),
+            literal_tree: flags.has_implode_three_trees(),
+            trees: None,
+        }
+    }
+
+    /// Consumes the decoder, returning the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.input.reader
+    }
+
+    /// Reads the trees at the start of the stream: literals (if present),
+    /// then lengths, then distances.
+    fn read_trees(&mut self) -> io::Result<Box<Trees>> {
+        let literals = match self.literal_tree {
+            true => Some(ShannonFano::read(&mut self.input, 256)?),
+            false => None,
+        };
+        let lengths = ShannonFano::read(&mut self.input, 64)?;
+        let distances = ShannonFano::read(&mut self.input, 64)?;
+        Ok(Box::new(Trees {
+            literals,
+            lengths,
+            distances,
+        }))
+    }
+
+    fn decode(&mut self, trees: &Trees) -> io::Result<()> {
+        if self.input.bits(1)? == 1 {
+            let byte = match &trees.literals {
+                Some(literals) => literals.decode(&mut self.input)?,
+                None => self.input.bits(8)? as u8,
+            };
+            self.window.push(byte);
+            return Ok(());
+        }
+
+        let low_bits = if self.large_dictionary { 7 } else { 6 };
+        let low = self.input.bits(low_bits)? as usize;
+        let high = usize::from(trees.distances.decode(&mut self.input)?);
+        let distance = (high << low_bits | low) + 1;
+
+        let mut length = usize::from(trees.lengths.decode(&mut self.input)?);
+        if length == 63 {
+            length += self.input.bits(8)? as usize;
+        }
+        let min_length = if self.literal_tree { 3 } else { 2 };
+        self.window.copy(distance, length + min_length);
+        Ok(())
+    }
+}
+
+impl<R: Read> WindowDecoder for ImplodeDecoder<R> {
+    fn window(&mut self) -> &mut Window {
+        &mut self.window
+    }
+
+    fn step(&mut self) -> io::Result<bool> {
+        if self.window.is_finished() {
+            return Ok(false);
+        }
+
+        let trees = match self.trees.take() {
+            Some(trees) => trees,
+            None => self.read_trees()?,
+        };
+        let result = self.decode(&trees);
+        self.trees = Some(trees);
+        result?;
+        Ok(true)
+    }
+}
+
+impl<R: Read> Read for ImplodeDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.read_window(buf)
+    }
+}
+
+/// A Shannon-Fano tree.
+///
+/// The APPNOTE assigns codes from the longest bit length down, which makes
+/// each code the complement of the canonical Huffman code for the same bit
+/// lengths. So codes are decoded as canonical Huffman codes with every bit
+/// inverted.
+#[derive(Debug)]
+struct ShannonFano {
+    /// Number of symbols with a code of each bit length
+    counts: [u16; MAX_BITS + 1],
+
+    /// Symbols ordered by canonical Huffman code
+    symbols: [u8; 256],
+}
+
+impl ShannonFano {
+    /// Reads a tree of `size` symbols stored as a run-length encoded list of
+    /// bit lengths: a byte count less one, then bytes of a bit length less
+    /// one in the low nibble and a repeat count less one in the high nibble.
+    fn read<R: Read>(input: &mut BitReader<R>, size: usize) -> io::Result<Self> {
+        let mut lengths = [0u8; 256];
+        let mut filled = 0;
+        let runs = input.bits(8)? + 1;
+        for _ in 0..runs {
+            let run = input.bits(8)?;
+            let length = (run & 0x0f) as u8 + 1;
+            let count = (run >> 4) as usize + 1;
+            if filled + count > size {
+                return Err(invalid_data("too many implode tree bit lengths"));
+            }
+            lengths[filled..filled + count].fill(length);
+            filled += count;
+        }
+
+        if filled != size {
+            return Err(invalid_data("too few implode tree bit lengths"));
+        }
+
+        Self::build(&lengths[..size])
+    }
+
+    fn build(lengths: &[u8]) -> io::Result<Self> {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &length in lengths {
+            counts[usize::from(length)] += 1;
+        }
+
+        let mut left: i32 = 1;
+        for &count in &counts[1..] {
+            left = (left << 1) - i32::from(count);
+            if left < 0 {
+                return Err(invalid_data("over-subscribed implode tree"));
+            }
+        }
+
+        if left > 0 {
+            return Err(invalid_data("incomplete implode tree"));
+        }
+
+        let mut offsets = [0u16; MAX_BITS + 1];
+        for bits in 1..MAX_BITS {
+            offsets[bits + 1] = offsets[bits] + counts[bits];
+        }
+
+        let mut symbols = [0u8; 256];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            let offset = &mut offsets[usize::from(length)];
+            symbols[usize::from(*offset)] = symbol as u8;
+            *offset += 1;
+        }
+
+        Ok(ShannonFano { counts, symbols })
+    }
+
+    fn decode<R: Read>(&self, input: &mut BitReader<R>) -> io::Result<u8> {
+        match input.canonical_code(&self.counts, true)? {
+            Some(index) => Ok(self.symbols[index]),
+            None => Err(invalid_data("invalid implode code")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flags(bits: u16) -> EntryFlags {
+        EntryFlags::new(bits)
+    }
+
+    /// Two trees where every length and distance code is 6 bits
+    fn flat_trees() -> Vec<u8> {
+        // 64 symbols of 6 bits in 4 runs of 16
+        let tree = [3, 0xf5, 0xf5, 0xf5, 0xf5];
+        [tree, tree].concat()
+    }
+
+    #[test]
+    fn test_uncoded_literals_and_match() {
+        // Literal b'a' (1 + 8 bits), then a match: flag 0, 6 low distance
+        // bits of 0, distance code 0 and length code 2 (complemented to
+        // 111111 and 111101), for a length of 4 at a distance of 1.
+        let mut data = flat_trees();
+        let bits: u32 = 1 | u32::from(b'a') << 1 | 0b111111 << 16 | 0b101111 << 22;
+        data.extend_from_slice(&bits.to_le_bytes());
+
+        let mut decoder = ImplodeDecoder::new(&data[..], flags(0), 5);
+        let mut output = Vec::new();
+        decoder.read_to_end(&mut output).unwrap();
+        assert_eq!(output, b"aaaaa");
+    }
+
+    #[test]
+    fn test_incomplete_tree() {
+        // 63 symbols of 6 bits and one of 7 bits leave a code unused
+        let data = [4, 0xf5, 0xf5, 0xf5, 0xe5, 0x06];
+        let mut decoder = ImplodeDecoder::new(&data[..], flags(0), 1);
+        let err = decoder.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_stops_at_uncompressed_size() {
+        let mut data = flat_trees();
+        data.extend_from_slice(&[0xff; 4]);
+        let mut decoder = ImplodeDecoder::new(&data[..], flags(0), 2);
+        let mut output = Vec::new();
+        decoder.read_to_end(&mut output).unwrap();
+        assert_eq!(output, [0xff, 0xff]);
+    }
+}
@@ -0,0 +1,253 @@
+use super::WINDOW_SIZE;
+use crate::bits::{BitReader, Window, WindowDecoder, invalid_data};
+use crate::{CompressionMethod, Error, ErrorKind};
+use std::io::{self, Read};
+
+/// The byte that introduces a match in the expanded stream.
+const DLE: u8 = 144;
+const MAX_FOLLOWERS: usize = 32;
+
+/// A [`Read`] adapter that decompresses a Reduce stream
+/// ([`CompressionMethod::REDUCE1`] through [`CompressionMethod::REDUCE4`]).
+///
+/// Reduce codes each byte relative to a set of likely followers of the byte
+/// before it. The resulting bytes are then expanded, with a DLE (144) byte
+/// introducing a match whose length and distance bits are split according to
+/// the compression factor of the method.
+///
+/// [`CompressionMethod::REDUCE1`]: crate::CompressionMethod::REDUCE1
+/// [`CompressionMethod::REDUCE4`]: crate::CompressionMethod::REDUCE4
+#[derive(Debug)]
+pub struct ReduceDecoder<R> {
+    input: BitReader<R>,
+    window: Window,
+    factor: u8,
+    followers: Option<Box<[FollowerSet]>>,
+    last: u8,
+    state: State,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct FollowerSet {
+    len: u8,
+    bytes: [u8; MAX_FOLLOWERS],
+}
+
+#[derive(Debug, Clone, Copy)]
+enum State {
+    Literal,
+    Escape,
+    Length { code: u8 },
+    Distance { code: u8, length: usize },
+}
+
+impl<R: Read> ReduceDecoder<R> {
+    /// Wraps a reader of Reduce compressed data.
+    ///
+    /// The compression factor is taken from the method, and decoding stops
+    /// once `uncompressed_size` bytes are output.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::UnsupportedCompressionMethod`] if the method is
+    /// not one of the four Reduce methods.
+    pub fn new(
+        reader: R,
+        method: CompressionMethod,
+        uncompressed_size: u64,
+    ) -> Result<Self, Error> {
+        let factor = match method {
+            CompressionMethod::REDUCE1
+            | CompressionMethod::REDUCE2
+            | CompressionMethod::REDUCE3
+            | CompressionMethod::REDUCE4 => (method.as_u16() - 1) as u8,
+            _ => return Err(Error::from(ErrorKind::UnsupportedCompressionMethod(method))),
+        };
+
+        Ok(ReduceDecoder {
+            input: BitReader::new(reader),
+            window: Window::new(WINDOW_SIZE, uncompressed_size),
+            factor,
+            followers: None,
+            last: 0,
+            state: State::Literal,
+        })
+    }
+
+    /// Consumes the decoder, returning the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.input.reader
+    }
+
+    /// Reads the follower sets, which are stored from byte 255 down to 0.
+    fn read_followers(&mut self) -> io::Result<Box<[FollowerSet]>> {
+        let mut followers = vec![FollowerSet::default(); 256].into_boxed_slice();
+        for set in followers.iter_mut().rev() {
+            let len = self.input.bits(6)? as usize;
+            if len > MAX_FOLLOWERS {
+                return Err(invalid_data("reduce follower set is too large"));
+            }
+
+            for byte in &mut set.bytes[..len] {
+                *byte = self.input.bits(8)? as u8;
+            }
+            set.len = len as u8;
+        }
+
+        Ok(followers)
+    }
+
+    /// Reads the next byte of the expanded stream.
+    fn next_byte(&mut self, followers: &[FollowerSet]) -> io::Result<u8> {
+        let set = &followers[usize::from(self.last)];
+        let byte = if set.len == 0 || self.input.bits(1)? == 1 {
+            self.input.bits(8)? as u8
+        } else {
+            // The index takes as many bits as it takes to write len - 1, but
+            // at least one.
+            let bits = (u8::BITS - (set.len - 1).leading_zeros()).max(1);
+            let index = self.input.bits(bits as u8)? as usize;
+            if index >= usize::from(set.len) {
+                return Err(invalid_data("invalid reduce follower index"));
+            }
+            set.bytes[index]
+        };
+
+        self.last = byte;
+        Ok(byte)
+    }
+}
+
+impl<R: Read> WindowDecoder for ReduceDecoder<R> {
+    fn window(&mut self) -> &mut Window {
+        &mut self.window
+    }
+
+    fn step(&mut self) -> io::Result<bool> {
+        if self.window.is_finished() {
+            return Ok(false);
+        }
+
+        let followers = match self.followers.take() {
+            Some(followers) => followers,
+            None => self.read_followers()?,
+        };
+        let byte = self.next_byte(&followers);
+        self.followers = Some(followers);
+        let byte = byte?;
+
+        let length_mask = 0xff >> self.factor;
+        self.state = match self.state {
+            State::Literal if byte == DLE => State::Escape,
+            State::Literal => {
+                self.window.push(byte);
+                State::Literal
+            }
+            State::Escape if byte == 0 => {
+                self.window.push(DLE);
+                State::Literal
+            }
+            State::Escape if byte & length_mask == length_mask => State::Length { code: byte },
+            State::Escape => State::Distance {
+                code: byte,
+                length: usize::from(byte & length_mask),
+            },
+            State::Length { code } => State::Distance {
+                code,
+                length: usize::from(length_mask) + usize::from(byte),
+            },
+            State::Distance { code, length } => {
+                let high = usize::from(code >> (8 - self.factor));
+                let distance = (high << 8) + usize::from(byte) + 1;
+                self.window.copy(distance, length + 3);
+                State::Literal
+            }
+        };
+
+        Ok(true)
+    }
+}
+
+impl<R: Read> Read for ReduceDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.read_window(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    /// Writes empty follower sets, so every byte is stored as 8 bits.
+    fn reduce_bytes(bytes: &[u8]) -> Vec<u8> {
+        let mut output = vec![0u8; 256 * 6 / 8];
+        output.extend_from_slice(bytes);
+        output
+    }
+
+    #[rstest]
+    #[case::literal_dle(CompressionMethod::REDUCE1, &[b'a', DLE, 0, b'b'], b"a\x90b".as_slice())]
+    #[case::short_match(CompressionMethod::REDUCE1, &[b'a', b'b', DLE, 1, 1], b"ababab".as_slice())]
+    #[case::long_match(CompressionMethod::REDUCE4, &[b'x', DLE, 0x0f, 2, 0], &[b'x'; 21])]
+    #[case::far_match(CompressionMethod::REDUCE2, &[b'y', DLE, 0x41, 0xff], b"y\0\0\0\0".as_slice())]
+    fn test_expansion(
+        #[case] method: CompressionMethod,
+        #[case] bytes: &[u8],
+        #[case] expected: &[u8],
+    ) {
+        let data = reduce_bytes(bytes);
+        let mut decoder = ReduceDecoder::new(&data[..], method, expected.len() as u64).unwrap();
+        let mut output = Vec::new();
+        decoder.read_to_end(&mut output).unwrap();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_follower_sets() {
+        // Byte 0 is followed by {b'a', b'b'}, and b'a' by {b'a'}
+        let mut bits = Vec::new();
+        for byte in (0..=255u8).rev() {
+            let followers: &[u8] = match byte {
+                0 => b"ab",
+                b'a' => b"a",
+                _ => b"",
+            };
+            bits.extend((0..6).map(|i| (followers.len() >> i) & 1));
+            for follower in followers {
+                bits.extend((0..8).map(|i| (usize::from(*follower) >> i) & 1));
+            }
+        }
+        // From 0: index 1 of two followers (b'b'), then a literal b'a' as
+        // b'b' has no followers, then follower index 0 of one (b'a').
+        bits.extend([0, 1]);
+        bits.extend((0..8).map(|i| (usize::from(b'a') >> i) & 1));
+        bits.extend([0, 0]);
+
+        let data: Vec<u8> = bits
+            .chunks(8)
+            .map(|chunk| chunk.iter().rev().fold(0, |acc, bit| acc << 1 | *bit as u8))
+            .collect();
+        let mut decoder = ReduceDecoder::new(&data[..], CompressionMethod::REDUCE3, 3).unwrap();
+        let mut output = Vec::new();
+        decoder.read_to_end(&mut output).unwrap();
+        assert_eq!(output, b"baa");
+    }
+
+    #[test]
+    fn test_rejects_other_methods() {
+        let err = ReduceDecoder::new(&[][..], CompressionMethod::DEFLATE, 0).unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            ErrorKind::UnsupportedCompressionMethod(CompressionMethod::DEFLATE)
+        ));
+    }
+
+    #[test]
+    fn test_truncated() {
+        let data = reduce_bytes(b"ab");
+        let mut decoder = ReduceDecoder::new(&data[..], CompressionMethod::REDUCE1, 3).unwrap();
+        let err = decoder.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,268 @@
+use crate::bits::{BitReader, invalid_data};
+use std::io::{self, Read};
+
+const MIN_CODE_SIZE: u8 = 9;
+const MAX_CODE_SIZE: u8 = 13;
+const MAX_CODES: usize = 1 << MAX_CODE_SIZE;
+
+/// The code that escapes a code size increase or partial clear.
+const CONTROL_CODE: u16 = 256;
+const FIRST_FREE_CODE: usize = 257;
+
+/// A [`Read`] adapter that decompresses a Shrink ([`CompressionMethod::SHRUNK`])
+/// stream.
+///
+/// Shrink is LZW with codes growing from 9 to 13 bits. Rather than resetting
+/// a full dictionary, the encoder signals a partial clear that frees only the
+/// codes no other code builds on. The stream ends with the compressed data.
+///
+/// [`CompressionMethod::SHRUNK`]: crate::CompressionMethod::SHRUNK
+#[derive(Debug)]
+pub struct ShrinkDecoder<R> {
+    input: BitReader<R>,
+    code_size: u8,
+    nodes: Box<[Node]>,
+
+    /// The most recently assigned code, where the search for the next free
+    /// code resumes
+    last_assigned: usize,
+    previous: Option<u16>,
+
+    /// The expansion of the most recent code and how much of it has been read
+    output: Vec<u8>,
+    output_position: usize,
+    done: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    parent: u16,
+    suffix: u8,
+    free: bool,
+}
+
+impl<R: Read> ShrinkDecoder<R> {
+    /// Wraps a reader of Shrink compressed data.
+    pub fn new(reader: R) -> Self {
+        let free = Node {
+            parent: 0,
+            suffix: 0,
+            free: true,
+        };
+        ShrinkDecoder {
+            input: BitReader::new(reader),
+            code_size: MIN_CODE_SIZE,
+            nodes: vec![free; MAX_CODES].into_boxed_slice(),
+            last_assigned: FIRST_FREE_CODE - 1,
+            previous: None,
+            output: Vec::new(),
+            output_position: 0,
+            done: false,
+        }
+    }
+
+    /// Consumes the decoder, returning the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.input.reader
+    }
+
+    fn next_free(&self) -> Option<usize> {
+        (self.last_assigned + 1..MAX_CODES).find(|&code| self.nodes[code].free)
+    }
+
+    /// Frees every code that is not the prefix of another code.
+    fn partial_clear(&mut self) {
+        let mut is_parent = vec![false; MAX_CODES];
+        for node in &self.nodes[FIRST_FREE_CODE..] {
+            if !node.free {
+                is_parent[usize::from(node.parent)] = true;
+            }
+        }
+
+        for (code, node) in self.nodes.iter_mut().enumerate().skip(FIRST_FREE_CODE) {
+            if !is_parent[code] {
+                node.free = true;
+            }
+        }
+
+        self.last_assigned = FIRST_FREE_CODE - 1;
+    }
+
+    /// Appends the string of a code to the output.
+    fn expand(&mut self, code: u16) -> io::Result<()> {
+        let start = self.output.len();
+        let mut code = usize::from(code);
+        while code >= FIRST_FREE_CODE {
+            if self.output.len() - start >= MAX_CODES {
+                return Err(invalid_data("shrink code refers to itself"));
+            }
+
+            let node = self.nodes[code];
+            self.output.push(node.suffix);
+            code = usize::from(node.parent);
+        }
+
+        if code == usize::from(CONTROL_CODE) {
+            return Err(invalid_data("invalid shrink code"));
+        }
+
+        self.output.push(code as u8);
+        self.output[start..].reverse();
+        Ok(())
+    }
+
+    /// Decodes the next code, leaving its expansion in the output.
+    fn step(&mut self) -> io::Result<()> {
+        let Some(code) = self.input.try_bits(self.code_size)? else {
+            self.done = true;
+            return Ok(());
+        };
+        let code = code as u16;
+
+        if code == CONTROL_CODE {
+            let Some(control) = self.input.try_bits(self.code_size)? else {
+                self.done = true;
+                return Ok(());
+            };
+
+            match control {
+                1 if self.code_size < MAX_CODE_SIZE => self.code_size += 1,
+                1 => return Err(invalid_data("shrink code size exceeds 13 bits")),
+                2 => self.partial_clear(),
+                _ => return Err(invalid_data("invalid shrink control code")),
+            }
+            return Ok(());
+        }
+
+        self.output.clear();
+        self.output_position = 0;
+
+        let Some(previous) = self.previous else {
+            if code > 255 {
+                return Err(invalid_data("first shrink code is not a literal"));
+            }
+            self.output.push(code as u8);
+            self.previous = Some(code);
+            return Ok(());
+        };
+
+        // The encoder is a code ahead of the decoder, so it may use the code
+        // about to be assigned, which is the previous string extended by its
+        // own first byte.
+        let free = self.next_free();
+        if usize::from(code) < FIRST_FREE_CODE || !self.nodes[usize::from(code)].free {
+            self.expand(code)?;
+        } else if free == Some(usize::from(code)) {
+            self.expand(previous)?;
+            self.output.push(self.output[0]);
+        } else {
+            return Err(invalid_data("shrink code is not assigned"));
+        }
+
+        if let Some(free) = free {
+            self.nodes[free] = Node {
+                parent: previous,
+                suffix: self.output[0],
+                free: false,
+            };
+            self.last_assigned = free;
+        }
+
+        self.previous = Some(code);
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for ShrinkDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        while self.output_position == self.output.len() {
+            if self.done {
+                return Ok(0);
+            }
+            self.step()?;
+        }
+
+        let remaining = &self.output[self.output_position..];
+        let read = remaining.len().min(buf.len());
+        buf[..read].copy_from_slice(&remaining[..read]);
+        self.output_position += read;
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shrink_codes(codes: &[(u32, u8)]) -> Vec<u8> {
+        let mut output = Vec::new();
+        let (mut bits, mut count) = (0u32, 0);
+        for &(code, size) in codes {
+            bits |= code << count;
+            count += size;
+            while count >= 8 {
+                output.push(bits as u8);
+                bits >>= 8;
+                count -= 8;
+            }
+        }
+        if count > 0 {
+            output.push(bits as u8);
+        }
+        output
+    }
+
+    fn decode(data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut output = Vec::new();
+        ShrinkDecoder::new(data).read_to_end(&mut output)?;
+        Ok(output)
+    }
+
+    #[test]
+    fn test_kwkwk() {
+        // "a", then 257 before it is assigned: "aa"
+        let data = shrink_codes(&[(b'a' as u32, 9), (257, 9), (257, 9)]);
+        assert_eq!(decode(&data).unwrap(), b"aaaaa");
+    }
+
+    #[test]
+    fn test_code_size_increase() {
+        let data = shrink_codes(&[
+            (b'a' as u32, 9),
+            (256, 9),
+            (1, 9),
+            (b'b' as u32, 10),
+            (257, 10),
+        ]);
+        assert_eq!(decode(&data).unwrap(), b"abab");
+    }
+
+    #[test]
+    fn test_partial_clear() {
+        // 257 = "ab", 258 = "ba", 259 = "abc". The clear keeps 257 as the
+        // parent of 259 and frees the leaves, so "cd" is assigned 258.
+        let data = shrink_codes(&[
+            (b'a' as u32, 9),
+            (b'b' as u32, 9),
+            (257, 9),
+            (b'c' as u32, 9),
+            (256, 9),
+            (2, 9),
+            (b'd' as u32, 9),
+            (258, 9),
+            (257, 9),
+        ]);
+        assert_eq!(decode(&data).unwrap(), b"ababcdcdab");
+    }
+
+    #[test]
+    fn test_unassigned_code() {
+        let data = shrink_codes(&[(b'a' as u32, 9), (300, 9)]);
+        let err = decode(&data).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -12,11 +12,31 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+pub mod aes;
+pub mod apk;
 mod archive;
+#[cfg(any(
+    feature = "deflate64",
+    feature = "legacy-methods",
+    feature = "dcl-implode"
+))]
+mod bits;
+#[cfg(feature = "std")]
+mod codec;
 mod crc;
+#[cfg(feature = "dcl-implode")]
+pub mod dcl_implode;
+#[cfg(feature = "deflate64")]
+pub mod deflate64;
+#[cfg(feature = "std")]
+pub mod diff;
 mod errors;
 pub mod extra_fields;
+#[cfg(feature = "std")]
+pub mod extract;
 mod headers;
+#[cfg(feature = "legacy-methods")]
+pub mod legacy;
 mod locator;
 mod mode;
 pub mod path;
@@ -30,13 +50,15 @@ mod writer;
 pub mod zipcrypto;
 
 pub use archive::*;
+#[cfg(feature = "std")]
+pub use codec::{Compressors, DecoderContext, Decompressors, Encoder, EncoderSink};
 pub use crc::{Crc32, crc32};
 pub use errors::{Error, ErrorKind};
 pub use headers::EntryFlags;
 #[cfg(feature = "std")]
 pub use headers::Header;
 pub use locator::*;
-pub use mode::EntryMode;
+pub use mode::{CreatorOs, DosAttributes, EntryMode, FileType};
 #[cfg(feature = "alloc")]
 pub use path::EntryPath;
 #[cfg(feature = "std")]
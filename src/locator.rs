@@ -220,6 +220,40 @@ impl EndOfCentralDirectory {
         Ok(result)
     }
 
+    /// Size of the encoding written by [`Self::write_snapshot`].
+    #[cfg(feature = "std")]
+    pub(crate) const SNAPSHOT_SIZE: usize = 50;
+
+    /// Appends the parsed values for a snapshot of the archive.
+    #[cfg(feature = "std")]
+    pub(crate) fn write_snapshot(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.eocd_offset.to_le_bytes());
+        let zip64_eocd_offset = self.zip64_eocd_offset.map_or(0, core::num::NonZero::get);
+        out.extend_from_slice(&zip64_eocd_offset.to_le_bytes());
+        out.extend_from_slice(&self.central_dir_size.to_le_bytes());
+        out.extend_from_slice(&self.central_dir_offset.to_le_bytes());
+        out.extend_from_slice(&self.num_entries.to_le_bytes());
+        out.extend_from_slice(&self.comment_len.to_le_bytes());
+        out.extend_from_slice(&self.base_offset.to_le_bytes());
+    }
+
+    /// Restores the values written by [`Self::write_snapshot`].
+    #[cfg(feature = "std")]
+    pub(crate) fn read_snapshot(data: &[u8; Self::SNAPSHOT_SIZE]) -> Result<Self, Error> {
+        let result = EndOfCentralDirectory {
+            eocd_offset: le_u64(&data[0..8]),
+            zip64_eocd_offset: NonZeroU64::new(le_u64(&data[8..16])),
+            central_dir_size: le_u64(&data[16..24]),
+            central_dir_offset: le_u64(&data[24..32]),
+            num_entries: le_u64(&data[32..40]),
+            comment_len: le_u16(&data[40..42]),
+            base_offset: le_u64(&data[42..50]),
+        };
+
+        result.validate()?;
+        Ok(result)
+    }
+
     fn validate(&self) -> Result<(), Error> {
         // It doesn't make sense if the start of the central directory is after
         // the end.
@@ -1,7 +1,8 @@
 /// ZIP creator system constants used in version_made_by field
 pub(crate) const CREATOR_UNIX: u16 = 3;
 pub(crate) const CREATOR_MACOS: u16 = 19;
-pub(crate) const CREATOR_NTFS: u16 = 11;
+pub(crate) const CREATOR_NTFS: u16 = 11; // as written by Info-ZIP
+pub(crate) const CREATOR_NTFS_SPEC: u16 = 10; // as assigned by the spec
 pub(crate) const CREATOR_VFAT: u16 = 14;
 pub(crate) const CREATOR_FAT: u16 = 0;
 
@@ -18,12 +19,48 @@ impl EntryMode {
         Self(value)
     }
 
+    /// Creates a mode from a file type and permission bits.
+    ///
+    /// The permission bits may include the setuid, setgid, and sticky bits.
+    ///
+    /// ```rust
+    /// use rawzip::{EntryMode, FileType};
+    ///
+    /// let mode = EntryMode::from_parts(FileType::Regular, 0o4755);
+    /// assert_eq!(mode.value(), 0o104755);
+    /// assert!(mode.is_setuid());
+    /// ```
+    #[must_use]
+    pub const fn from_parts(file_type: FileType, permissions: u32) -> Self {
+        Self(file_type.mode_bits() | (permissions & 0o7777))
+    }
+
     /// Returns the raw mode value
     #[must_use]
     pub const fn value(&self) -> u32 {
         self.0
     }
 
+    /// Returns the type of file.
+    #[must_use]
+    pub const fn file_type(&self) -> FileType {
+        match self.0 & S_IFMT {
+            S_IFDIR => FileType::Directory,
+            S_IFLNK => FileType::Symlink,
+            S_IFIFO => FileType::Fifo,
+            S_IFCHR => FileType::CharDevice,
+            S_IFBLK => FileType::BlockDevice,
+            S_IFSOCK => FileType::Socket,
+            _ => FileType::Regular,
+        }
+    }
+
+    /// Returns true if this is a directory.
+    #[must_use]
+    pub const fn is_dir(&self) -> bool {
+        self.0 & S_IFMT == S_IFDIR
+    }
+
     /// Returns true if this is a symbolic link.
     #[must_use]
     pub const fn is_symlink(&self) -> bool {
@@ -35,15 +72,214 @@ impl EntryMode {
     pub const fn permissions(&self) -> u32 {
         self.0 & 0o777
     }
+
+    /// Returns true if the set-user-ID bit is set.
+    #[must_use]
+    pub const fn is_setuid(&self) -> bool {
+        self.0 & S_ISUID != 0
+    }
+
+    /// Returns true if the set-group-ID bit is set.
+    #[must_use]
+    pub const fn is_setgid(&self) -> bool {
+        self.0 & S_ISGID != 0
+    }
+
+    /// Returns true if the sticky bit is set.
+    #[must_use]
+    pub const fn is_sticky(&self) -> bool {
+        self.0 & S_ISVTX != 0
+    }
+}
+
+/// The type of file an entry represents, following the Unix file types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileType {
+    /// A regular file.
+    Regular,
+    /// A directory.
+    Directory,
+    /// A symbolic link.
+    Symlink,
+    /// A named pipe.
+    Fifo,
+    /// A character device.
+    CharDevice,
+    /// A block device.
+    BlockDevice,
+    /// A Unix domain socket.
+    Socket,
+}
+
+impl FileType {
+    const fn mode_bits(self) -> u32 {
+        match self {
+            FileType::Regular => S_IFREG,
+            FileType::Directory => S_IFDIR,
+            FileType::Symlink => S_IFLNK,
+            FileType::Fifo => S_IFIFO,
+            FileType::CharDevice => S_IFCHR,
+            FileType::BlockDevice => S_IFBLK,
+            FileType::Socket => S_IFSOCK,
+        }
+    }
+}
+
+/// The operating system of the tool that created an entry, stored in the
+/// upper byte of "version made by" (§ 4.4.2).
+///
+/// It determines how the external file attributes are interpreted. Contains
+/// associated consts for the systems mentioned in the spec.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CreatorOs(u8);
+
+impl CreatorOs {
+    /// MS-DOS and OS/2 (FAT / VFAT / FAT32 file systems).
+    pub const MSDOS: Self = Self(0);
+    pub const AMIGA: Self = Self(1);
+    pub const OPENVMS: Self = Self(2);
+    pub const UNIX: Self = Self(3);
+    pub const VM_CMS: Self = Self(4);
+    pub const ATARI_ST: Self = Self(5);
+    pub const OS2_HPFS: Self = Self(6);
+    pub const MACINTOSH: Self = Self(7);
+    pub const Z_SYSTEM: Self = Self(8);
+    pub const CPM: Self = Self(9);
+    /// Windows NTFS according to the spec. Info-ZIP writes
+    /// [`CreatorOs::MVS`] for NTFS instead, and rawzip reads both as MS-DOS
+    /// attributes.
+    pub const WINDOWS_NTFS: Self = Self(10);
+    pub const MVS: Self = Self(11);
+    pub const VSE: Self = Self(12);
+    pub const ACORN_RISC: Self = Self(13);
+    pub const VFAT: Self = Self(14);
+    pub const ALTERNATE_MVS: Self = Self(15);
+    pub const BEOS: Self = Self(16);
+    pub const TANDEM: Self = Self(17);
+    pub const OS400: Self = Self(18);
+    /// macOS, which stores Unix attributes.
+    pub const OSX: Self = Self(19);
+
+    /// Wrap a raw creator id.
+    #[inline]
+    pub const fn new(id: u8) -> Self {
+        Self(id)
+    }
+
+    /// Returns the raw value of the creator id.
+    #[inline]
+    pub const fn as_u8(self) -> u8 {
+        self.0
+    }
+
+    /// Returns the system name (eg: `"UNIX"`) when known.
+    #[inline]
+    pub const fn name(self) -> Option<&'static str> {
+        match self.0 {
+            0 => Some("MSDOS"),
+            1 => Some("AMIGA"),
+            2 => Some("OPENVMS"),
+            3 => Some("UNIX"),
+            4 => Some("VM_CMS"),
+            5 => Some("ATARI_ST"),
+            6 => Some("OS2_HPFS"),
+            7 => Some("MACINTOSH"),
+            8 => Some("Z_SYSTEM"),
+            9 => Some("CPM"),
+            10 => Some("WINDOWS_NTFS"),
+            11 => Some("MVS"),
+            12 => Some("VSE"),
+            13 => Some("ACORN_RISC"),
+            14 => Some("VFAT"),
+            15 => Some("ALTERNATE_MVS"),
+            16 => Some("BEOS"),
+            17 => Some("TANDEM"),
+            18 => Some("OS400"),
+            19 => Some("OSX"),
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Debug for CreatorOs {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "CreatorOs::{name}"),
+            None => write!(f, "CreatorOs({})", self.0),
+        }
+    }
+}
+
+impl core::fmt::Display for CreatorOs {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} ({})", self.0, self.name().unwrap_or("UNKNOWN"))
+    }
+}
+
+/// MS-DOS file attributes, stored in the low byte of the external file
+/// attributes.
+///
+/// Most tools set these regardless of the creator OS, though only the
+/// read-only and directory flags are widely meaningful outside of Windows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct DosAttributes(u8);
+
+impl DosAttributes {
+    /// The file is read-only.
+    pub const READ_ONLY: Self = Self(0x01);
+    /// The file is hidden.
+    pub const HIDDEN: Self = Self(0x02);
+    /// The file belongs to the operating system.
+    pub const SYSTEM: Self = Self(0x04);
+    /// The entry is a volume label.
+    pub const VOLUME_LABEL: Self = Self(0x08);
+    /// The entry is a directory.
+    pub const DIRECTORY: Self = Self(0x10);
+    /// The file has changed since it was last backed up.
+    pub const ARCHIVE: Self = Self(0x20);
+
+    /// Wrap raw attribute bits.
+    #[inline]
+    pub const fn new(bits: u8) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw attribute bits.
+    #[inline]
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Returns true if every flag of `other` is set.
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl core::ops::BitOr for DosAttributes {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl core::ops::BitOrAssign for DosAttributes {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
 }
 
 /// Unix file type and permission constants
-const S_IFMT: u32 = 0o170000; // File type mask
+pub(crate) const S_IFMT: u32 = 0o170000; // File type mask
 const S_IFSOCK: u32 = 0o140000; // Socket
-const S_IFLNK: u32 = 0o120000; // Symbolic link
-const S_IFREG: u32 = 0o100000; // Regular file
+pub(crate) const S_IFLNK: u32 = 0o120000; // Symbolic link
+pub(crate) const S_IFREG: u32 = 0o100000; // Regular file
 const S_IFBLK: u32 = 0o060000; // Block device
-const S_IFDIR: u32 = 0o040000; // Directory
+pub(crate) const S_IFDIR: u32 = 0o040000; // Directory
 const S_IFCHR: u32 = 0o020000; // Character device
 const S_IFIFO: u32 = 0o010000; // FIFO
 const S_ISUID: u32 = 0o004000; // Set user ID
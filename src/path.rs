@@ -51,6 +51,10 @@ Normalized paths have been validated and sanitized according to these rules:
 - Drive letters: Windows drive prefixes removed (`C:\\foo` -> `foo`)
 - Escape prevention: Paths cannot escape the archive root directory
 
+Normalization rewrites offending paths. To reject them instead, use
+[`ZipFilePath::try_normalize_strict`], which reports the offense as an
+[`UnsafePathKind`].
+
 ## Usage Examples
 
 ```rust
@@ -103,12 +107,27 @@ information is used internally when creating ZIP archives.
 )]
 
 #[cfg(feature = "alloc")]
-use crate::Error;
+mod collision;
+#[cfg(feature = "alloc")]
+mod portability;
+#[cfg(feature = "alloc")]
+mod symlink;
+#[cfg(feature = "alloc")]
+pub use collision::*;
+#[cfg(feature = "alloc")]
+pub use portability::*;
+#[cfg(feature = "alloc")]
+pub use symlink::*;
+
 use crate::ZipStr;
 #[cfg(feature = "alloc")]
+use crate::{Error, ErrorKind};
+#[cfg(feature = "alloc")]
 use alloc::borrow::Cow;
 #[cfg(feature = "alloc")]
 use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 
 /// Raw path data directly from a ZIP archive.
 ///
@@ -174,6 +193,49 @@ impl AsRef<str> for NormalizedPathBuf {
     }
 }
 
+/// The reason a path was rejected by strict normalization.
+///
+/// See [`ZipFilePath::from_str_strict`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UnsafePathKind {
+    /// A `..` component that climbs above the archive root
+    ParentTraversal,
+    /// A leading separator, like `/etc/passwd`
+    Absolute,
+    /// A drive letter prefix, like `C:`
+    DriveLetter,
+    /// A UNC or device namespace prefix, like `\\server\share`
+    UncPrefix,
+    /// A NUL byte, which truncates names in C APIs
+    NulByte,
+    /// An empty path or an empty component, like `a//b`
+    EmptyComponent,
+    /// A path that resolves to the archive root itself, like `.` or `a/..`
+    ArchiveRoot,
+    /// A colon past a drive letter, which Windows reads as an NTFS alternate
+    /// data stream, like `file.txt:hidden`, or a device, like `COM1:`
+    Colon,
+}
+
+#[cfg(feature = "alloc")]
+impl core::fmt::Display for UnsafePathKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let msg = match self {
+            UnsafePathKind::ParentTraversal => "parent traversal past the archive root",
+            UnsafePathKind::Absolute => "absolute path",
+            UnsafePathKind::DriveLetter => "drive letter",
+            UnsafePathKind::UncPrefix => "UNC prefix",
+            UnsafePathKind::NulByte => "NUL byte",
+            UnsafePathKind::EmptyComponent => "empty component",
+            UnsafePathKind::ArchiveRoot => "path resolves to the archive root",
+            UnsafePathKind::Colon => "colon in a component",
+        };
+        f.write_str(msg)
+    }
+}
+
 /// Type-safe wrapper for ZIP archive file paths.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct ZipFilePath<R> {
@@ -232,6 +294,85 @@ impl ZipFilePath<()> {
         }
     }
 
+    /// Creates a normalized path from a UTF-8 string, rejecting paths that
+    /// [`ZipFilePath::from_str`] would have to rewrite to keep them inside
+    /// the archive root.
+    ///
+    /// Backslashes are accepted as separators, and `.` components and `..`
+    /// components that stay within the root are resolved as usual. A path
+    /// must name something below the root, so `.` and `a/..` are rejected.
+    ///
+    /// ```rust
+    /// use rawzip::ErrorKind;
+    /// use rawzip::path::{UnsafePathKind, ZipFilePath};
+    ///
+    /// let path = ZipFilePath::from_str_strict("dir\\sub/../file.txt")?;
+    /// assert_eq!(path.as_str(), "dir/file.txt");
+    ///
+    /// let err = ZipFilePath::from_str_strict("../../etc/passwd").unwrap_err();
+    /// assert!(matches!(
+    ///     err.kind(),
+    ///     ErrorKind::UnsafePath(UnsafePathKind::ParentTraversal)
+    /// ));
+    /// # Ok::<(), rawzip::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::UnsafePath`]
+    /// error describing the first offense found.
+    #[cfg(feature = "alloc")]
+    pub fn from_str_strict(name: &str) -> Result<ZipFilePath<NormalizedPath<'_>>, Error> {
+        Self::check_strict(name).map_err(|kind| Error::from(ErrorKind::UnsafePath(kind)))?;
+        Ok(Self::from_str(name))
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn check_strict(name: &str) -> Result<(), UnsafePathKind> {
+        let is_separator = |c: u8| c == b'/' || c == b'\\';
+        let bytes = name.as_bytes();
+        if bytes.contains(&0) {
+            return Err(UnsafePathKind::NulByte);
+        }
+
+        match bytes {
+            [] => return Err(UnsafePathKind::EmptyComponent),
+            [a, b, ..] if is_separator(*a) && is_separator(*b) => {
+                return Err(UnsafePathKind::UncPrefix);
+            }
+            [a, ..] if is_separator(*a) => return Err(UnsafePathKind::Absolute),
+            _ => {}
+        }
+
+        match bytes {
+            [a, b':', ..] if a.is_ascii_alphabetic() => return Err(UnsafePathKind::DriveLetter),
+            _ if bytes.contains(&b':') => return Err(UnsafePathKind::Colon),
+            _ => {}
+        }
+
+        // A single trailing separator marks a directory
+        let name = name.strip_suffix(['/', '\\']).unwrap_or(name);
+        let mut depth = 0usize;
+        for component in name.split(['/', '\\']) {
+            match component {
+                "" => return Err(UnsafePathKind::EmptyComponent),
+                "." => {}
+                ".." => {
+                    depth = depth
+                        .checked_sub(1)
+                        .ok_or(UnsafePathKind::ParentTraversal)?;
+                }
+                _ => depth += 1,
+            }
+        }
+
+        if depth == 0 {
+            return Err(UnsafePathKind::ArchiveRoot);
+        }
+
+        Ok(())
+    }
+
     #[cfg(feature = "alloc")]
     fn normalize_alloc(s: &str) -> String {
         // 4.4.17.1 All slashes MUST be forward slashes '/'
@@ -295,6 +436,101 @@ where
     }
 }
 
+/// The characters for bytes `0x80..=0xFF` in code page 437. Lower bytes map
+/// to ASCII.
+#[cfg(feature = "alloc")]
+const CP437_HIGH: [char; 128] = [
+    '\u{00c7}', '\u{00fc}', '\u{00e9}', '\u{00e2}', '\u{00e4}', '\u{00e0}', '\u{00e5}', '\u{00e7}',
+    '\u{00ea}', '\u{00eb}', '\u{00e8}', '\u{00ef}', '\u{00ee}', '\u{00ec}', '\u{00c4}', '\u{00c5}',
+    '\u{00c9}', '\u{00e6}', '\u{00c6}', '\u{00f4}', '\u{00f6}', '\u{00f2}', '\u{00fb}', '\u{00f9}',
+    '\u{00ff}', '\u{00d6}', '\u{00dc}', '\u{00a2}', '\u{00a3}', '\u{00a5}', '\u{20a7}', '\u{0192}',
+    '\u{00e1}', '\u{00ed}', '\u{00f3}', '\u{00fa}', '\u{00f1}', '\u{00d1}', '\u{00aa}', '\u{00ba}',
+    '\u{00bf}', '\u{2310}', '\u{00ac}', '\u{00bd}', '\u{00bc}', '\u{00a1}', '\u{00ab}', '\u{00bb}',
+    '\u{2591}', '\u{2592}', '\u{2593}', '\u{2502}', '\u{2524}', '\u{2561}', '\u{2562}', '\u{2556}',
+    '\u{2555}', '\u{2563}', '\u{2551}', '\u{2557}', '\u{255d}', '\u{255c}', '\u{255b}', '\u{2510}',
+    '\u{2514}', '\u{2534}', '\u{252c}', '\u{251c}', '\u{2500}', '\u{253c}', '\u{255e}', '\u{255f}',
+    '\u{255a}', '\u{2554}', '\u{2569}', '\u{2566}', '\u{2560}', '\u{2550}', '\u{256c}', '\u{2567}',
+    '\u{2568}', '\u{2564}', '\u{2565}', '\u{2559}', '\u{2558}', '\u{2552}', '\u{2553}', '\u{256b}',
+    '\u{256a}', '\u{2518}', '\u{250c}', '\u{2588}', '\u{2584}', '\u{258c}', '\u{2590}', '\u{2580}',
+    '\u{03b1}', '\u{00df}', '\u{0393}', '\u{03c0}', '\u{03a3}', '\u{03c3}', '\u{00b5}', '\u{03c4}',
+    '\u{03a6}', '\u{0398}', '\u{03a9}', '\u{03b4}', '\u{221e}', '\u{03c6}', '\u{03b5}', '\u{2229}',
+    '\u{2261}', '\u{00b1}', '\u{2265}', '\u{2264}', '\u{2320}', '\u{2321}', '\u{00f7}', '\u{2248}',
+    '\u{00b0}', '\u{2219}', '\u{00b7}', '\u{221a}', '\u{207f}', '\u{00b2}', '\u{25a0}', '\u{00a0}',
+];
+
+/// Decodes bytes as code page 437, the default encoding of zip file names and
+/// comments when the UTF-8 flag is unset.
+///
+/// Borrows the input when it is entirely ASCII.
+///
+/// ```rust
+/// use rawzip::path::decode_cp437;
+///
+/// assert_eq!(decode_cp437(b"caf\x82.txt"), "café.txt");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_cp437(bytes: &[u8]) -> Cow<'_, str> {
+    if bytes.is_ascii() {
+        // ASCII is valid UTF-8
+        return Cow::Borrowed(core::str::from_utf8(bytes).unwrap_or_default());
+    }
+
+    let decoded = bytes
+        .iter()
+        .map(|&b| match b {
+            0x00..=0x7f => char::from(b),
+            _ => CP437_HIGH[usize::from(b - 0x80)],
+        })
+        .collect::<String>();
+    Cow::Owned(decoded)
+}
+
+/// Encodes text as code page 437, returning `None` if a character is not
+/// representable.
+///
+/// Borrows the input when it is entirely ASCII.
+#[cfg(feature = "alloc")]
+pub fn encode_cp437(text: &str) -> Option<Cow<'_, [u8]>> {
+    if text.is_ascii() {
+        return Some(Cow::Borrowed(text.as_bytes()));
+    }
+
+    text.chars()
+        .map(|c| match c {
+            '\0'..='\x7f' => Some(c as u8),
+            _ => CP437_HIGH
+                .iter()
+                .position(|&x| x == c)
+                .map(|i| 0x80 + i as u8),
+        })
+        .collect::<Option<Vec<u8>>>()
+        .map(Cow::Owned)
+}
+
+/// Decodes entry names and comments that are not flagged as UTF-8.
+///
+/// [`Cp437`] is the decoder the zip specification prescribes, but archives
+/// created on systems with other default code pages (like Shift-JIS) can
+/// supply their own decoder.
+#[cfg(feature = "alloc")]
+pub trait NameDecoder {
+    /// Decodes the raw bytes into text.
+    fn decode<'a>(&self, bytes: &'a [u8]) -> Cow<'a, str>;
+}
+
+/// The code page 437 [`NameDecoder`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Cp437;
+
+#[cfg(feature = "alloc")]
+impl NameDecoder for Cp437 {
+    #[inline]
+    fn decode<'a>(&self, bytes: &'a [u8]) -> Cow<'a, str> {
+        decode_cp437(bytes)
+    }
+}
+
 #[cfg(any(feature = "std", test))]
 pub(crate) fn str_needs_utf8(s: &str) -> bool {
     for ch in s.chars() {
@@ -321,6 +557,11 @@ impl<'a> ZipFilePath<RawPath<'a>> {
     /// Attempts to normalize this raw path into a safe, validated path.
     ///
     /// Validates the raw bytes as UTF-8 and applies normalization rules.
+    /// Names of entries without the UTF-8 flag may be in code page 437
+    /// instead, so prefer
+    /// [`ZipFileHeaderRecord::decoded_file_path`](crate::ZipFileHeaderRecord::decoded_file_path)
+    /// for entry names, which also falls back to the Unicode Path extra field
+    /// and code page 437.
     ///
     /// # Errors
     ///
@@ -332,6 +573,56 @@ impl<'a> ZipFilePath<RawPath<'a>> {
         let name = core::str::from_utf8(raw_data.as_bytes()).map_err(Error::utf8)?;
         Ok(ZipFilePath::from_str(name))
     }
+
+    /// Attempts to normalize this raw path, rejecting paths that would have
+    /// to be rewritten to stay inside the archive root.
+    ///
+    /// This suits tools that need to know an entry was malicious rather than
+    /// receive a cleaned name. See [`ZipFilePath::from_str_strict`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file path contains invalid UTF-8 sequences or
+    /// is unsafe.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn try_normalize_strict(self) -> Result<ZipFilePath<NormalizedPath<'a>>, Error> {
+        let raw_data = self.data.0;
+        let name = core::str::from_utf8(raw_data.as_bytes()).map_err(Error::utf8)?;
+        ZipFilePath::from_str_strict(name)
+    }
+
+    /// Decodes this raw path with the given decoder and normalizes it.
+    ///
+    /// Unlike [`ZipFilePath::try_normalize`], this does not assume UTF-8,
+    /// which is appropriate for names without the UTF-8 flag.
+    ///
+    /// ```rust
+    /// use rawzip::path::{Cp437, ZipFilePath};
+    ///
+    /// let path = ZipFilePath::from_bytes(b"dir\\r\x82sum\x82.txt").normalize_with(&Cp437);
+    /// assert_eq!(path.as_str(), "dir/résumé.txt");
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn normalize_with<D>(self, decoder: &D) -> ZipFilePath<NormalizedPath<'a>>
+    where
+        D: NameDecoder + ?Sized,
+    {
+        match decoder.decode(self.as_bytes()) {
+            Cow::Borrowed(name) => ZipFilePath::from_str(name),
+            Cow::Owned(name) => ZipFilePath::from_str(&name).into_owned().into(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> From<ZipFilePath<NormalizedPathBuf>> for ZipFilePath<NormalizedPath<'a>> {
+    #[inline]
+    fn from(path: ZipFilePath<NormalizedPathBuf>) -> Self {
+        ZipFilePath {
+            data: NormalizedPath(Cow::Owned(path.data.0)),
+        }
+    }
 }
 
 impl AsRef<[u8]> for ZipFilePath<RawPath<'_>> {
@@ -415,6 +706,151 @@ impl<'a> ZipFilePath<NormalizedPath<'a>> {
     }
 }
 
+#[cfg(feature = "alloc")]
+impl ZipFilePath<NormalizedPath<'_>> {
+    #[inline]
+    fn borrowed(path: &str) -> ZipFilePath<NormalizedPath<'_>> {
+        ZipFilePath {
+            data: NormalizedPath(Cow::Borrowed(path)),
+        }
+    }
+
+    /// Returns an iterator over the components of the path.
+    ///
+    /// ```rust
+    /// use rawzip::path::ZipFilePath;
+    ///
+    /// let path = ZipFilePath::from_str("docs/guide/intro.md");
+    /// let components = path.components().collect::<Vec<_>>();
+    /// assert_eq!(components, ["docs", "guide", "intro.md"]);
+    /// ```
+    #[inline]
+    pub fn components(&self) -> impl DoubleEndedIterator<Item = &str> + '_ {
+        self.as_str().split('/').filter(|x| !x.is_empty())
+    }
+
+    /// Returns the directory containing this path, with a trailing slash, or
+    /// `None` if the path is at the archive root.
+    ///
+    /// ```rust
+    /// use rawzip::path::ZipFilePath;
+    ///
+    /// let path = ZipFilePath::from_str("docs/guide/");
+    /// let parent = path.parent().unwrap();
+    /// assert_eq!(parent.as_str(), "docs/");
+    /// assert!(parent.parent().is_none());
+    /// ```
+    pub fn parent(&self) -> Option<ZipFilePath<NormalizedPath<'_>>> {
+        let path = self.as_str();
+        let path = path.strip_suffix('/').unwrap_or(path);
+        let end = path.rfind('/')?;
+        Some(Self::borrowed(&path[..=end]))
+    }
+
+    /// Returns the final component of the path, or `None` if the path is
+    /// empty. The name of a directory is returned without its trailing
+    /// slash.
+    #[inline]
+    pub fn file_name(&self) -> Option<&str> {
+        self.components().next_back()
+    }
+
+    /// Returns the extension of [`ZipFilePath::file_name`], following the
+    /// rules of `std::path::Path::extension`: names without a dot, or with
+    /// only a leading dot like `.hidden`, have no extension.
+    ///
+    /// ```rust
+    /// use rawzip::path::ZipFilePath;
+    ///
+    /// assert_eq!(ZipFilePath::from_str("a/archive.tar.gz").extension(), Some("gz"));
+    /// assert_eq!(ZipFilePath::from_str("a/.hidden").extension(), None);
+    /// ```
+    pub fn extension(&self) -> Option<&str> {
+        let name = self.file_name()?;
+        match name.rsplit_once('.')? {
+            ("", _) => None,
+            (_, extension) => Some(extension),
+        }
+    }
+
+    /// Returns true if the path begins with all of the components of
+    /// `prefix`. Only whole components match: `docs/a` starts with `docs`
+    /// but `docs2/a` does not.
+    pub fn starts_with<P: AsRef<str>>(&self, prefix: P) -> bool {
+        self.strip_prefix(prefix).is_some()
+    }
+
+    /// Returns the path relative to `prefix`, or `None` if the path does not
+    /// start with `prefix`. A trailing slash on the path is preserved.
+    ///
+    /// ```rust
+    /// use rawzip::path::ZipFilePath;
+    ///
+    /// let path = ZipFilePath::from_str("docs/guide/");
+    /// assert_eq!(path.strip_prefix("docs").unwrap().as_str(), "guide/");
+    /// assert_eq!(path.strip_prefix("docs/guide/").unwrap().as_str(), "");
+    /// assert!(path.strip_prefix("doc").is_none());
+    /// ```
+    pub fn strip_prefix<P: AsRef<str>>(
+        &self,
+        prefix: P,
+    ) -> Option<ZipFilePath<NormalizedPath<'_>>> {
+        let prefix = prefix.as_ref();
+        let prefix = prefix.strip_suffix('/').unwrap_or(prefix);
+        if prefix.is_empty() {
+            return Some(Self::borrowed(self.as_str()));
+        }
+
+        let rest = self.as_str().strip_prefix(prefix)?;
+        match rest.as_bytes().first() {
+            None => Some(Self::borrowed(rest)),
+            Some(b'/') if rest.len() == 1 => Some(Self::borrowed("")),
+            Some(b'/') => Some(Self::borrowed(&rest[1..])),
+            Some(_) => None,
+        }
+    }
+
+    /// Joins the path onto a native directory, with a result that is
+    /// guaranteed to stay under `root`.
+    ///
+    /// Normalization already removes traversal, absolute, and drive letter
+    /// components. In addition, components that the host would not treat as
+    /// a single plain name, and components that Windows would resolve to `.`
+    /// or `..` after stripping trailing dots and spaces (like `.. `), are
+    /// skipped.
+    ///
+    /// ```rust
+    /// use rawzip::path::ZipFilePath;
+    /// use std::path::Path;
+    ///
+    /// let path = ZipFilePath::from_str("../docs/guide.md");
+    /// let native = path.join_under(Path::new("out"));
+    /// assert_eq!(native, Path::new("out").join("docs").join("guide.md"));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn join_under(&self, root: &std::path::Path) -> std::path::PathBuf {
+        use std::path::{Component, Path};
+
+        let mut result = root.to_path_buf();
+        for component in self.components() {
+            let trimmed = component.trim_end_matches(['.', ' ']);
+            if trimmed.is_empty() || trimmed == ".." {
+                continue;
+            }
+
+            let mut native = Path::new(component).components();
+            if matches!(
+                (native.next(), native.next()),
+                (Some(Component::Normal(_)), None)
+            ) {
+                result.push(component);
+            }
+        }
+
+        result
+    }
+}
+
 #[cfg(feature = "alloc")]
 impl ZipFilePath<NormalizedPathBuf> {
     /// Returns the normalized string slice.
@@ -431,7 +867,11 @@ impl ZipFilePath<NormalizedPathBuf> {
 ///
 /// - [`EntryPath::conformant`] normalizes UTF-8 text and sets the
 ///   [`EntryFlags::is_utf8`](crate::EntryFlags::is_utf8) flag when needed.
+/// - [`EntryPath::cp437`] normalizes UTF-8 text and encodes it as code page
+///   437 without that flag when possible.
 /// - [`EntryPath::verbatim`] writes uninterpreted bytes without that flag.
+/// - [`EntryPath::verbatim_with_unicode_path`] writes uninterpreted bytes
+///   along with a UTF-8 name in an Info-ZIP Unicode Path extra field.
 ///
 /// # Examples
 ///
@@ -456,8 +896,14 @@ pub(crate) enum EntryPathInner<'a> {
     Conformant(Cow<'a, str>),
     /// UTF-8 text already normalized by the reader.
     Normalized(Cow<'a, str>),
-    /// Uninterpreted bytes without utf-8 flag.
-    Verbatim(Cow<'a, [u8]>),
+    /// Normalize UTF-8 text and encode it as CP437 when representable.
+    Cp437(Cow<'a, str>),
+    /// Uninterpreted bytes without utf-8 flag, optionally accompanied by a
+    /// UTF-8 name for an Info-ZIP Unicode Path extra field.
+    Verbatim {
+        bytes: Cow<'a, [u8]>,
+        unicode: Option<Cow<'a, str>>,
+    },
 }
 
 #[cfg(feature = "alloc")]
@@ -468,10 +914,51 @@ impl<'a> EntryPath<'a> {
         EntryPath(EntryPathInner::Conformant(path.into()))
     }
 
+    /// Creates a normalized path that is encoded as code page 437 without
+    /// the utf-8 flag when every character is representable.
+    ///
+    /// Legacy readers that ignore the utf-8 flag display CP437 names
+    /// correctly. Paths with characters outside of CP437 are written like
+    /// [`EntryPath::conformant`].
+    #[inline]
+    pub fn cp437<S: Into<Cow<'a, str>>>(path: S) -> Self {
+        EntryPath(EntryPathInner::Cp437(path.into()))
+    }
+
     /// Creates an exact, uninterpreted path without utf-8 flag.
     #[inline]
     pub fn verbatim<B: Into<Cow<'a, [u8]>>>(path: B) -> Self {
-        EntryPath(EntryPathInner::Verbatim(path.into()))
+        EntryPath(EntryPathInner::Verbatim {
+            bytes: path.into(),
+            unicode: None,
+        })
+    }
+
+    /// Creates an exact, uninterpreted path without utf-8 flag that carries
+    /// its UTF-8 name in an Info-ZIP Unicode Path extra field (0x7075).
+    ///
+    /// This is how legacy encoded names, like CP437, are written so that
+    /// readers aware of the field (see
+    /// [`ZipFileHeaderRecord::unicode_file_path`](crate::ZipFileHeaderRecord::unicode_file_path))
+    /// recover the UTF-8 name, while others see the raw bytes. The UTF-8
+    /// name is written as given.
+    ///
+    /// ```rust
+    /// use rawzip::EntryPath;
+    ///
+    /// // "café.txt" in CP437
+    /// let path = EntryPath::verbatim_with_unicode_path(b"caf\x82.txt", "café.txt");
+    /// ```
+    #[inline]
+    pub fn verbatim_with_unicode_path<B, S>(path: B, unicode: S) -> Self
+    where
+        B: Into<Cow<'a, [u8]>>,
+        S: Into<Cow<'a, str>>,
+    {
+        EntryPath(EntryPathInner::Verbatim {
+            bytes: path.into(),
+            unicode: Some(unicode.into()),
+        })
     }
 }
 
@@ -569,6 +1056,135 @@ mod tests {
         assert!(ZipFilePath::from_bytes(input).try_normalize().is_err());
     }
 
+    #[rstest]
+    #[case(b"test.txt", "test.txt")]
+    #[case(b"dir\\test.txt", "dir/test.txt")]
+    #[case(b"dir/./test.txt", "dir/test.txt")]
+    #[case(b"dir/sub/../test.txt", "dir/test.txt")]
+    #[case(b"a/b/../../test.txt", "test.txt")]
+    #[case(b"./test.txt", "test.txt")]
+    #[case(b"foo/bar/", "foo/bar/")]
+    #[case(b"dir\\", "dir/")]
+    #[case(b"x..y/", "x..y/")]
+    fn test_zip_path_strict_accepts(#[case] input: &[u8], #[case] expected: &str) {
+        let path = ZipFilePath::from_bytes(input)
+            .try_normalize_strict()
+            .unwrap();
+        assert_eq!(path.as_str(), expected);
+        assert_eq!(
+            path,
+            ZipFilePath::from_bytes(input).try_normalize().unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case(b"../test.txt", UnsafePathKind::ParentTraversal)]
+    #[case(b"dir/../../test.txt", UnsafePathKind::ParentTraversal)]
+    #[case(b"..\\..\\test.txt", UnsafePathKind::ParentTraversal)]
+    #[case(b"..", UnsafePathKind::ParentTraversal)]
+    #[case(b"/etc/passwd", UnsafePathKind::Absolute)]
+    #[case(b"\\Windows", UnsafePathKind::Absolute)]
+    #[case(b"/", UnsafePathKind::Absolute)]
+    #[case(b"C:\\hello\\test.txt", UnsafePathKind::DriveLetter)]
+    #[case(b"C:/hello/test.txt", UnsafePathKind::DriveLetter)]
+    #[case(b"C:", UnsafePathKind::DriveLetter)]
+    #[case(b"z:file.txt", UnsafePathKind::DriveLetter)]
+    #[case(b"file.txt:stream", UnsafePathKind::Colon)]
+    #[case(b"dir/file.txt::$DATA", UnsafePathKind::Colon)]
+    #[case(b"COM1:", UnsafePathKind::Colon)]
+    #[case(b"dir/C:/test.txt", UnsafePathKind::Colon)]
+    #[case(b"\\\\server\\share\\test.txt", UnsafePathKind::UncPrefix)]
+    #[case(b"\\\\?\\C:\\test.txt", UnsafePathKind::UncPrefix)]
+    #[case(b"//server/share", UnsafePathKind::UncPrefix)]
+    #[case(b"test\0.txt", UnsafePathKind::NulByte)]
+    #[case(b"/\0", UnsafePathKind::NulByte)]
+    #[case(b"dir//test.txt", UnsafePathKind::EmptyComponent)]
+    #[case(b"dir//", UnsafePathKind::EmptyComponent)]
+    #[case(b"", UnsafePathKind::EmptyComponent)]
+    #[case(b".", UnsafePathKind::ArchiveRoot)]
+    #[case(b"./", UnsafePathKind::ArchiveRoot)]
+    #[case(b"a/..", UnsafePathKind::ArchiveRoot)]
+    #[case(b"a/./..", UnsafePathKind::ArchiveRoot)]
+    #[case(b"a\\b\\..\\..\\", UnsafePathKind::ArchiveRoot)]
+    fn test_zip_path_strict_rejects(#[case] input: &[u8], #[case] expected: UnsafePathKind) {
+        let err = ZipFilePath::from_bytes(input)
+            .try_normalize_strict()
+            .unwrap_err();
+        match err.kind() {
+            ErrorKind::UnsafePath(kind) => assert_eq!(*kind, expected),
+            kind => panic!("unexpected error: {kind}"),
+        }
+    }
+
+    #[rstest]
+    #[case("a/b/c.txt", &["a", "b", "c.txt"], Some("a/b/"), Some("c.txt"), Some("txt"))]
+    #[case("a/b/", &["a", "b"], Some("a/"), Some("b"), None)]
+    #[case("c.tar.gz", &["c.tar.gz"], None, Some("c.tar.gz"), Some("gz"))]
+    #[case("dir.d/", &["dir.d"], None, Some("dir.d"), Some("d"))]
+    #[case("a/.hidden", &["a", ".hidden"], Some("a/"), Some(".hidden"), None)]
+    #[case("a/trailing.", &["a", "trailing."], Some("a/"), Some("trailing."), Some(""))]
+    #[case("", &[], None, None, None)]
+    fn test_zip_path_components(
+        #[case] path: &str,
+        #[case] components: &[&str],
+        #[case] parent: Option<&str>,
+        #[case] file_name: Option<&str>,
+        #[case] extension: Option<&str>,
+    ) {
+        let path = ZipFilePath::from_str(path);
+        assert_eq!(path.components().collect::<Vec<_>>(), components);
+        assert_eq!(path.parent().as_ref().map(|x| x.as_str()), parent);
+        assert_eq!(path.file_name(), file_name);
+        assert_eq!(path.extension(), extension);
+    }
+
+    #[rstest]
+    #[case("a/b/c", "a", Some("b/c"))]
+    #[case("a/b/c", "a/", Some("b/c"))]
+    #[case("a/b/c", "a/b", Some("c"))]
+    #[case("a/b/", "a/b", Some(""))]
+    #[case("a/b/", "a/b/", Some(""))]
+    #[case("a/b", "a/b", Some(""))]
+    #[case("a/b/", "", Some("a/b/"))]
+    #[case("ab/c", "a", None)]
+    #[case("a/b", "a/b/c", None)]
+    #[case("a/b", "b", None)]
+    fn test_zip_path_strip_prefix(
+        #[case] path: &str,
+        #[case] prefix: &str,
+        #[case] expected: Option<&str>,
+    ) {
+        let path = ZipFilePath::from_str(path);
+        let stripped = path.strip_prefix(prefix);
+        assert_eq!(stripped.as_ref().map(|x| x.as_str()), expected);
+        assert_eq!(path.starts_with(prefix), expected.is_some());
+    }
+
+    #[cfg(feature = "std")]
+    #[rstest]
+    #[case("a/b.txt", &["a", "b.txt"])]
+    #[case("../../etc/passwd", &["etc", "passwd"])]
+    #[case("a/.. /b", &["a", "b"])]
+    #[case("a/.../b. ", &["a", "b. "])]
+    #[case("dir/", &["dir"])]
+    #[case("", &[])]
+    fn test_zip_path_join_under(#[case] path: &str, #[case] expected: &[&str]) {
+        let root = std::path::Path::new("root");
+        let joined = ZipFilePath::from_str(path).join_under(root);
+        let mut expected_path = root.to_path_buf();
+        expected_path.extend(expected);
+        assert_eq!(joined, expected_path);
+        assert!(joined.starts_with(root));
+    }
+
+    #[test]
+    fn test_zip_path_strict_invalid_utf8() {
+        let err = ZipFilePath::from_bytes(b"../\xff")
+            .try_normalize_strict()
+            .unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::InvalidUtf8(_)));
+    }
+
     #[rstest]
     #[case("test.txt", false)]
     #[case("hello_world", false)]
@@ -633,4 +1249,45 @@ mod tests {
         let result = file_path_utf8(raw_path).unwrap();
         assert_eq!(result, "test/file.txt");
     }
+
+    #[rstest]
+    #[case(b"plain.txt", "plain.txt")]
+    #[case(b"caf\x82.txt", "caf\u{e9}.txt")]
+    #[case(b"\x80\x9e\xb0\xe1\xff", "\u{c7}\u{20a7}\u{2591}\u{df}\u{a0}")]
+    fn test_decode_cp437(#[case] input: &[u8], #[case] expected: &str) {
+        assert_eq!(decode_cp437(input), expected);
+        assert_eq!(encode_cp437(expected).unwrap().as_ref(), input);
+    }
+
+    #[test]
+    fn test_cp437_round_trip_all_bytes() {
+        let bytes = (0..=255u8).collect::<Vec<_>>();
+        let decoded = decode_cp437(&bytes);
+        assert_eq!(decoded.chars().count(), 256);
+        assert_eq!(encode_cp437(&decoded).unwrap().as_ref(), bytes.as_slice());
+    }
+
+    #[test]
+    fn test_encode_cp437_unrepresentable() {
+        assert_eq!(encode_cp437("\u{1f980}.txt"), None);
+        assert!(matches!(encode_cp437("ascii.txt"), Some(Cow::Borrowed(_))));
+    }
+
+    #[test]
+    fn test_normalize_with_decoder() {
+        struct Latin1;
+        impl NameDecoder for Latin1 {
+            fn decode<'a>(&self, bytes: &'a [u8]) -> Cow<'a, str> {
+                Cow::Owned(bytes.iter().map(|&b| char::from(b)).collect())
+            }
+        }
+
+        let path = ZipFilePath::from_bytes(b"../caf\xe9.txt");
+        assert_eq!(path.normalize_with(&Latin1).as_str(), "caf\u{e9}.txt");
+        assert_eq!(path.normalize_with(&Cp437).as_str(), "caf\u{398}.txt");
+
+        // Borrows when the decoder does
+        let path = ZipFilePath::from_bytes(b"dir/file.txt").normalize_with(&Cp437);
+        assert!(matches!(path.data.0, Cow::Borrowed(_)));
+    }
 }
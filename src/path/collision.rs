@@ -0,0 +1,405 @@
+//! Detection of entries that collide when extracted to case-insensitive or
+//! normalizing filesystems.
+
+mod tables;
+
+use super::{NormalizedPath, ZipFilePath};
+use crate::{Error, ZipSliceEntries};
+#[cfg(feature = "std")]
+use crate::{ReaderAt, ZipEntries};
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use tables::{COMBINING_CLASSES, DECOMPOSITIONS, FOLDING_EXCEPTIONS};
+
+/// Finds entries that would overwrite each other when extracted.
+///
+/// Names like `Readme.md` and `README.md`, or `é` written precomposed (NFC)
+/// and decomposed (NFD), are distinct in an archive but refer to the same
+/// file on case-insensitive (Windows, macOS) or normalizing (macOS)
+/// filesystems. Paths are compared by a key that is case-folded and
+/// canonically decomposed, so both spellings land in the same group.
+///
+/// File and directory conflicts are reported too: an entry `a` and an entry
+/// `a/b` cannot both be extracted, as `a` must be a file in one and a
+/// directory in the other.
+///
+/// Directory entries that differ only in case are not reported, as
+/// extracting them merges their contents rather than overwriting anything;
+/// conflicts between the files within them are still reported.
+///
+/// Keys follow Unicode's canonical caseless match: simple case folding (so
+/// final sigma `ς` matches `σ` and `Σ`) combined with full canonical
+/// decomposition, from the Unicode Character Database 14.0. Locale specific
+/// folding, like Turkish dotless i, is not applied.
+///
+/// ```rust
+/// use rawzip::path::{CollisionDetector, CollisionKind};
+/// use rawzip::{ZipArchive, ZipArchiveWriter};
+///
+/// let mut output = Vec::new();
+/// let mut archive = ZipArchiveWriter::new(&mut output);
+/// for name in ["Readme.md", "README.md", "docs", "docs/index.md"] {
+///     let (mut entry, config) = archive.new_file(name).start()?;
+///     let writer = config.wrap(&mut entry);
+///     let (_, output) = writer.finish()?;
+///     entry.finish(output)?;
+/// }
+/// archive.finish()?;
+///
+/// let archive = ZipArchive::from_slice(&output)?;
+/// let detector = CollisionDetector::from_slice_entries(archive.entries())?;
+/// let collisions = detector.collisions();
+/// assert_eq!(collisions.len(), 2);
+/// assert_eq!(collisions[0].kind(), CollisionKind::Equivalent);
+/// assert_eq!(collisions[0].paths(), ["Readme.md", "README.md"]);
+/// assert_eq!(collisions[1].kind(), CollisionKind::FileDirectory);
+/// assert_eq!(collisions[1].paths(), ["docs", "docs/index.md"]);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CollisionDetector {
+    paths: Vec<String>,
+    keys: BTreeMap<String, Vec<usize>>,
+}
+
+impl CollisionDetector {
+    /// Creates an empty detector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a detector from the entries of a reader-based archive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an entry cannot be read or its name cannot be
+    /// decoded (see
+    /// [`ZipFileHeaderRecord::decoded_file_path`](crate::ZipFileHeaderRecord::decoded_file_path)).
+    #[cfg(feature = "std")]
+    pub fn from_entries<R: ReaderAt>(mut entries: ZipEntries<'_, '_, R>) -> Result<Self, Error> {
+        let mut detector = Self::new();
+        while let Some(entry) = entries.next_entry()? {
+            detector.insert(&entry.decoded_file_path()?);
+        }
+        Ok(detector)
+    }
+
+    /// Creates a detector from the entries of a slice-based archive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an entry cannot be parsed or its name cannot be
+    /// decoded (see
+    /// [`ZipFileHeaderRecord::decoded_file_path`](crate::ZipFileHeaderRecord::decoded_file_path)).
+    pub fn from_slice_entries(mut entries: ZipSliceEntries<'_>) -> Result<Self, Error> {
+        let mut detector = Self::new();
+        while let Some(entry) = entries.next_entry()? {
+            detector.insert(&entry.decoded_file_path()?);
+        }
+        Ok(detector)
+    }
+
+    /// Records a path. Paths are reported in the order they are inserted.
+    pub fn insert(&mut self, path: &ZipFilePath<NormalizedPath<'_>>) {
+        self.insert_str(path.as_str());
+    }
+
+    fn insert_str(&mut self, path: &str) {
+        let index = self.paths.len();
+        self.paths.push(String::from(path));
+        self.keys
+            .entry(collision_key(path.trim_end_matches('/')))
+            .or_default()
+            .push(index);
+    }
+
+    /// Returns the number of recorded paths.
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Returns true if no paths have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Returns the groups of conflicting paths, ordered by their first
+    /// member.
+    pub fn collisions(&self) -> Vec<Collision> {
+        let is_dir = |&i: &usize| self.paths[i].ends_with('/');
+        let mut groups = Vec::new();
+        for (key, members) in &self.keys {
+            if members.iter().all(is_dir) {
+                continue;
+            }
+
+            // Keys sort after their prefix, so descendants of `key` are the
+            // contiguous range of keys starting with `key/`
+            let mut prefix = key.clone();
+            prefix.push('/');
+            let mut descendants = self
+                .keys
+                .range(prefix.clone()..)
+                .take_while(|(k, _)| k.starts_with(&prefix))
+                .flat_map(|(_, v)| v.iter().copied())
+                .peekable();
+
+            let kind = if descendants.peek().is_some() || members.iter().any(is_dir) {
+                CollisionKind::FileDirectory
+            } else if members.len() > 1 {
+                CollisionKind::Equivalent
+            } else {
+                continue;
+            };
+
+            let mut indices = members
+                .iter()
+                .copied()
+                .chain(descendants)
+                .collect::<Vec<_>>();
+            indices.sort_unstable();
+            groups.push((indices, kind));
+        }
+
+        groups.sort_by_key(|(indices, _)| indices[0]);
+        groups
+            .into_iter()
+            .map(|(indices, kind)| Collision {
+                kind,
+                paths: indices.into_iter().map(|i| self.paths[i].clone()).collect(),
+            })
+            .collect()
+    }
+}
+
+impl<'a> Extend<ZipFilePath<NormalizedPath<'a>>> for CollisionDetector {
+    fn extend<T: IntoIterator<Item = ZipFilePath<NormalizedPath<'a>>>>(&mut self, iter: T) {
+        for path in iter {
+            self.insert(&path);
+        }
+    }
+}
+
+/// The way paths in a [`Collision`] conflict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CollisionKind {
+    /// The paths are identical after case folding and Unicode normalization
+    Equivalent,
+    /// A file path is also used as a directory by other paths
+    FileDirectory,
+}
+
+/// A group of paths that cannot all be extracted side by side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collision {
+    kind: CollisionKind,
+    paths: Vec<String>,
+}
+
+impl Collision {
+    /// The way the paths conflict.
+    pub fn kind(&self) -> CollisionKind {
+        self.kind
+    }
+
+    /// The conflicting paths, in the order they were inserted.
+    ///
+    /// For [`CollisionKind::FileDirectory`], this includes the file and every
+    /// path that treats it as a directory.
+    pub fn paths(&self) -> &[String] {
+        &self.paths
+    }
+}
+
+/// Returns the case-folded, canonically decomposed form of a path, which is
+/// equal for paths that a case-insensitive, normalizing filesystem treats as
+/// the same.
+///
+/// This is the canonical caseless match of Unicode §3.13: the path is
+/// decomposed, case folded, and decomposed again, as folding may produce
+/// characters with decompositions of their own.
+fn collision_key(path: &str) -> String {
+    let mut result = String::with_capacity(path.len());
+    for c in path.chars() {
+        decompose(c, &mut |c| decompose(fold_case(c), &mut |c| result.push(c)));
+    }
+
+    reorder_marks(result)
+}
+
+/// Applies the simple case folding of a character.
+///
+/// Characters that fold to their lowercase form are lowercased, others are
+/// looked up in the table of exceptions. Characters whose lowercase form is
+/// more than one character, like `İ`, have no simple folding and are kept.
+fn fold_case(c: char) -> char {
+    let code = u32::from(c);
+    if let Ok(i) = FOLDING_EXCEPTIONS.binary_search_by_key(&code, |x| x[0]) {
+        return char::from_u32(FOLDING_EXCEPTIONS[i][1]).unwrap_or(c);
+    }
+
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(folded), None) => folded,
+        _ => c,
+    }
+}
+
+const HANGUL_BASE: u32 = 0xac00;
+const HANGUL_L: u32 = 0x1100;
+const HANGUL_V: u32 = 0x1161;
+const HANGUL_T: u32 = 0x11a7;
+const HANGUL_V_COUNT: u32 = 21;
+const HANGUL_T_COUNT: u32 = 28;
+const HANGUL_COUNT: u32 = 19 * HANGUL_V_COUNT * HANGUL_T_COUNT;
+
+fn decompose(c: char, emit: &mut impl FnMut(char)) {
+    let code = u32::from(c);
+    let offset = code.wrapping_sub(HANGUL_BASE);
+    if offset < HANGUL_COUNT {
+        let l = HANGUL_L + offset / (HANGUL_V_COUNT * HANGUL_T_COUNT);
+        let v = HANGUL_V + (offset % (HANGUL_V_COUNT * HANGUL_T_COUNT)) / HANGUL_T_COUNT;
+        let t = HANGUL_T + offset % HANGUL_T_COUNT;
+        for x in [l, v, t] {
+            if x != HANGUL_T {
+                emit(char::from_u32(x).unwrap_or(c));
+            }
+        }
+        return;
+    }
+
+    match DECOMPOSITIONS.binary_search_by_key(&code, |x| x[0]) {
+        Ok(i) => {
+            let [_, base, mark] = DECOMPOSITIONS[i];
+            decompose(char::from_u32(base).unwrap_or(c), emit);
+            if mark != 0 {
+                emit(char::from_u32(mark).unwrap_or(c));
+            }
+        }
+        Err(_) => emit(c),
+    }
+}
+
+/// The canonical combining class of a character, which determines the order
+/// of combining marks after decomposition. Starters have a class of zero.
+fn combining_class(c: char) -> u8 {
+    let code = u32::from(c);
+    let i = COMBINING_CLASSES.partition_point(|&(_, last, _)| last < code);
+    match COMBINING_CLASSES.get(i) {
+        Some(&(first, _, class)) if first <= code => class,
+        _ => 0,
+    }
+}
+
+/// Applies the canonical ordering algorithm: runs of combining marks are
+/// stably sorted by combining class.
+fn reorder_marks(s: String) -> String {
+    if !s.chars().any(|c| combining_class(c) != 0) {
+        return s;
+    }
+
+    let mut chars = s.chars().collect::<Vec<_>>();
+    let mut start = 0;
+    while start < chars.len() {
+        let len = chars[start..]
+            .iter()
+            .take_while(|&&c| combining_class(c) != 0)
+            .count();
+        chars[start..start + len].sort_by_key(|&c| combining_class(c));
+        start += len.max(1);
+    }
+
+    chars.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn collisions(paths: &[&str]) -> Vec<Collision> {
+        let mut detector = CollisionDetector::new();
+        for path in paths {
+            detector.insert(&ZipFilePath::from_str(path));
+        }
+        detector.collisions()
+    }
+
+    #[rstest]
+    #[case("Readme.md", "README.md")]
+    #[case("caf\u{e9}.txt", "cafe\u{301}.txt")]
+    #[case("CAF\u{c9}.txt", "cafe\u{301}.txt")]
+    #[case("\u{1ec7}", "e\u{302}\u{323}")]
+    #[case("\u{1ec7}", "e\u{323}\u{302}")]
+    #[case("\u{212b}", "\u{e5}")]
+    #[case("\u{d55c}", "\u{1112}\u{1161}\u{11ab}")]
+    #[case("\u{c9c0}", "\u{110c}\u{1175}")]
+    #[case("\u{3a9}MEGA", "\u{3c9}mega")]
+    #[case("\u{3b1}\u{3c2}", "\u{391}\u{3a3}")]
+    #[case("\u{3d1}", "\u{398}")]
+    #[case("\u{17f}", "S")]
+    #[case("\u{212a}", "k")]
+    #[case("\u{13a0}", "\u{ab70}")]
+    #[case("\u{130}", "i\u{307}")]
+    #[case("\u{958}", "\u{915}\u{93c}")]
+    #[case("\u{1d15e}", "\u{1d157}\u{1d165}")]
+    #[case("\u{f900}", "\u{8c48}")]
+    #[case("dir/a.txt", "DIR/a.txt")]
+    #[case("a.txt", "a.txt")]
+    fn test_equivalent(#[case] a: &str, #[case] b: &str) {
+        let result = collisions(&[a, "other", b]);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].kind(), CollisionKind::Equivalent);
+        assert_eq!(result[0].paths(), [a, b]);
+    }
+
+    #[rstest]
+    #[case("a.txt", "b.txt")]
+    #[case("e\u{301}", "e\u{300}")]
+    #[case("resume", "r\u{e9}sum\u{e9}")]
+    #[case("A/", "a/")]
+    #[case("\u{131}", "i")]
+    #[case("stra\u{df}e", "strasse")]
+    #[case("ab", "a/b")]
+    fn test_distinct(#[case] a: &str, #[case] b: &str) {
+        assert_eq!(collisions(&[a, b]), Vec::new());
+    }
+
+    #[test]
+    fn test_file_directory() {
+        let result = collisions(&["A/b", "x", "a", "a/c/d", "ab", "a/"]);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].kind(), CollisionKind::FileDirectory);
+        assert_eq!(result[0].paths(), ["A/b", "a", "a/c/d", "a/"]);
+
+        let result = collisions(&["dir/", "DIR"]);
+        assert_eq!(result[0].kind(), CollisionKind::FileDirectory);
+        assert_eq!(result[0].paths(), ["dir/", "DIR"]);
+    }
+
+    #[test]
+    fn test_nested_groups_in_order() {
+        let result = collisions(&["b", "B", "a/b", "a", "a/b/c"]);
+        let kinds = result.iter().map(|x| x.kind()).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                CollisionKind::Equivalent,
+                CollisionKind::FileDirectory,
+                CollisionKind::FileDirectory
+            ]
+        );
+        assert_eq!(result[0].paths(), ["b", "B"]);
+        assert_eq!(result[1].paths(), ["a/b", "a", "a/b/c"]);
+        assert_eq!(result[2].paths(), ["a/b", "a/b/c"]);
+    }
+
+    #[test]
+    fn test_tables_sorted() {
+        assert!(DECOMPOSITIONS.windows(2).all(|x| x[0][0] < x[1][0]));
+        assert!(FOLDING_EXCEPTIONS.windows(2).all(|x| x[0][0] < x[1][0]));
+        assert!(COMBINING_CLASSES.windows(2).all(|x| x[0].1 < x[1].0));
+    }
+}
@@ -0,0 +1,358 @@
+//! Target filesystem portability checks for normalized paths.
+
+use super::{NormalizedPath, ZipFilePath};
+use alloc::borrow::Cow;
+use alloc::string::String;
+
+/// Device names that Windows reserves regardless of extension.
+const WINDOWS_RESERVED: [&str; 30] = [
+    "CON", "PRN", "AUX", "NUL", "COM0", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7",
+    "COM8", "COM9", "COM¹", "COM²", "COM³", "LPT0", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6",
+    "LPT7", "LPT8", "LPT9", "LPT¹", "LPT²", "LPT³",
+];
+
+/// Describes the file name rules of a target filesystem.
+///
+/// Normalized paths are free of traversal, but their components may still
+/// be rejected or misinterpreted by the filesystem they are extracted to.
+/// Use a profile with [`ZipFilePath::validate_for`] to reject such paths or
+/// [`ZipFilePath::sanitize_for`] to rewrite them.
+///
+/// ```rust
+/// use rawzip::path::{TargetFilesystem, ZipFilePath};
+///
+/// let path = ZipFilePath::from_str("docs/NUL.txt");
+/// assert!(path.validate_for(&TargetFilesystem::posix()).is_ok());
+/// assert!(path.validate_for(&TargetFilesystem::windows()).is_err());
+///
+/// let sanitized = path.sanitize_for(&TargetFilesystem::windows());
+/// assert_eq!(sanitized.as_str(), "docs/_NUL.txt");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetFilesystem {
+    reserved_names: bool,
+    trailing_dots_and_spaces: bool,
+    control_characters: bool,
+    invalid_characters: &'static [char],
+    max_component_len: usize,
+}
+
+impl TargetFilesystem {
+    /// Windows (NTFS, FAT): reserved device names, trailing dots and spaces,
+    /// control characters, `<>:"|?*`, and components over 255 bytes.
+    pub fn windows() -> Self {
+        TargetFilesystem {
+            reserved_names: true,
+            trailing_dots_and_spaces: true,
+            control_characters: true,
+            invalid_characters: &['<', '>', ':', '"', '|', '?', '*'],
+            max_component_len: 255,
+        }
+    }
+
+    /// macOS (APFS, HFS+): `:`, NUL, and components over 255 bytes.
+    pub fn macos() -> Self {
+        TargetFilesystem {
+            reserved_names: false,
+            trailing_dots_and_spaces: false,
+            control_characters: false,
+            invalid_characters: &[':', '\0'],
+            max_component_len: 255,
+        }
+    }
+
+    /// POSIX filesystems: NUL and components over 255 bytes.
+    pub fn posix() -> Self {
+        TargetFilesystem {
+            reserved_names: false,
+            trailing_dots_and_spaces: false,
+            control_characters: false,
+            invalid_characters: &['\0'],
+            max_component_len: 255,
+        }
+    }
+
+    /// Sets the maximum length of a path component in bytes.
+    #[must_use]
+    pub fn max_component_len(mut self, len: usize) -> Self {
+        self.max_component_len = len.max(1);
+        self
+    }
+
+    fn is_invalid_char(&self, c: char) -> bool {
+        (self.control_characters && c.is_ascii_control()) || self.invalid_characters.contains(&c)
+    }
+
+    fn is_reserved(&self, component: &str) -> bool {
+        if !self.reserved_names {
+            return false;
+        }
+
+        // Windows ignores the extension and trailing spaces of device names
+        let stem = component.split('.').next().unwrap_or_default();
+        let stem = stem.trim_end_matches(' ');
+        WINDOWS_RESERVED
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(stem))
+    }
+
+    fn check_component(&self, component: &str) -> Option<PortabilityErrorKind> {
+        if let Some(c) = component.chars().find(|&c| self.is_invalid_char(c)) {
+            return Some(PortabilityErrorKind::InvalidCharacter(c));
+        }
+
+        if component.len() > self.max_component_len {
+            return Some(PortabilityErrorKind::ComponentTooLong {
+                len: component.len(),
+            });
+        }
+
+        if self.trailing_dots_and_spaces && component.ends_with(['.', ' ']) {
+            return Some(PortabilityErrorKind::TrailingDotOrSpace);
+        }
+
+        if self.is_reserved(component) {
+            return Some(PortabilityErrorKind::ReservedName);
+        }
+
+        None
+    }
+
+    /// Checks every component of the path against the target's rules.
+    pub fn validate(&self, path: &str) -> Result<(), PortabilityError> {
+        for component in path.split('/').filter(|x| !x.is_empty()) {
+            if let Some(kind) = self.check_component(component) {
+                return Err(PortabilityError {
+                    kind,
+                    component: String::from(component),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Rewrites the path so that it satisfies the target's rules.
+    ///
+    /// Invalid characters are replaced with `_`, long components are
+    /// truncated at a character boundary, trailing dots and spaces are
+    /// removed, and reserved names are prefixed with `_`. The input is
+    /// returned unchanged when it already satisfies the rules.
+    pub fn sanitize<'a>(&self, path: Cow<'a, str>) -> Cow<'a, str> {
+        if self.validate(&path).is_ok() {
+            return path;
+        }
+
+        let mut result = String::with_capacity(path.len());
+        for (i, component) in path.split('/').enumerate() {
+            if i != 0 {
+                result.push('/');
+            }
+
+            if component.is_empty() || self.check_component(component).is_none() {
+                result.push_str(component);
+            } else {
+                result.push_str(&self.sanitize_component(component));
+            }
+        }
+
+        Cow::Owned(result)
+    }
+
+    fn sanitize_component(&self, component: &str) -> String {
+        let mut result = component
+            .chars()
+            .map(|c| if self.is_invalid_char(c) { '_' } else { c })
+            .collect::<String>();
+
+        let trim = |result: &mut String| {
+            while result.len() > self.max_component_len {
+                result.pop();
+            }
+
+            if self.trailing_dots_and_spaces {
+                let len = result.trim_end_matches(['.', ' ']).len();
+                result.truncate(len);
+            }
+        };
+
+        trim(&mut result);
+        if result.is_empty() {
+            result.push('_');
+        }
+
+        if self.is_reserved(&result) {
+            result.insert(0, '_');
+            trim(&mut result);
+        }
+
+        result
+    }
+}
+
+/// The reason a path component is not portable to a target filesystem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PortabilityErrorKind {
+    /// A reserved device name, like `CON` or `NUL.txt` on Windows
+    ReservedName,
+    /// A trailing dot or space, which Windows silently strips
+    TrailingDotOrSpace,
+    /// A character the target does not allow in names
+    InvalidCharacter(char),
+    /// A component longer than the target allows
+    ComponentTooLong {
+        /// The length of the component in bytes
+        len: usize,
+    },
+}
+
+/// A path component that is not portable to a target filesystem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortabilityError {
+    kind: PortabilityErrorKind,
+    component: String,
+}
+
+impl PortabilityError {
+    /// The reason the component is not portable.
+    pub fn kind(&self) -> PortabilityErrorKind {
+        self.kind
+    }
+
+    /// The offending path component.
+    pub fn component(&self) -> &str {
+        &self.component
+    }
+}
+
+impl core::fmt::Display for PortabilityError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.kind {
+            PortabilityErrorKind::ReservedName => {
+                write!(f, "reserved name: {}", self.component)
+            }
+            PortabilityErrorKind::TrailingDotOrSpace => {
+                write!(f, "trailing dot or space: {}", self.component)
+            }
+            PortabilityErrorKind::InvalidCharacter(c) => {
+                write!(f, "invalid character {c:?}: {}", self.component)
+            }
+            PortabilityErrorKind::ComponentTooLong { len } => {
+                write!(f, "component too long ({len} bytes): {}", self.component)
+            }
+        }
+    }
+}
+
+impl core::error::Error for PortabilityError {}
+
+impl<'a> ZipFilePath<NormalizedPath<'a>> {
+    /// Checks that every component of the path is valid on the target
+    /// filesystem.
+    ///
+    /// See [`TargetFilesystem`] for an example.
+    pub fn validate_for(&self, target: &TargetFilesystem) -> Result<(), PortabilityError> {
+        target.validate(self.as_str())
+    }
+
+    /// Rewrites the path to be valid on the target filesystem.
+    ///
+    /// See [`TargetFilesystem::sanitize`] for the rewrite rules.
+    pub fn sanitize_for(self, target: &TargetFilesystem) -> ZipFilePath<NormalizedPath<'a>> {
+        ZipFilePath {
+            data: NormalizedPath(target.sanitize(self.data.0)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("CON", PortabilityErrorKind::ReservedName)]
+    #[case("dir/nul.txt", PortabilityErrorKind::ReservedName)]
+    #[case("Com1.tar.gz", PortabilityErrorKind::ReservedName)]
+    #[case("LPT\u{b2}", PortabilityErrorKind::ReservedName)]
+    #[case("aux .txt", PortabilityErrorKind::ReservedName)]
+    #[case("file.", PortabilityErrorKind::TrailingDotOrSpace)]
+    #[case("dir /file", PortabilityErrorKind::TrailingDotOrSpace)]
+    #[case("what?.txt", PortabilityErrorKind::InvalidCharacter('?'))]
+    #[case("a<b>", PortabilityErrorKind::InvalidCharacter('<'))]
+    #[case("tab\there", PortabilityErrorKind::InvalidCharacter('\t'))]
+    fn test_windows_rejects(#[case] path: &str, #[case] kind: PortabilityErrorKind) {
+        let err = TargetFilesystem::windows().validate(path).unwrap_err();
+        assert_eq!(err.kind(), kind);
+        assert!(TargetFilesystem::posix().validate(path).is_ok());
+    }
+
+    #[rstest]
+    #[case("console.txt")]
+    #[case("CONFIG")]
+    #[case("COM10")]
+    #[case("dir/file.txt")]
+    #[case("dir/")]
+    #[case(".hidden")]
+    fn test_windows_accepts(#[case] path: &str) {
+        assert!(TargetFilesystem::windows().validate(path).is_ok());
+    }
+
+    #[test]
+    fn test_component_length() {
+        let long = "a".repeat(256);
+        let err = TargetFilesystem::posix().validate(&long).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            PortabilityErrorKind::ComponentTooLong { len: 256 }
+        );
+        assert!(TargetFilesystem::posix().validate(&long[..255]).is_ok());
+
+        let target = TargetFilesystem::posix().max_component_len(8);
+        assert!(target.validate("dir/12345678").is_ok());
+        assert!(target.validate("123456789/file").is_err());
+    }
+
+    #[test]
+    fn test_macos() {
+        let target = TargetFilesystem::macos();
+        assert_eq!(
+            target.validate("a:b").unwrap_err().kind(),
+            PortabilityErrorKind::InvalidCharacter(':')
+        );
+        assert!(target.validate("CON.").is_ok());
+    }
+
+    #[rstest]
+    #[case("dir/CON", "dir/_CON")]
+    #[case("nul.txt", "_nul.txt")]
+    #[case("file. . ", "file")]
+    #[case("...", "_")]
+    #[case("a<b>c?/d*", "a_b_c_/d_")]
+    #[case("dir/", "dir/")]
+    #[case("con./x", "_con/x")]
+    fn test_windows_sanitize(#[case] path: &str, #[case] expected: &str) {
+        let target = TargetFilesystem::windows();
+        let sanitized = target.sanitize(Cow::Borrowed(path));
+        assert_eq!(sanitized, expected);
+        assert!(target.validate(&sanitized).is_ok());
+    }
+
+    #[test]
+    fn test_sanitize_truncates_on_char_boundary() {
+        let target = TargetFilesystem::posix().max_component_len(5);
+        let sanitized = target.sanitize(Cow::Borrowed("caf\u{e9}s/ok"));
+        assert_eq!(sanitized, "caf\u{e9}/ok");
+
+        let target = TargetFilesystem::windows().max_component_len(4);
+        let sanitized = target.sanitize(Cow::Borrowed("com1.x"));
+        assert!(target.validate(&sanitized).is_ok(), "{sanitized}");
+    }
+
+    #[test]
+    fn test_sanitize_borrows_valid_paths() {
+        let path = ZipFilePath::from_str("dir/file.txt").sanitize_for(&TargetFilesystem::windows());
+        assert!(matches!(path.data.0, Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,210 @@
+//! Validation and resolution of symbolic link targets.
+
+use super::{NormalizedPath, ZipFilePath};
+use crate::{Error, ErrorKind};
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+
+/// The longest symbolic link target accepted by [`SymlinkTarget`], in bytes.
+///
+/// This matches the `PATH_MAX` of Linux and comfortably exceeds that of
+/// macOS and Windows.
+pub const MAX_SYMLINK_TARGET_LEN: usize = 4096;
+
+/// The validated target of a symbolic link entry.
+///
+/// Symbolic link entries store their target as the entry's data. A target is
+/// valid when it is non-empty UTF-8 without NUL bytes and at most
+/// [`MAX_SYMLINK_TARGET_LEN`] bytes. The target is resolved lexically against
+/// the directory containing the link to determine whether it escapes the
+/// archive root.
+///
+/// ```rust
+/// use rawzip::path::{SymlinkTarget, ZipFilePath};
+///
+/// let link = ZipFilePath::from_str("docs/latest");
+/// let target = SymlinkTarget::new(&link, b"v2/readme.md")?;
+/// assert_eq!(target.resolved().unwrap().as_str(), "docs/v2/readme.md");
+///
+/// let target = SymlinkTarget::new(&link, b"../../etc/passwd")?;
+/// assert!(target.escapes_root());
+/// # Ok::<(), rawzip::Error>(())
+/// ```
+///
+/// Lexical resolution cannot see other symbolic links: if `docs/v2` were
+/// itself a link, `docs/v2/..` would refer to the parent of wherever it
+/// points. Extractors should refuse to write through links, like
+/// [`Extractor`](crate::extract::Extractor) does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymlinkTarget<'a> {
+    target: Cow<'a, str>,
+    resolved: Option<String>,
+}
+
+impl<'a> SymlinkTarget<'a> {
+    /// Validates the target data of the symbolic link entry at `link`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the target is empty, too long, not UTF-8, or
+    /// contains a NUL byte.
+    pub fn new(link: &ZipFilePath<NormalizedPath<'_>>, target: &'a [u8]) -> Result<Self, Error> {
+        let target = validate(target)?;
+        Ok(SymlinkTarget {
+            resolved: resolve(link, target),
+            target: Cow::Borrowed(target),
+        })
+    }
+
+    /// Reads and validates the target data of the symbolic link entry at
+    /// `link`, consuming at most one byte more than
+    /// [`MAX_SYMLINK_TARGET_LEN`] from the reader.
+    ///
+    /// The reader should produce decompressed data, like a
+    /// [`ZipVerifier`](crate::ZipVerifier) does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails or the target is invalid.
+    #[cfg(feature = "std")]
+    pub fn read<R>(
+        link: &ZipFilePath<NormalizedPath<'_>>,
+        reader: R,
+    ) -> Result<SymlinkTarget<'static>, Error>
+    where
+        R: std::io::Read,
+    {
+        use std::io::Read;
+
+        let mut data = Vec::new();
+        let limit = MAX_SYMLINK_TARGET_LEN as u64 + 1;
+        reader.take(limit).read_to_end(&mut data)?;
+        let target = validate(&data)?;
+        Ok(SymlinkTarget {
+            resolved: resolve(link, target),
+            target: Cow::Owned(target.to_string()),
+        })
+    }
+
+    /// Returns the target as stored in the archive.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.target
+    }
+
+    /// Returns the archive path the target refers to, or `None` if it
+    /// escapes the archive root.
+    ///
+    /// A target that resolves to the archive root itself is an empty path.
+    pub fn resolved(&self) -> Option<ZipFilePath<NormalizedPath<'_>>> {
+        let resolved = self.resolved.as_deref()?;
+        Some(ZipFilePath {
+            data: NormalizedPath(Cow::Borrowed(resolved)),
+        })
+    }
+
+    /// Returns true if the target is absolute, has a drive or device prefix,
+    /// or climbs above the archive root.
+    #[inline]
+    pub fn escapes_root(&self) -> bool {
+        self.resolved.is_none()
+    }
+}
+
+fn validate(target: &[u8]) -> Result<&str, Error> {
+    let msg = if target.is_empty() {
+        "symlink target is empty"
+    } else if target.len() > MAX_SYMLINK_TARGET_LEN {
+        "symlink target is too long"
+    } else if target.contains(&0) {
+        "symlink target contains a NUL byte"
+    } else {
+        return core::str::from_utf8(target).map_err(Error::utf8);
+    };
+
+    Err(Error::from(ErrorKind::InvalidInput {
+        msg: msg.to_string(),
+    }))
+}
+
+/// Resolves the target against the directory of the link, returning `None`
+/// if the result is outside of the archive root.
+fn resolve(link: &ZipFilePath<NormalizedPath<'_>>, target: &str) -> Option<String> {
+    if target.starts_with(['/', '\\']) || target.contains(':') {
+        return None;
+    }
+
+    let mut resolved = String::new();
+    if let Some(parent) = link.parent() {
+        resolved.push_str(parent.as_str().trim_end_matches('/'));
+    }
+
+    for component in target.split(['/', '\\']) {
+        match component {
+            "" | "." => {}
+            ".." if resolved.is_empty() => return None,
+            ".." => {
+                let end = resolved.rfind('/').unwrap_or(0);
+                resolved.truncate(end);
+            }
+            _ => {
+                if !resolved.is_empty() {
+                    resolved.push('/');
+                }
+                resolved.push_str(component);
+            }
+        }
+    }
+
+    Some(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("link", "target", Some("target"))]
+    #[case("a/b/link", "../c", Some("a/c"))]
+    #[case("a/b/link", "../../c", Some("c"))]
+    #[case("a/b/link", "./x\\y", Some("a/b/x/y"))]
+    #[case("a/link", "..", Some(""))]
+    #[case("a/link", "x/../../y", Some("y"))]
+    #[case("link", "../target", None)]
+    #[case("a/b/link", "../../../c", None)]
+    #[case("a/link", "x/../../../y", None)]
+    #[case("link", "/etc/passwd", None)]
+    #[case("link", "\\\\server\\share", None)]
+    #[case("link", "C:\\Windows", None)]
+    fn test_resolve(#[case] link: &str, #[case] target: &str, #[case] expected: Option<&str>) {
+        let link = ZipFilePath::from_str(link);
+        let target = SymlinkTarget::new(&link, target.as_bytes()).unwrap();
+        assert_eq!(target.resolved().as_ref().map(|x| x.as_str()), expected);
+        assert_eq!(target.escapes_root(), expected.is_none());
+    }
+
+    #[rstest]
+    #[case(b"")]
+    #[case(b"a\0b")]
+    #[case(b"\xff")]
+    #[case(&[b'a'; MAX_SYMLINK_TARGET_LEN + 1])]
+    fn test_invalid(#[case] target: &[u8]) {
+        let link = ZipFilePath::from_str("link");
+        assert!(SymlinkTarget::new(&link, target).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_read_is_bounded() {
+        let link = ZipFilePath::from_str("link");
+        let data = [b'a'; MAX_SYMLINK_TARGET_LEN];
+        let target = SymlinkTarget::read(&link, &data[..]).unwrap();
+        assert_eq!(target.as_str().len(), MAX_SYMLINK_TARGET_LEN);
+
+        let mut reader = std::io::repeat(b'a');
+        assert!(SymlinkTarget::read(&link, &mut reader).is_err());
+    }
+}
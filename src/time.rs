@@ -433,7 +433,7 @@ impl ZipDateTime<Utc> {
 
     /// Creates a ZipDateTime from an NTFS timestamp (100ns ticks since 1601)
     pub(crate) fn from_ntfs(ticks: u64) -> UtcDateTime {
-        let unix_seconds = (ticks / 10_000_000).saturating_sub(NTFS_EPOCH_OFFSET) as i64;
+        let unix_seconds = (ticks / 10_000_000) as i64 - NTFS_EPOCH_OFFSET as i64;
         let (year, month, day, hour, minute, second) = unix_timestamp_to_components(unix_seconds);
         let nanosecond = ((ticks % 10_000_000) * 100) as u32;
         ZipDateTime {
@@ -448,6 +448,21 @@ impl ZipDateTime<Utc> {
         }
     }
 
+    /// Converts to an NTFS timestamp (100ns ticks since 1601), saturating at
+    /// zero for earlier times and at `u64::MAX` for times past the year
+    /// 60056.
+    #[cfg(feature = "std")]
+    pub(crate) fn to_ntfs(self) -> u64 {
+        let seconds = self.to_unix().saturating_add(NTFS_EPOCH_OFFSET as i64);
+        match u64::try_from(seconds) {
+            Ok(seconds) => seconds
+                .checked_mul(10_000_000)
+                .and_then(|ticks| ticks.checked_add(u64::from(self.nanosecond / 100)))
+                .unwrap_or(u64::MAX),
+            Err(_) => 0,
+        }
+    }
+
     /// Convert to Unix timestamp (seconds since epoch).
     ///
     /// Returns the number of seconds since the Unix epoch (1970-01-01 00:00:00 UTC).
@@ -662,8 +677,13 @@ fn parse_ntfs_timestamp(data: &[u8]) -> Option<UtcDateTime> {
         return None;
     }
 
-    // Extract modification time (first 8 bytes of timestamp data)
+    // Extract modification time (first 8 bytes of timestamp data), where zero
+    // means the time is unset
     let mtime_ticks = le_u64(&data[8..16]);
+    if mtime_ticks == 0 {
+        return None;
+    }
+
     Some(UtcDateTime::from_ntfs(mtime_ticks))
 }
 
@@ -965,6 +985,19 @@ mod tests {
         assert_eq!(datetime.timezone(), TimeZone::Utc);
     }
 
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_to_ntfs_saturates() {
+        let datetime = utc_from_components(2010, 9, 5, 2, 12, 1, 500000000);
+        assert_eq!(UtcDateTime::from_ntfs(datetime.to_ntfs()), datetime);
+
+        let far_future = utc_from_components(65000, 1, 1, 0, 0, 0, 0);
+        assert_eq!(far_future.to_ntfs(), u64::MAX);
+
+        let before_1601 = utc_from_components(1500, 1, 1, 0, 0, 0, 0);
+        assert_eq!(before_1601.to_ntfs(), 0);
+    }
+
     #[test]
     fn test_to_unix_comprehensive() {
         // Test comprehensive cases including edge cases and leap years
@@ -1215,15 +1248,14 @@ mod property_tests {
 
         // Convert NTFS ticks to Unix timestamp for jiff
         // NTFS ticks are 100-nanosecond intervals since 1601-01-01
-        let unix_seconds = (ntfs_ticks / 10_000_000).saturating_sub(NTFS_EPOCH_OFFSET);
+        let unix_seconds = (ntfs_ticks / 10_000_000) as i64 - NTFS_EPOCH_OFFSET as i64;
         let nanoseconds = ((ntfs_ticks % 10_000_000) * 100) as u32;
 
-        if unix_seconds > u32::MAX as u64 {
+        if unix_seconds > u32::MAX as i64 {
             return;
         }
 
-        let Ok(jiff_timestamp) = jiff::Timestamp::new(unix_seconds as i64, nanoseconds as i32)
-        else {
+        let Ok(jiff_timestamp) = jiff::Timestamp::new(unix_seconds, nanoseconds as i32) else {
             return;
         };
 
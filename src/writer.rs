@@ -3,14 +3,19 @@ use crate::{
     END_OF_CENTRAL_DIR_LOCATOR_SIGNATURE, END_OF_CENTRAL_DIR_SIGNATURE_BYTES,
     END_OF_CENTRAL_DIR_SIGNATURE64, EntryFlags, Error, Header, ZipFileHeaderFixed,
     ZipLocalFileHeaderFixed,
+    codec::{Compressors, Encoder, EncoderSink, EntrySink},
     errors::ErrorKind,
-    extra_fields::{ExtraFieldId, ExtraFieldsContainer},
-    mode::CREATOR_UNIX,
-    path::{EntryPath, EntryPathInner, ZipFilePath, str_needs_utf8},
+    extra_fields::{ExtraFieldId, ExtraFieldValue, ExtraFieldsContainer},
+    mode::{CREATOR_UNIX, DosAttributes, EntryMode, S_IFDIR, S_IFLNK, S_IFMT, S_IFREG},
+    path::{EntryPath, EntryPathInner, ZipFilePath, encode_cp437, str_needs_utf8},
     time::{DosDateTime, UtcDateTime},
+    zipcrypto,
 };
 use std::io::{self, Write};
 
+mod tree;
+pub use tree::*;
+
 // ZIP64 constants
 const ZIP64_VERSION_NEEDED: u16 = 45; // 4.5
 const ZIP64_EOCD_SIZE: usize = 56;
@@ -28,7 +33,7 @@ const ZIP64_THRESHOLD_ENTRIES: usize = u16::MAX as usize;
 fn with_resolved_entry_path<T>(
     path: EntryPath<'_>,
     trim_trailing_slash: bool,
-    write: impl FnOnce(&[u8], bool) -> T,
+    write: impl FnOnce(&[u8], bool, Option<&str>) -> T,
 ) -> T {
     match path.0 {
         EntryPathInner::Conformant(path) => {
@@ -40,7 +45,20 @@ fn with_resolved_entry_path<T>(
                 path
             };
             let path = path.as_str();
-            write(path.as_bytes(), str_needs_utf8(path))
+            write(path.as_bytes(), str_needs_utf8(path), None)
+        }
+        EntryPathInner::Cp437(path) => {
+            let path = ZipFilePath::from_str(&path);
+            let path = if trim_trailing_slash {
+                path.trim_trailing_slash()
+            } else {
+                path
+            };
+            let path = path.as_str();
+            match encode_cp437(path) {
+                Some(encoded) => write(&encoded, false, None),
+                None => write(path.as_bytes(), str_needs_utf8(path), None),
+            }
         }
         EntryPathInner::Normalized(path) => {
             let path = if trim_trailing_slash {
@@ -48,9 +66,9 @@ fn with_resolved_entry_path<T>(
             } else {
                 &path
             };
-            write(path.as_bytes(), str_needs_utf8(path))
+            write(path.as_bytes(), str_needs_utf8(path), None)
         }
-        EntryPathInner::Verbatim(path) => write(&path, false),
+        EntryPathInner::Verbatim { bytes, unicode } => write(&bytes, false, unicode.as_deref()),
     }
 }
 
@@ -58,11 +76,18 @@ fn with_resolved_entry_path<T>(
 struct CountWriter<W> {
     writer: W,
     count: u64,
+    // When present, entry bytes are staged in memory instead of being written
+    // through so they can be reordered before the central directory is written.
+    staging: Option<Vec<u8>>,
 }
 
 impl<W> CountWriter<W> {
     fn new(writer: W, count: u64) -> Self {
-        CountWriter { writer, count }
+        CountWriter {
+            writer,
+            count,
+            staging: None,
+        }
     }
 
     fn count(&self) -> u64 {
@@ -72,7 +97,13 @@ impl<W> CountWriter<W> {
 
 impl<W: Write> Write for CountWriter<W> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        let bytes_written = self.writer.write(buf)?;
+        let bytes_written = match self.staging.as_mut() {
+            Some(staging) => {
+                staging.extend_from_slice(buf);
+                buf.len()
+            }
+            None => self.writer.write(buf)?,
+        };
         self.count += bytes_written as u64;
         Ok(bytes_written)
     }
@@ -87,6 +118,7 @@ impl<W: Write> Write for CountWriter<W> {
 pub struct ZipArchiveWriterBuilder {
     count: u64,
     capacity: usize,
+    reproducible: Option<ReproducibleOptions>,
 }
 
 impl ZipArchiveWriterBuilder {
@@ -140,14 +172,211 @@ impl ZipArchiveWriterBuilder {
         self
     }
 
+    /// Writes a reproducible archive, where the output depends only on the
+    /// entries' names, contents, and explicitly set metadata.
+    ///
+    /// See [`ReproducibleOptions`] for the normalizations that are applied.
+    ///
+    /// ```rust
+    /// use rawzip::{ReproducibleOptions, ZipArchiveWriter};
+    /// use std::io::Write;
+    ///
+    /// fn build(names: &[&str]) -> Vec<u8> {
+    ///     let mut output = Vec::new();
+    ///     let mut archive = ZipArchiveWriter::builder()
+    ///         .reproducible(ReproducibleOptions::new().sort_entries(true))
+    ///         .build(&mut output);
+    ///     for name in names {
+    ///         let (mut entry, config) = archive.new_file(*name).start().unwrap();
+    ///         let mut writer = config.wrap(&mut entry);
+    ///         writer.write_all(name.as_bytes()).unwrap();
+    ///         let (_, desc) = writer.finish().unwrap();
+    ///         entry.finish(desc).unwrap();
+    ///     }
+    ///     archive.finish().unwrap();
+    ///     output
+    /// }
+    ///
+    /// assert_eq!(build(&["a.txt", "b.txt"]), build(&["b.txt", "a.txt"]));
+    /// ```
+    pub fn reproducible(mut self, options: ReproducibleOptions) -> Self {
+        self.reproducible = Some(options);
+        self
+    }
+
     /// Builds a `ZipArchiveWriter` that writes to `writer`.
     pub fn build<W>(&self, writer: W) -> ZipArchiveWriter<W> {
+        let mut writer = CountWriter::new(writer, self.count);
+        if self.reproducible.as_ref().is_some_and(|x| x.sort_entries) {
+            writer.staging = Some(Vec::new());
+        }
+
         ZipArchiveWriter {
-            writer: CountWriter::new(writer, self.count),
+            writer,
             files: Vec::with_capacity(self.capacity),
             file_names: Vec::new(),
             file_comments: Vec::new(),
             archive_comment: Vec::new(),
+            apk_signing_block: Vec::new(),
+            reproducible: self.reproducible.clone(),
+        }
+    }
+}
+
+/// How modification times are rewritten in a reproducible archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum ReproducibleTime {
+    /// Keep the modification times as given.
+    #[default]
+    Preserve,
+    /// Replace modification times later than the given time.
+    Clamp(UtcDateTime),
+    /// Replace every modification time, including unset ones.
+    Fixed(UtcDateTime),
+}
+
+/// Options for writing reproducible (bit-for-bit deterministic) archives.
+///
+/// Pass to [`ZipArchiveWriterBuilder::reproducible`]. By default:
+///
+/// - Unix permissions are normalized to `0644` for files and `0755` for
+///   directories and files with any execute bit set. Entries without
+///   permissions receive the normalized defaults.
+/// - User supplied extra fields that record host specific data (NTFS
+///   timestamps, Extended Timestamps, Info-ZIP Unix ownership) are dropped.
+///   The Extended Timestamp that rawzip writes from
+///   [`ZipFileBuilder::last_modified`] is kept.
+/// - Modification times are kept as given. Use
+///   [`ReproducibleOptions::clamp_mtime`] or
+///   [`ReproducibleOptions::fixed_mtime`] to rewrite them.
+/// - Entries are written in the order they are added.
+///
+/// Enabling [`ReproducibleOptions::sort_entries`] guarantees identical output
+/// for identical entries, whatever order they were added in.
+#[derive(Debug, Clone)]
+pub struct ReproducibleOptions {
+    time: ReproducibleTime,
+    normalize_permissions: bool,
+    strip_extra_fields: bool,
+    sort_entries: bool,
+}
+
+impl Default for ReproducibleOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReproducibleOptions {
+    /// Creates options with the default normalizations.
+    pub fn new() -> Self {
+        ReproducibleOptions {
+            time: ReproducibleTime::Preserve,
+            normalize_permissions: true,
+            strip_extra_fields: true,
+            sort_entries: false,
+        }
+    }
+
+    /// Reads the `SOURCE_DATE_EPOCH` environment variable as defined by
+    /// [reproducible-builds.org](https://reproducible-builds.org/specs/source-date-epoch/).
+    ///
+    /// Returns `None` when the variable is unset or is not an integer number
+    /// of seconds since the Unix epoch. Typically passed to
+    /// [`ReproducibleOptions::clamp_mtime`].
+    pub fn source_date_epoch() -> Option<UtcDateTime> {
+        let value = std::env::var("SOURCE_DATE_EPOCH").ok()?;
+        let seconds = value.trim().parse::<i64>().ok()?;
+        Some(UtcDateTime::from_unix(seconds))
+    }
+
+    /// Replaces modification times that are later than `time` with `time`.
+    ///
+    /// Entries without a modification time are left without one.
+    #[must_use]
+    pub fn clamp_mtime(mut self, time: UtcDateTime) -> Self {
+        self.time = ReproducibleTime::Clamp(time);
+        self
+    }
+
+    /// Sets every entry's modification time to `time`, including entries
+    /// that did not set one.
+    #[must_use]
+    pub fn fixed_mtime(mut self, time: UtcDateTime) -> Self {
+        self.time = ReproducibleTime::Fixed(time);
+        self
+    }
+
+    /// Controls whether Unix permissions are normalized and MS-DOS attributes
+    /// cleared (default: true).
+    #[must_use]
+    pub fn normalize_permissions(mut self, normalize: bool) -> Self {
+        self.normalize_permissions = normalize;
+        self
+    }
+
+    /// Controls whether extra fields that record host specific data are
+    /// dropped (default: true).
+    #[must_use]
+    pub fn strip_extra_fields(mut self, strip: bool) -> Self {
+        self.strip_extra_fields = strip;
+        self
+    }
+
+    /// Controls whether entries are sorted by name (default: false).
+    ///
+    /// Sorting requires buffering every entry in memory until
+    /// [`ZipArchiveWriter::finish`], where the local entries and the central
+    /// directory are written out in name order. Offsets reported by
+    /// [`ZipArchiveWriter::stream_offset`] reflect the order entries were
+    /// added, not their final position.
+    #[must_use]
+    pub fn sort_entries(mut self, sort: bool) -> Self {
+        self.sort_entries = sort;
+        self
+    }
+
+    /// Rewrites the entry options according to the configured normalizations.
+    fn apply(&self, options: &mut ZipEntryOptions, is_dir: bool) {
+        let rewrite = |time: Option<UtcDateTime>| match self.time {
+            ReproducibleTime::Preserve => time,
+            ReproducibleTime::Clamp(max) => time.map(|x| x.min(max)),
+            ReproducibleTime::Fixed(fixed) => time.map(|_| fixed),
+        };
+        options.modification_time = match self.time {
+            ReproducibleTime::Fixed(time) => Some(time),
+            _ => rewrite(options.modification_time),
+        };
+        options.access_time = rewrite(options.access_time);
+        options.creation_time = rewrite(options.creation_time);
+
+        if self.normalize_permissions {
+            let mode = options.unix_permissions.unwrap_or(0);
+            let normalized = if is_dir || mode & S_IFMT == S_IFDIR {
+                S_IFDIR | 0o755
+            } else if mode & S_IFMT == S_IFLNK {
+                S_IFLNK | 0o777
+            } else if mode & 0o111 != 0 {
+                S_IFREG | 0o755
+            } else {
+                S_IFREG | 0o644
+            };
+            options.unix_permissions = Some(normalized);
+            options.dos_attributes = DosAttributes::default();
+        }
+
+        if self.strip_extra_fields {
+            options.extra_fields.retain(|id| {
+                !matches!(
+                    id,
+                    ExtraFieldId::NTFS
+                        | ExtraFieldId::UNIX
+                        | ExtraFieldId::EXTENDED_TIMESTAMP
+                        | ExtraFieldId::INFO_ZIP_UNIX_ORIGINAL
+                        | ExtraFieldId::INFO_ZIP_UNIX
+                        | ExtraFieldId::INFO_ZIP_UNIX_UID_GID
+                )
+            });
         }
     }
 }
@@ -184,7 +413,9 @@ pub struct ZipArchiveWriter<W> {
     file_names: Vec<u8>,
     file_comments: Vec<u8>,
     archive_comment: Vec<u8>,
+    apk_signing_block: Vec<u8>,
     writer: CountWriter<W>,
+    reproducible: Option<ReproducibleOptions>,
 }
 
 impl ZipArchiveWriter<()> {
@@ -210,6 +441,29 @@ impl<W> ZipArchiveWriter<W> {
         self.archive_comment = comment.into();
     }
 
+    /// Sets an APK Signing Block to write between the last entry and the
+    /// central directory.
+    ///
+    /// This carries the block of an APK over when its entries are copied
+    /// into a new archive, as read from
+    /// [`ZipSliceArchive::apk_signing_block`](crate::ZipSliceArchive::apk_signing_block).
+    /// The v2 and later signatures cover the entries and the central
+    /// directory, so the block only remains valid if those are reproduced
+    /// byte for byte. Otherwise the APK must be re-signed.
+    ///
+    /// Returns an error if `block` is not a well-formed signing block.
+    pub fn set_apk_signing_block(&mut self, block: impl Into<Vec<u8>>) -> Result<(), Error> {
+        let block = block.into();
+        if crate::apk::SigningBlock::new(&block, 0).is_none() {
+            return Err(Error::from(ErrorKind::InvalidInput {
+                msg: "malformed APK signing block".to_string(),
+            }));
+        }
+
+        self.apk_signing_block = block;
+        Ok(())
+    }
+
     /// Returns the current offset in the output stream.
     ///
     /// Analogous to [`std::io::Cursor::position`].
@@ -296,11 +550,22 @@ pub struct ZipFileBuilder<'archive, 'path, W> {
     path: EntryPath<'path>,
     compression_method: CompressionMethod,
     modification_time: Option<UtcDateTime>,
+    access_time: Option<UtcDateTime>,
+    creation_time: Option<UtcDateTime>,
     unix_permissions: Option<u32>,
+    dos_attributes: DosAttributes,
     extra_fields: ExtraFieldsContainer,
     crc32_option: Crc32Option,
     file_comment: Vec<u8>,
     encrypted: bool,
+    zipcrypto: Option<ZipCryptoHeader>,
+}
+
+/// The cipher and random header bytes of a ZipCrypto entry.
+#[derive(Debug)]
+struct ZipCryptoHeader {
+    cipher: zipcrypto::Cipher,
+    header_random: [u8; zipcrypto::HEADER_LEN - 1],
 }
 
 impl<'archive, W> ZipFileBuilder<'archive, '_, W>
@@ -325,6 +590,31 @@ where
         self
     }
 
+    /// Sets the last access time for the file entry.
+    ///
+    /// Access and creation times are written to the local header's Extended
+    /// Timestamp field. When [`ZipFileBuilder::last_modified`] is set and any
+    /// time has sub-second precision or falls outside the Extended Timestamp's
+    /// range (1970 to 2106), an NTFS extra field is written as well. Unset
+    /// access or creation times in the NTFS field default to the
+    /// modification time.
+    #[must_use]
+    #[inline]
+    pub fn last_accessed(mut self, access_time: UtcDateTime) -> Self {
+        self.access_time = Some(access_time);
+        self
+    }
+
+    /// Sets the creation time for the file entry.
+    ///
+    /// See [`ZipFileBuilder::last_accessed`] for how the time is written.
+    #[must_use]
+    #[inline]
+    pub fn created(mut self, creation_time: UtcDateTime) -> Self {
+        self.creation_time = Some(creation_time);
+        self
+    }
+
     /// Sets the Unix permissions for the file entry.
     ///
     /// Accepts either:
@@ -341,6 +631,41 @@ where
         self
     }
 
+    /// Sets the Unix mode for the file entry from an [`EntryMode`].
+    ///
+    /// Equivalent to [`unix_permissions`](Self::unix_permissions) with the
+    /// mode's raw value.
+    ///
+    /// ```rust
+    /// # let mut output = std::io::Cursor::new(Vec::new());
+    /// # let mut archive = rawzip::ZipArchiveWriter::new(&mut output);
+    /// use rawzip::{EntryMode, FileType};
+    ///
+    /// let (mut entry, config) = archive
+    ///     .new_file("bin/tool")
+    ///     .mode(EntryMode::from_parts(FileType::Regular, 0o755))
+    ///     .start()?;
+    /// # let (_, descriptor) = config.wrap(&mut entry).finish()?;
+    /// # entry.finish(descriptor)?;
+    /// # Ok::<(), rawzip::Error>(())
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn mode(self, mode: EntryMode) -> Self {
+        self.unix_permissions(mode.value())
+    }
+
+    /// Sets the MS-DOS attributes for the file entry.
+    ///
+    /// They are stored in the low byte of the external file attributes, next
+    /// to any Unix permissions.
+    #[must_use]
+    #[inline]
+    pub fn dos_attributes(mut self, attributes: DosAttributes) -> Self {
+        self.dos_attributes = attributes;
+        self
+    }
+
     /// Adds an extra field to this file entry.
     ///
     /// Extra fields contain additional metadata about files in ZIP archives,
@@ -352,9 +677,13 @@ where
     /// Will return an error if the total size exceeds 65,535 bytes for the
     /// specified headers.
     ///
-    /// Rawzip will automatically add extra fields:
+    /// Rawzip will automatically add the extra fields below, and rejects a
+    /// field with the same id when creating the entry:
     ///
-    /// - `EXTENDED_TIMESTAMP` when `last_modified()` is set
+    /// - `EXTENDED_TIMESTAMP` when `last_modified()`, `last_accessed()`, or
+    ///   `created()` is set and the times are representable
+    /// - `NTFS` when `last_modified()` is set and a time has sub-second
+    ///   precision or is outside the Extended Timestamp range
     /// - `ZIP64` when 32-bit thresholds are met
     ///
     /// # Examples
@@ -442,13 +771,46 @@ where
         Ok(self)
     }
 
+    /// Encodes and adds a well-known extra field to this file entry.
+    ///
+    /// The field is placed in the local and central headers according to
+    /// its specification. See [`ExtraFieldValue`] for the supported fields.
+    ///
+    /// ```rust
+    /// # use std::io::{Cursor, Write};
+    /// # use rawzip::{ZipArchive, ZipArchiveWriter, extra_fields::{ExtraField, ExtraFieldValue}};
+    /// let mut output = Cursor::new(Vec::new());
+    /// let mut archive = ZipArchiveWriter::new(&mut output);
+    /// let (mut entry, config) = archive.new_file("file.txt")
+    ///     .typed_extra_field(ExtraFieldValue::UnixUidGid { uid: 1000, gid: 100 })?
+    ///     .start()?;
+    /// let mut writer = config.wrap(&mut entry);
+    /// writer.write_all(b"Hello")?;
+    /// let (_, desc) = writer.finish()?;
+    /// entry.finish(desc)?;
+    /// archive.finish()?;
+    ///
+    /// let zip_data = output.into_inner();
+    /// let archive = ZipArchive::from_slice(&zip_data)?;
+    /// let entry = archive.entries().next_entry()?.unwrap();
+    /// let Some(ExtraField::UnixUidGid(ids)) = entry.extra_fields().typed().next() else {
+    ///     panic!("expected uid/gid field");
+    /// };
+    /// assert_eq!((ids.uid(), ids.gid()), (Some(1000), Some(100)));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn typed_extra_field(mut self, field: ExtraFieldValue<'_>) -> Result<Self, Error> {
+        field.encode(&mut self.extra_fields)?;
+        Ok(self)
+    }
+
     /// Marks the file entry as encrypted by setting file headers.
     ///
     /// rawzip **DOES NOT** perform encryption itself.
     ///
-    /// The caller remains responsible for writing the encrypted payload (for
-    /// WinZip AES: the salt, password verifier, ciphertext, and authentication
-    /// code) and for any associated extra fields.
+    /// The caller remains responsible for writing the encrypted payload and
+    /// for any associated extra fields. Prefer [`zipcrypto`](Self::zipcrypto)
+    /// or, for WinZip AES, [`aes`](Self::aes), which take care of the headers.
     #[must_use]
     #[inline]
     pub fn encrypted(mut self, encrypted: bool) -> Self {
@@ -456,6 +818,85 @@ where
         self
     }
 
+    /// Sets up the headers of a WinZip AES entry: the [`CompressionMethod::AES`]
+    /// method, the encrypted flag, the AES extra field in both headers, and
+    /// the CRC32 option of the AES version.
+    ///
+    /// Use this in place of [`compression_method`](Self::compression_method),
+    /// as the real method is recorded in the extra field. rawzip does not
+    /// compress or encrypt the data, so write it through an
+    /// [`aes::Encryptor`](crate::aes::Encryptor) wrapped in an encoder for
+    /// [`Parameters::compression_method`](crate::aes::Parameters::compression_method).
+    ///
+    /// An entry cannot be both AES and [`zipcrypto`](Self::zipcrypto)
+    /// encrypted, so starting one with both fails.
+    pub fn aes(self, parameters: &crate::aes::Parameters) -> Result<Self, Error> {
+        self.compression_method(CompressionMethod::AES)
+            .encrypted(true)
+            .crc32(parameters.version.crc32_option())
+            .typed_extra_field(parameters.extra_field())
+    }
+
+    /// Encrypts the file entry with traditional PKWARE encryption
+    /// ("ZipCrypto").
+    ///
+    /// This marks the entry as encrypted and has the [`ZipEntryWriter`] write
+    /// the 12-byte encryption header, with the check byte taken from
+    /// [`ZipEntryWriter::last_modified_dos`], and encrypt everything written
+    /// to it afterwards. So wrap the entry in a compressor as usual; the
+    /// header counts towards the compressed size.
+    ///
+    /// `rng` fills the 11 random header bytes. Reusing them across entries
+    /// under the same password leaks information, so draw them from a
+    /// cryptographic RNG. ZipCrypto is weak, so prefer WinZip AES (see
+    /// [`aes`](Self::aes)) for confidentiality. Combining the two makes
+    /// starting the entry fail with [`ErrorKind::InvalidInput`].
+    ///
+    /// ```rust
+    /// # use std::io::{Read, Write};
+    /// # use rawzip::{CompressionMethod, ZipArchive, ZipArchiveWriter};
+    /// # use rawzip::zipcrypto::Decryptor;
+    /// let mut output = std::io::Cursor::new(Vec::new());
+    /// let mut archive = ZipArchiveWriter::new(&mut output);
+    /// let (mut entry, config) = archive
+    ///     .new_file("secret.txt")
+    ///     .compression_method(CompressionMethod::DEFLATE)
+    ///     // In production, fill from a cryptographic RNG
+    ///     .zipcrypto(b"hunter2", |bytes| bytes.fill(0x5a))
+    ///     .start()?;
+    /// let encoder = flate2::write::DeflateEncoder::new(&mut entry, flate2::Compression::default());
+    /// let mut writer = config.wrap(encoder);
+    /// writer.write_all(b"Hello")?;
+    /// let (encoder, descriptor) = writer.finish()?;
+    /// encoder.finish()?;
+    /// entry.finish(descriptor)?;
+    /// archive.finish()?;
+    ///
+    /// let data = output.into_inner();
+    /// let archive = ZipArchive::from_slice(&data)?;
+    /// let entry = archive.entries().next_entry()?.unwrap();
+    /// let zip_entry = archive.get_entry(entry.wayfinder())?;
+    /// let local_header = zip_entry.local_header();
+    /// let decryptor = Decryptor::new_checked(zip_entry.data(), b"hunter2", &local_header)?;
+    /// let mut contents = String::new();
+    /// zip_entry
+    ///     .verifying_reader(flate2::read::DeflateDecoder::new(decryptor))
+    ///     .read_to_string(&mut contents)?;
+    /// assert_eq!(contents, "Hello");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn zipcrypto(mut self, password: &[u8], rng: impl FnOnce(&mut [u8])) -> Self {
+        let mut header_random = [0u8; zipcrypto::HEADER_LEN - 1];
+        rng(&mut header_random);
+        self.zipcrypto = Some(ZipCryptoHeader {
+            cipher: zipcrypto::Cipher::new(password),
+            header_random,
+        });
+        self.encrypted = true;
+        self
+    }
+
     /// Sets the CRC32 calculation option for the file entry.
     ///
     /// By default, CRC32 is calculated automatically from the data. Use this
@@ -519,21 +960,138 @@ where
     /// # archive.finish().unwrap();
     /// ```
     pub fn start(self) -> Result<(ZipEntryWriter<'archive, W>, ZipDataWriterConfig), Error> {
+        self.start_entry(false)
+    }
+
+    /// Rejects an entry set up for both ZipCrypto and WinZip AES.
+    fn check_encryption(&self) -> Result<(), Error> {
+        if self.zipcrypto.is_some() && self.compression_method == CompressionMethod::AES {
+            return Err(Error::from(ErrorKind::InvalidInput {
+                msg: "zipcrypto cannot be combined with winzip aes".to_string(),
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Starts the entry, holding back the local header until the first write
+    /// when `deferred` is set.
+    fn start_entry(
+        self,
+        deferred: bool,
+    ) -> Result<(ZipEntryWriter<'archive, W>, ZipDataWriterConfig), Error> {
+        self.check_encryption()?;
         let crc32_option = self.crc32_option;
         let options = ZipEntryOptions {
             compression_method: self.compression_method,
             modification_time: self.modification_time,
+            access_time: self.access_time,
+            creation_time: self.creation_time,
             unix_permissions: self.unix_permissions,
+            dos_attributes: self.dos_attributes,
             extra_fields: self.extra_fields,
             file_comment: self.file_comment,
-            encrypted: self.encrypted,
+            encrypted: self.encrypted || self.zipcrypto.is_some(),
         };
-        let entry_writer = self.archive.new_file_with_options(self.path, options)?;
+        let mut entry_writer = self
+            .archive
+            .new_file_with_options(self.path, options, deferred)?;
+        if let Some(zipcrypto) = self.zipcrypto {
+            entry_writer.start_zipcrypto(zipcrypto)?;
+        }
 
         let data_writer_config = ZipDataWriterConfig { crc32_option };
 
         Ok((entry_writer, data_writer_config))
     }
+
+    /// Mark the start of file data, compressing it with the compressor
+    /// registered for the entry's compression method.
+    ///
+    /// Unlike [`start`](Self::start), the returned writer accepts
+    /// uncompressed data and takes care of compressing it, computing the
+    /// CRC32, and finishing the compressed stream before the entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::UnsupportedCompressionMethod`] if no compressor is
+    /// registered for the method. Encrypted entries are rejected unless
+    /// encrypted by [`zipcrypto`](Self::zipcrypto), as are errors from the
+    /// compressor factory. Nothing is written to the archive on error, as the
+    /// local header is held back until the compressor first writes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::io::Write;
+    /// # let mut output = std::io::Cursor::new(Vec::new());
+    /// # let mut archive = rawzip::ZipArchiveWriter::new(&mut output);
+    /// use rawzip::{CompressionMethod, Compressors, Encoder};
+    ///
+    /// let mut compressors = Compressors::new();
+    /// compressors.register(CompressionMethod::DEFLATE, |sink| {
+    ///     let encoder = flate2::write::DeflateEncoder::new(sink, flate2::Compression::default());
+    ///     Ok(Encoder::new(encoder, |encoder| encoder.finish()))
+    /// });
+    ///
+    /// let mut writer = archive
+    ///     .new_file("file.txt")
+    ///     .compression_method(CompressionMethod::DEFLATE)
+    ///     .start_with(&compressors)?;
+    /// writer.write_all(b"Hello")?;
+    /// writer.finish()?;
+    /// # archive.finish()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn start_with(self, compressors: &Compressors) -> Result<ZipFileWriter<'archive>, Error>
+    where
+        W: 'archive,
+    {
+        if self.encrypted && self.zipcrypto.is_none() {
+            return Err(Error::from(ErrorKind::InvalidInput {
+                msg: "encrypted entries cannot be started with a compressor".to_string(),
+            }));
+        }
+
+        self.check_encryption()?;
+        let factory = compressors.factory(self.compression_method)?;
+        let (entry, config) = self.start_entry(true)?;
+        let encoder = factory(EncoderSink::new(entry))?;
+        Ok(ZipFileWriter {
+            inner: config.wrap(encoder),
+        })
+    }
+}
+
+/// A writer of an entry's uncompressed data, created by
+/// [`ZipFileBuilder::start_with`].
+///
+/// The entry is only added to the archive once [`finish`](Self::finish) is
+/// called.
+#[derive(Debug)]
+pub struct ZipFileWriter<'a> {
+    inner: ZipDataWriter<Encoder<'a>>,
+}
+
+impl ZipFileWriter<'_> {
+    /// Finishes the compressed stream and the entry, returning the number of
+    /// compressed bytes written.
+    pub fn finish(self) -> Result<u64, Error> {
+        let (encoder, descriptor) = self.inner.finish()?;
+        encoder.finish()?.finish(descriptor)
+    }
+}
+
+impl Write for ZipFileWriter<'_> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
 }
 
 /// A builder for creating a new directory entry in a ZIP archive.
@@ -542,7 +1100,10 @@ pub struct ZipDirBuilder<'a, W> {
     archive: &'a mut ZipArchiveWriter<W>,
     path: EntryPath<'a>,
     modification_time: Option<UtcDateTime>,
+    access_time: Option<UtcDateTime>,
+    creation_time: Option<UtcDateTime>,
     unix_permissions: Option<u32>,
+    dos_attributes: DosAttributes,
     extra_fields: ExtraFieldsContainer,
     file_comment: Vec<u8>,
 }
@@ -561,6 +1122,26 @@ where
         self
     }
 
+    /// Sets the last access time for the directory entry.
+    ///
+    /// See [`ZipFileBuilder::last_accessed`] for details.
+    #[must_use]
+    #[inline]
+    pub fn last_accessed(mut self, access_time: UtcDateTime) -> Self {
+        self.access_time = Some(access_time);
+        self
+    }
+
+    /// Sets the creation time for the directory entry.
+    ///
+    /// See [`ZipFileBuilder::last_accessed`] for details.
+    #[must_use]
+    #[inline]
+    pub fn created(mut self, creation_time: UtcDateTime) -> Self {
+        self.creation_time = Some(creation_time);
+        self
+    }
+
     /// Sets the Unix permissions for the directory entry.
     ///
     /// See [`ZipFileBuilder::unix_permissions`] for details.
@@ -571,6 +1152,23 @@ where
         self
     }
 
+    /// Sets the Unix mode for the directory entry from an [`EntryMode`].
+    ///
+    /// See [`ZipFileBuilder::mode`] for details.
+    #[must_use]
+    pub fn mode(self, mode: EntryMode) -> Self {
+        self.unix_permissions(mode.value())
+    }
+
+    /// Sets the MS-DOS attributes for the directory entry.
+    ///
+    /// See [`ZipFileBuilder::dos_attributes`] for details.
+    #[must_use]
+    pub fn dos_attributes(mut self, attributes: DosAttributes) -> Self {
+        self.dos_attributes = attributes;
+        self
+    }
+
     /// Adds an extra field to this directory entry.
     ///
     /// See [`ZipFileBuilder::extra_field`] for details and examples.
@@ -585,6 +1183,14 @@ where
         Ok(self)
     }
 
+    /// Encodes and adds a well-known extra field to this directory entry.
+    ///
+    /// See [`ZipFileBuilder::typed_extra_field`] for details.
+    pub fn typed_extra_field(mut self, field: ExtraFieldValue<'_>) -> Result<Self, Error> {
+        field.encode(&mut self.extra_fields)?;
+        Ok(self)
+    }
+
     /// Sets a comment for this directory entry.
     ///
     /// See [`ZipFileBuilder::comment`] for details. The comment is validated
@@ -600,7 +1206,10 @@ where
         let options = ZipEntryOptions {
             compression_method: CompressionMethod::STORE, // Directories always use Store
             modification_time: self.modification_time,
+            access_time: self.access_time,
+            creation_time: self.creation_time,
             unix_permissions: self.unix_permissions,
+            dos_attributes: self.dos_attributes,
             extra_fields: self.extra_fields,
             file_comment: self.file_comment,
             encrypted: false,
@@ -609,13 +1218,111 @@ where
     }
 }
 
+/// A builder for creating a new symbolic link entry in a ZIP archive.
+///
+/// The link is written as a stored file entry whose data is the target and
+/// whose Unix mode has the symbolic link file type (`S_IFLNK`).
+#[derive(Debug)]
+pub struct ZipSymlinkBuilder<'a, W> {
+    archive: &'a mut ZipArchiveWriter<W>,
+    path: EntryPath<'a>,
+    target: &'a [u8],
+    modification_time: Option<UtcDateTime>,
+    permissions: u32,
+    extra_fields: ExtraFieldsContainer,
+    file_comment: Vec<u8>,
+}
+
+impl<W> ZipSymlinkBuilder<'_, W>
+where
+    W: Write,
+{
+    /// Sets the modification time for the symbolic link entry.
+    ///
+    /// See [`ZipFileBuilder::last_modified`] for details.
+    #[must_use]
+    #[inline]
+    pub fn last_modified(mut self, modification_time: UtcDateTime) -> Self {
+        self.modification_time = Some(modification_time);
+        self
+    }
+
+    /// Sets the permission bits of the symbolic link. Defaults to `0o777`.
+    ///
+    /// The file type is always a symbolic link, so only the permission bits
+    /// (`0o777`) are used.
+    #[must_use]
+    #[inline]
+    pub fn unix_permissions(mut self, permissions: u32) -> Self {
+        self.permissions = permissions & 0o777;
+        self
+    }
+
+    /// Adds an extra field to this symbolic link entry.
+    ///
+    /// See [`ZipFileBuilder::extra_field`] for details.
+    pub fn extra_field(
+        mut self,
+        id: ExtraFieldId,
+        data: &[u8],
+        location: Header,
+    ) -> Result<Self, Error> {
+        self.extra_fields.add_field(id, data, location)?;
+        Ok(self)
+    }
+
+    /// Sets a comment for this symbolic link entry.
+    ///
+    /// See [`ZipFileBuilder::comment`] for details.
+    #[must_use]
+    pub fn comment(mut self, comment: impl Into<Vec<u8>>) -> Self {
+        self.file_comment = comment.into();
+        self
+    }
+
+    /// Creates the symbolic link entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the target is empty or contains a NUL byte, or if
+    /// writing fails.
+    pub fn create(self) -> Result<(), Error> {
+        if self.target.is_empty() || self.target.contains(&0) {
+            return Err(Error::from(ErrorKind::InvalidInput {
+                msg: "symlink target must be non-empty and without NUL bytes".to_string(),
+            }));
+        }
+
+        let options = ZipEntryOptions {
+            compression_method: CompressionMethod::STORE,
+            modification_time: self.modification_time,
+            access_time: None,
+            creation_time: None,
+            unix_permissions: Some(S_IFLNK | self.permissions),
+            dos_attributes: DosAttributes::default(),
+            extra_fields: self.extra_fields,
+            file_comment: self.file_comment,
+            encrypted: false,
+        };
+
+        let mut entry = self
+            .archive
+            .new_file_with_options(self.path, options, false)?;
+        let mut writer = ZipDataWriter::with_crc32(&mut entry, Crc32Option::Calculate);
+        writer.write_all(self.target)?;
+        let (_, descriptor) = writer.finish()?;
+        entry.finish(descriptor)?;
+        Ok(())
+    }
+}
+
 impl<W> ZipArchiveWriter<W>
 where
     W: Write,
 {
     /// Writes a local file header with filtered extra fields.
     fn write_local_header(
-        &mut self,
+        out: &mut impl Write,
         name_bytes: &[u8],
         flags: u16,
         compression_method: CompressionMethod,
@@ -627,18 +1334,14 @@ where
             .map(DosDateTime::from)
             .unwrap_or_default();
 
-        if let Some(datetime) = options.modification_time.as_ref() {
-            let unix_time = datetime.to_unix().max(0) as u32;
-            let mut data = [0u8; 5];
-            data[0] = 1; // Flags: modification time present
-            data[1..].copy_from_slice(&unix_time.to_le_bytes());
-            options.extra_fields.add_field(
-                ExtraFieldId::EXTENDED_TIMESTAMP,
-                &data,
-                Header::CENTRAL,
-            )?;
+        if options.extra_fields.contains(ExtraFieldId::ZIP64) {
+            return Err(Error::from(ErrorKind::InvalidInput {
+                msg: "zip64 extra field is written by rawzip".to_string(),
+            }));
         }
 
+        write_timestamp_fields(options)?;
+
         let header = ZipLocalFileHeaderFixed {
             signature: ZipLocalFileHeaderFixed::SIGNATURE,
             version_needed: 20,
@@ -653,11 +1356,11 @@ where
             extra_field_len: options.extra_fields.local_size,
         };
 
-        header.write(&mut self.writer)?;
-        self.writer.write_all(name_bytes)?;
+        header.write(&mut *out)?;
+        out.write_all(name_bytes)?;
         options
             .extra_fields
-            .write_extra_fields(&mut self.writer, Header::LOCAL)?;
+            .write_extra_fields(out, Header::LOCAL)?;
         Ok(())
     }
 
@@ -682,21 +1385,93 @@ where
             archive: self,
             path: path.into(),
             modification_time: None,
+            access_time: None,
+            creation_time: None,
             unix_permissions: None,
+            dos_attributes: DosAttributes::default(),
             extra_fields: ExtraFieldsContainer::new(),
             file_comment: Vec::new(),
         }
     }
 
+    /// Creates a builder for adding a symbolic link to the archive.
+    ///
+    /// The target is stored verbatim as the entry's data and is typically a
+    /// path relative to the directory containing the link.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::io::Cursor;
+    /// # let mut output = Cursor::new(Vec::new());
+    /// # let mut archive = rawzip::ZipArchiveWriter::new(&mut output);
+    /// archive.new_symlink("docs/latest", "v2").create()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn new_symlink<'a, T>(
+        &'a mut self,
+        path: impl Into<EntryPath<'a>>,
+        target: &'a T,
+    ) -> ZipSymlinkBuilder<'a, W>
+    where
+        T: AsRef<[u8]> + ?Sized,
+    {
+        ZipSymlinkBuilder {
+            archive: self,
+            path: path.into(),
+            target: target.as_ref(),
+            modification_time: None,
+            permissions: 0o777,
+            extra_fields: ExtraFieldsContainer::new(),
+            file_comment: Vec::new(),
+        }
+    }
+
+    /// Creates a builder for archiving the directory tree at `path`.
+    ///
+    /// Every file, directory, and symbolic link beneath `path` is added with
+    /// its modification time and, on Unix, its permissions. Entry names are
+    /// relative to `path`, which itself is not added.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::io::Cursor;
+    /// # let dir = std::env::temp_dir().join("rawzip-add-dir-all-example");
+    /// # std::fs::create_dir_all(dir.join("src"))?;
+    /// # std::fs::write(dir.join("src/main.rs"), "fn main() {}")?;
+    /// # let mut output = Cursor::new(Vec::new());
+    /// # let mut archive = rawzip::ZipArchiveWriter::new(&mut output);
+    /// use rawzip::SymlinkHandling;
+    ///
+    /// archive
+    ///     .add_dir_all(&dir)
+    ///     .symlinks(SymlinkHandling::Skip)
+    ///     .filter(|name| !name.starts_with("target/"))
+    ///     .create()?;
+    /// # std::fs::remove_dir_all(&dir)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn add_dir_all(&mut self, path: impl AsRef<std::path::Path>) -> ZipDirTreeBuilder<'_, W> {
+        ZipDirTreeBuilder::new(self, path.as_ref().to_path_buf())
+    }
+
     /// Adds a new directory to the archive with options (internal method).
     ///
     /// The path of the directory must end with a `/`.
     fn new_dir_with_options(
         &mut self,
         path: EntryPath<'_>,
-        options: ZipEntryOptions,
+        mut options: ZipEntryOptions,
     ) -> Result<(), Error> {
-        with_resolved_entry_path(path, false, |path_bytes, needs_utf8| {
+        if let Some(reproducible) = self.reproducible.as_ref() {
+            reproducible.apply(&mut options, true);
+        }
+
+        with_resolved_entry_path(path, false, |path_bytes, needs_utf8, unicode| {
+            add_unicode_path(&mut options, path_bytes, unicode)?;
             self.write_dir_entry(path_bytes, needs_utf8, options)
         })
     }
@@ -723,14 +1498,19 @@ where
         let local_header_offset = self.writer.count();
         let flags = if needs_utf8 { FLAG_UTF8_ENCODING } else { 0 };
 
-        // Store the path bytes in the central buffer.
         let name_len = path_bytes.len() as u16;
-        self.file_names.extend_from_slice(path_bytes);
-
         let file_comment_len = comment_len(&options.file_comment)?;
 
-        self.write_local_header(path_bytes, flags, CompressionMethod::STORE, &mut options)?;
+        Self::write_local_header(
+            &mut self.writer,
+            path_bytes,
+            flags,
+            CompressionMethod::STORE,
+            &mut options,
+        )?;
 
+        // Store the path bytes in the central buffer once the entry is accepted.
+        self.file_names.extend_from_slice(path_bytes);
         self.file_comments.extend_from_slice(&options.file_comment);
 
         let file_header = FileHeader {
@@ -744,6 +1524,7 @@ where
             flags,
             modification_time: options.modification_time,
             unix_permissions: options.unix_permissions,
+            dos_attributes: options.dos_attributes,
             extra_fields: options.extra_fields,
         };
         self.files.push(file_header);
@@ -781,11 +1562,15 @@ where
             path: path.into(),
             compression_method: CompressionMethod::STORE,
             modification_time: None,
+            access_time: None,
+            creation_time: None,
             unix_permissions: None,
+            dos_attributes: DosAttributes::default(),
             extra_fields: ExtraFieldsContainer::new(),
             crc32_option: Crc32Option::default(),
             file_comment: Vec::new(),
             encrypted: false,
+            zipcrypto: None,
         }
     }
 
@@ -793,19 +1578,30 @@ where
     fn new_file_with_options(
         &mut self,
         path: EntryPath<'_>,
-        options: ZipEntryOptions,
+        mut options: ZipEntryOptions,
+        deferred: bool,
     ) -> Result<ZipEntryWriter<'_, W>, Error> {
-        with_resolved_entry_path(path, true, |path_bytes, needs_utf8| {
-            self.write_file_entry(path_bytes, needs_utf8, options)
+        if let Some(reproducible) = self.reproducible.as_ref() {
+            reproducible.apply(&mut options, false);
+        }
+
+        with_resolved_entry_path(path, true, |path_bytes, needs_utf8, unicode| {
+            add_unicode_path(&mut options, path_bytes, unicode)?;
+            self.write_file_entry(path_bytes, needs_utf8, options, deferred)
         })
     }
 
     /// Writes a file using resolved path bytes.
+    ///
+    /// A deferred entry validates and encodes its local header, but leaves it
+    /// to the entry writer to write on first use. Until then, the archive is
+    /// untouched and the entry writer may be dropped.
     fn write_file_entry(
         &mut self,
         path_bytes: &[u8],
         needs_utf8: bool,
         mut options: ZipEntryOptions,
+        deferred: bool,
     ) -> Result<ZipEntryWriter<'_, W>, Error> {
         if path_bytes.len() > u16::MAX as usize {
             return Err(Error::from(ErrorKind::InvalidInput {
@@ -822,15 +1618,26 @@ where
             flags |= FLAG_ENCRYPTED;
         }
 
-        // Store the path bytes in the central buffer.
         let name_len = path_bytes.len() as u16;
-        self.file_names.extend_from_slice(path_bytes);
-
         let file_comment_len = comment_len(&options.file_comment)?;
 
-        self.write_local_header(path_bytes, flags, options.compression_method, &mut options)?;
+        let method = options.compression_method;
+        let pending = if deferred {
+            let mut header = Vec::new();
+            Self::write_local_header(&mut header, path_bytes, flags, method, &mut options)?;
+            Some(PendingHeader {
+                header,
+                name: path_bytes.to_vec(),
+                comment: options.file_comment,
+            })
+        } else {
+            Self::write_local_header(&mut self.writer, path_bytes, flags, method, &mut options)?;
 
-        self.file_comments.extend_from_slice(&options.file_comment);
+            // Store the path bytes in the central buffer once the entry is accepted.
+            self.file_names.extend_from_slice(path_bytes);
+            self.file_comments.extend_from_slice(&options.file_comment);
+            None
+        };
 
         Ok(ZipEntryWriter {
             inner: self,
@@ -842,7 +1649,10 @@ where
             flags,
             modification_time: options.modification_time,
             unix_permissions: options.unix_permissions,
+            dos_attributes: options.dos_attributes,
             extra_fields: options.extra_fields,
+            cipher: None,
+            pending,
         })
     }
 
@@ -855,6 +1665,11 @@ where
         W: Write,
     {
         let archive_comment_len = comment_len(&self.archive_comment)?;
+        if let Some(staging) = self.writer.staging.take() {
+            self.write_sorted_entries(&staging)?;
+        }
+
+        self.writer.write_all(&self.apk_signing_block)?;
         let central_directory_offset = self.writer.count();
         let total_entries = self.files.len();
 
@@ -901,7 +1716,8 @@ where
                 file_comment_len: file.file_comment_len,
                 disk_number_start: 0,
                 internal_file_attrs: 0,
-                external_file_attrs: file.unix_permissions.map(|x| x << 16).unwrap_or(0),
+                external_file_attrs: file.unix_permissions.map(|x| x << 16).unwrap_or(0)
+                    | u32::from(file.dos_attributes.bits()),
                 local_header_offset: file.local_header_offset.min(ZIP64_THRESHOLD_OFFSET) as u32,
             };
 
@@ -976,6 +1792,63 @@ where
         self.writer.flush()?;
         Ok(self.writer.writer)
     }
+
+    /// Writes staged entries in name order, rewriting the central directory
+    /// bookkeeping to match.
+    fn write_sorted_entries(&mut self, staging: &[u8]) -> Result<(), Error> {
+        let base_offset = self.writer.count() - staging.len() as u64;
+
+        // Entries are staged back to back in the order they were finished, so
+        // each entry runs until the next one's local header.
+        let mut spans = Vec::with_capacity(self.files.len());
+        let mut name_offset = 0;
+        let mut comment_offset = 0;
+        for (i, file) in self.files.iter().enumerate() {
+            let start = (file.local_header_offset - base_offset) as usize;
+            let end = self
+                .files
+                .get(i + 1)
+                .map(|next| (next.local_header_offset - base_offset) as usize)
+                .unwrap_or(staging.len());
+            let name = name_offset..name_offset + file.name_len as usize;
+            let comment = comment_offset..comment_offset + file.file_comment_len as usize;
+            name_offset = name.end;
+            comment_offset = comment.end;
+            spans.push((name, comment, start..end));
+        }
+
+        // Entries with the same name are ordered by their bytes so that the
+        // output stays independent of insertion order.
+        let mut order = (0..self.files.len()).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| {
+            let (name_a, _, data_a) = &spans[a];
+            let (name_b, _, data_b) = &spans[b];
+            self.file_names[name_a.clone()]
+                .cmp(&self.file_names[name_b.clone()])
+                .then_with(|| staging[data_a.clone()].cmp(&staging[data_b.clone()]))
+        });
+
+        self.writer.count = base_offset;
+        let mut files = std::mem::take(&mut self.files)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        let mut file_names = Vec::with_capacity(self.file_names.len());
+        let mut file_comments = Vec::with_capacity(self.file_comments.len());
+        for i in order {
+            let (name, comment, data) = &spans[i];
+            let mut file = files[i].take().expect("entry visited once");
+            file.local_header_offset = self.writer.count();
+            self.writer.write_all(&staging[data.clone()])?;
+            file_names.extend_from_slice(&self.file_names[name.clone()]);
+            file_comments.extend_from_slice(&self.file_comments[comment.clone()]);
+            self.files.push(file);
+        }
+
+        self.file_names = file_names;
+        self.file_comments = file_comments;
+        Ok(())
+    }
 }
 
 /// A writer for a file in a ZIP archive.
@@ -995,7 +1868,19 @@ pub struct ZipEntryWriter<'a, W> {
     flags: u16,
     modification_time: Option<UtcDateTime>,
     unix_permissions: Option<u32>,
+    dos_attributes: DosAttributes,
     extra_fields: ExtraFieldsContainer,
+    cipher: Option<zipcrypto::Cipher>,
+    pending: Option<PendingHeader>,
+}
+
+/// The parts of a deferred entry that are written once data arrives.
+#[derive(Debug)]
+struct PendingHeader {
+    /// The local header, followed by the encryption header if any
+    header: Vec<u8>,
+    name: Vec<u8>,
+    comment: Vec<u8>,
 }
 
 /// Configuration for creating data writers.
@@ -1042,13 +1927,51 @@ impl<'a, W> ZipEntryWriter<'a, W> {
             .unwrap_or_default()
     }
 
+    /// Writes the ZipCrypto encryption header and encrypts the rest of the
+    /// entry. The writer always sets the data descriptor flag, so the check
+    /// byte is the high byte of the DOS mod time.
+    fn start_zipcrypto(&mut self, zipcrypto: ZipCryptoHeader) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        let ZipCryptoHeader {
+            mut cipher,
+            header_random,
+        } = zipcrypto;
+        let check_byte = (self.last_modified_dos().packed_time() >> 8) as u8;
+        let header = zipcrypto::encrypt_header(&mut cipher, header_random, check_byte);
+        match self.pending.as_mut() {
+            Some(pending) => {
+                pending.header.extend_from_slice(&header);
+                self.compressed_bytes += header.len() as u64;
+            }
+            None => self.write_all(&header).map_err(Error::io)?,
+        }
+        self.cipher = Some(cipher);
+        Ok(())
+    }
+
+    /// Writes the local header of a deferred entry, if it is still pending.
+    fn write_pending(&mut self) -> io::Result<()>
+    where
+        W: Write,
+    {
+        if let Some(pending) = self.pending.take() {
+            self.inner.writer.write_all(&pending.header)?;
+            self.inner.file_names.extend_from_slice(&pending.name);
+            self.inner.file_comments.extend_from_slice(&pending.comment);
+        }
+        Ok(())
+    }
+
     /// Finishes writing the file entry.
     ///
     /// This writes the data descriptor if necessary and adds the file entry to the central directory.
-    pub fn finish(self, mut output: DataDescriptorOutput) -> Result<u64, Error>
+    pub fn finish(mut self, mut output: DataDescriptorOutput) -> Result<u64, Error>
     where
         W: Write,
     {
+        self.write_pending()?;
         output.compressed_size = self.compressed_bytes;
         let mut buffer = [0u8; 24];
         buffer[0..4].copy_from_slice(&DataDescriptor::SIGNATURE.to_le_bytes());
@@ -1081,6 +2004,7 @@ impl<'a, W> ZipEntryWriter<'a, W> {
             flags: self.flags,
             modification_time: self.modification_time,
             unix_permissions: self.unix_permissions,
+            dos_attributes: self.dos_attributes,
             extra_fields: self.extra_fields,
         };
         self.inner.files.push(file_header);
@@ -1089,17 +2013,42 @@ impl<'a, W> ZipEntryWriter<'a, W> {
     }
 }
 
+impl<W> EntrySink for ZipEntryWriter<'_, W>
+where
+    W: Write,
+{
+    fn finish(self: Box<Self>, output: DataDescriptorOutput) -> Result<u64, Error> {
+        ZipEntryWriter::finish(*self, output)
+    }
+}
+
 impl<W> Write for ZipEntryWriter<'_, W>
 where
     W: Write,
 {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        let bytes_written = self.inner.writer.write(buf)?;
-        self.compressed_bytes += bytes_written as u64;
-        Ok(bytes_written)
+        self.write_pending()?;
+        let Some(cipher) = self.cipher.as_mut() else {
+            let bytes_written = self.inner.writer.write(buf)?;
+            self.compressed_bytes += bytes_written as u64;
+            return Ok(bytes_written);
+        };
+
+        // The cipher advances with every byte, so encrypted bytes must all be
+        // written.
+        let mut scratch = [0u8; 1024];
+        for chunk in buf.chunks(scratch.len()) {
+            let part = &mut scratch[..chunk.len()];
+            part.copy_from_slice(chunk);
+            cipher.encrypt(part);
+            self.inner.writer.write_all(part)?;
+            self.compressed_bytes += part.len() as u64;
+        }
+        Ok(buf.len())
     }
 
     fn flush(&mut self) -> io::Result<()> {
+        self.write_pending()?;
         self.inner.writer.flush()
     }
 }
@@ -1243,6 +2192,7 @@ struct FileHeader {
     flags: u16,
     modification_time: Option<UtcDateTime>,
     unix_permissions: Option<u32>,
+    dos_attributes: DosAttributes,
     extra_fields: ExtraFieldsContainer,
 }
 
@@ -1350,11 +2300,138 @@ where
     Ok(())
 }
 
+/// Adds the Unicode Path extra field for a verbatim path that carries one.
+fn add_unicode_path(
+    options: &mut ZipEntryOptions,
+    path_bytes: &[u8],
+    unicode: Option<&str>,
+) -> Result<(), Error> {
+    let Some(path) = unicode else {
+        return Ok(());
+    };
+
+    ExtraFieldValue::UnicodePath {
+        original: path_bytes,
+        path,
+    }
+    .encode(&mut options.extra_fields)
+}
+
+/// Appends the timestamp extra fields for the entry's times.
+///
+/// The Extended Timestamp is written whenever every set time fits in its
+/// unsigned 32-bit seconds. A modification-time-only field is kept to the
+/// central directory, while access and creation times use the full local form
+/// alongside the central form (which carries only the modification time). The
+/// NTFS field is appended last, so readers that take the last timestamp prefer
+/// its 100ns precision, whenever the Extended Timestamp would lose any set
+/// time. An absent modification time leaves a zero in its NTFS slot.
+fn write_timestamp_fields(options: &mut ZipEntryOptions) -> Result<(), Error> {
+    let times = [
+        options.modification_time,
+        options.access_time,
+        options.creation_time,
+    ];
+
+    if times.iter().all(Option::is_none) {
+        return Ok(());
+    }
+
+    let duplicate = |id: ExtraFieldId| {
+        Error::from(ErrorKind::InvalidInput {
+            msg: format!(
+                "extra field 0x{:04x} conflicts with entry timestamps",
+                id.as_u16()
+            ),
+        })
+    };
+
+    let fits_extended = |time: &UtcDateTime| u32::try_from(time.to_unix()).is_ok();
+    let extended_representable = times.iter().flatten().all(fits_extended);
+    let lossless = times
+        .iter()
+        .flatten()
+        .all(|time| fits_extended(time) && time.nanosecond() == 0);
+
+    if extended_representable {
+        if options
+            .extra_fields
+            .contains(ExtraFieldId::EXTENDED_TIMESTAMP)
+        {
+            return Err(duplicate(ExtraFieldId::EXTENDED_TIMESTAMP));
+        }
+
+        let mut flags = 0u8;
+        let mut local = [0u8; 13];
+        let mut local_len = 1;
+        for (bit, time) in times.iter().enumerate() {
+            if let Some(time) = time {
+                flags |= 1 << bit;
+                let seconds = time.to_unix() as u32;
+                local[local_len..local_len + 4].copy_from_slice(&seconds.to_le_bytes());
+                local_len += 4;
+            }
+        }
+        local[0] = flags;
+
+        // The central form has the local flags but only the modification time
+        let central_len = if options.modification_time.is_some() {
+            5
+        } else {
+            1
+        };
+
+        if options.access_time.is_none() && options.creation_time.is_none() {
+            options.extra_fields.add_field(
+                ExtraFieldId::EXTENDED_TIMESTAMP,
+                &local[..central_len],
+                Header::CENTRAL,
+            )?;
+        } else {
+            options.extra_fields.add_field(
+                ExtraFieldId::EXTENDED_TIMESTAMP,
+                &local[..local_len],
+                Header::LOCAL,
+            )?;
+            options.extra_fields.add_field(
+                ExtraFieldId::EXTENDED_TIMESTAMP,
+                &local[..central_len],
+                Header::CENTRAL,
+            )?;
+        }
+    }
+
+    if !lossless {
+        if options.extra_fields.contains(ExtraFieldId::NTFS) {
+            return Err(duplicate(ExtraFieldId::NTFS));
+        }
+
+        // Zero marks an unset time, which readers skip in the mtime slot
+        let mtime = options.modification_time.map_or(0, UtcDateTime::to_ntfs);
+        let atime = options.access_time.map_or(mtime, UtcDateTime::to_ntfs);
+        let ctime = options.creation_time.map_or(mtime, UtcDateTime::to_ntfs);
+        let mut data = [0u8; 32];
+        data[4..6].copy_from_slice(&1u16.to_le_bytes()); // attribute tag
+        data[6..8].copy_from_slice(&24u16.to_le_bytes()); // attribute size
+        data[8..16].copy_from_slice(&mtime.to_le_bytes());
+        data[16..24].copy_from_slice(&atime.to_le_bytes());
+        data[24..32].copy_from_slice(&ctime.to_le_bytes());
+        options
+            .extra_fields
+            .add_field(ExtraFieldId::NTFS, &data, Header::default())?;
+    }
+
+    Ok(())
+}
+
 #[derive(Debug, Clone)]
 struct ZipEntryOptions {
     compression_method: CompressionMethod,
     modification_time: Option<UtcDateTime>,
+    access_time: Option<UtcDateTime>,
+    creation_time: Option<UtcDateTime>,
     unix_permissions: Option<u32>,
+    dos_attributes: DosAttributes,
     extra_fields: ExtraFieldsContainer,
     file_comment: Vec<u8>,
     encrypted: bool,
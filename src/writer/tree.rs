@@ -0,0 +1,324 @@
+//! Archiving a directory tree from the filesystem.
+
+use super::ZipArchiveWriter;
+use crate::{CompressionMethod, Compressors, Error, ErrorKind, time::UtcDateTime};
+use std::fs::{self, Metadata};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// How [`ZipArchiveWriter::add_dir_all`] handles symbolic links.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum SymlinkHandling {
+    /// Archive the file or directory the link points to. Links that form a
+    /// cycle are an error.
+    Follow,
+
+    /// Archive the link itself as a symbolic link entry.
+    #[default]
+    Store,
+
+    /// Leave links out of the archive.
+    Skip,
+}
+
+type Filter<'a> = Box<dyn FnMut(&str) -> bool + 'a>;
+type MethodSelector<'a> = Box<dyn FnMut(&str) -> CompressionMethod + 'a>;
+
+/// A builder for archiving a directory tree, created by
+/// [`ZipArchiveWriter::add_dir_all`].
+///
+/// Entries are named relative to the walked directory and emitted in sorted
+/// order, so the same tree always produces the same sequence of entries. The
+/// modification time of every entry is captured from the filesystem, as are
+/// the permissions on Unix.
+///
+/// Files are stored uncompressed unless compressors are configured. Special
+/// files like sockets and devices are left out.
+pub struct ZipDirTreeBuilder<'a, W> {
+    archive: &'a mut ZipArchiveWriter<W>,
+    root: PathBuf,
+    symlinks: SymlinkHandling,
+    filter: Option<Filter<'a>>,
+    method: MethodSelector<'a>,
+    compressors: Option<&'a Compressors>,
+}
+
+impl<W> std::fmt::Debug for ZipDirTreeBuilder<'_, W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ZipDirTreeBuilder")
+            .field("root", &self.root)
+            .field("symlinks", &self.symlinks)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, W> ZipDirTreeBuilder<'a, W>
+where
+    W: Write,
+{
+    pub(super) fn new(archive: &'a mut ZipArchiveWriter<W>, root: PathBuf) -> Self {
+        ZipDirTreeBuilder {
+            archive,
+            root,
+            symlinks: SymlinkHandling::default(),
+            filter: None,
+            method: Box::new(|_| CompressionMethod::STORE),
+            compressors: None,
+        }
+    }
+
+    /// Sets how symbolic links are handled. Defaults to
+    /// [`SymlinkHandling::Store`].
+    ///
+    /// A symbolic link to the walked directory itself is always followed.
+    #[must_use]
+    pub fn symlinks(mut self, symlinks: SymlinkHandling) -> Self {
+        self.symlinks = symlinks;
+        self
+    }
+
+    /// Sets a filter deciding which entries are archived.
+    ///
+    /// The filter receives the entry name, which ends with `/` for
+    /// directories, and returns `false` to exclude it. The contents of an
+    /// excluded directory are not visited.
+    #[must_use]
+    pub fn filter<F>(mut self, filter: F) -> Self
+    where
+        F: FnMut(&str) -> bool + 'a,
+    {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Sets the compressors used for file data.
+    ///
+    /// `method` picks the compression method for each file from its entry
+    /// name, and the file is then compressed with the encoder that
+    /// `compressors` has registered for the method. Writing a file fails with
+    /// [`ErrorKind::UnsupportedCompressionMethod`] if none is registered.
+    ///
+    /// ```rust
+    /// # use std::io::Cursor;
+    /// # let dir = std::env::temp_dir().join("rawzip-add-dir-all-doc");
+    /// # std::fs::create_dir_all(&dir)?;
+    /// # let mut output = Cursor::new(Vec::new());
+    /// # let mut archive = rawzip::ZipArchiveWriter::new(&mut output);
+    /// use rawzip::{CompressionMethod, Compressors, Encoder};
+    ///
+    /// let mut compressors = Compressors::new();
+    /// compressors.register(CompressionMethod::DEFLATE, |sink| {
+    ///     let encoder = flate2::write::DeflateEncoder::new(sink, flate2::Compression::default());
+    ///     Ok(Encoder::new(encoder, |encoder| encoder.finish()))
+    /// });
+    ///
+    /// archive
+    ///     .add_dir_all(&dir)
+    ///     .compressor(
+    ///         |name| match name.ends_with(".png") {
+    ///             true => CompressionMethod::STORE,
+    ///             false => CompressionMethod::DEFLATE,
+    ///         },
+    ///         &compressors,
+    ///     )
+    ///     .create()?;
+    /// # std::fs::remove_dir_all(&dir)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn compressor<M>(mut self, method: M, compressors: &'a Compressors) -> Self
+    where
+        M: FnMut(&str) -> CompressionMethod + 'a,
+    {
+        self.method = Box::new(method);
+        self.compressors = Some(compressors);
+        self
+    }
+
+    /// Walks the directory and writes its entries to the archive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the root is not a directory, a name is not UTF-8,
+    /// the filesystem cannot be read, followed links form a cycle, or
+    /// writing an entry fails.
+    pub fn create(mut self) -> Result<(), Error> {
+        let root = std::mem::take(&mut self.root);
+        if !fs::metadata(&root)?.is_dir() {
+            return Err(Error::from(io::Error::from(io::ErrorKind::NotADirectory)));
+        }
+
+        let mut ancestors = Vec::new();
+        if self.symlinks == SymlinkHandling::Follow {
+            ancestors.push(fs::canonicalize(&root)?);
+        }
+
+        self.walk(&root, "", &mut ancestors)
+    }
+
+    fn walk(
+        &mut self,
+        dir: &Path,
+        prefix: &str,
+        ancestors: &mut Vec<PathBuf>,
+    ) -> Result<(), Error> {
+        let mut names = fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.file_name()))
+            .collect::<Result<Vec<_>, _>>()?;
+        names.sort();
+
+        for name in names {
+            let Some(name_str) = name.to_str() else {
+                return Err(Error::from(ErrorKind::InvalidInput {
+                    msg: format!(
+                        "file name is not valid UTF-8: {}",
+                        dir.join(&name).display()
+                    ),
+                }));
+            };
+
+            let path = dir.join(&name);
+            let mut metadata = fs::symlink_metadata(&path)?;
+            if metadata.file_type().is_symlink() {
+                match self.symlinks {
+                    SymlinkHandling::Skip => continue,
+                    SymlinkHandling::Store => {
+                        self.add_symlink(&path, format!("{prefix}{name_str}"), &metadata)?;
+                        continue;
+                    }
+                    SymlinkHandling::Follow => metadata = fs::metadata(&path)?,
+                }
+            }
+
+            if metadata.is_dir() {
+                let entry_name = format!("{prefix}{name_str}/");
+                if !self.include(&entry_name) {
+                    continue;
+                }
+
+                let pushed = self.symlinks == SymlinkHandling::Follow;
+                if pushed {
+                    let canonical = fs::canonicalize(&path)?;
+                    if ancestors.contains(&canonical) {
+                        return Err(Error::from(ErrorKind::InvalidInput {
+                            msg: format!("symbolic link cycle at {}", path.display()),
+                        }));
+                    }
+                    ancestors.push(canonical);
+                }
+
+                let mut builder = self.archive.new_dir(&entry_name);
+                if let Some(time) = modified(&metadata) {
+                    builder = builder.last_modified(time);
+                }
+                if let Some(mode) = unix_mode(&metadata) {
+                    builder = builder.unix_permissions(mode);
+                }
+                builder.create()?;
+
+                self.walk(&path, &entry_name, ancestors)?;
+                if pushed {
+                    ancestors.pop();
+                }
+            } else if metadata.is_file() {
+                let entry_name = format!("{prefix}{name_str}");
+                if self.include(&entry_name) {
+                    self.add_file(&path, &entry_name, &metadata)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn include(&mut self, name: &str) -> bool {
+        self.filter.as_mut().is_none_or(|filter| filter(name))
+    }
+
+    fn add_symlink(&mut self, path: &Path, name: String, metadata: &Metadata) -> Result<(), Error> {
+        if !self.include(&name) {
+            return Ok(());
+        }
+
+        let target = fs::read_link(path)?;
+        let Some(target) = target.to_str() else {
+            return Err(Error::from(ErrorKind::InvalidInput {
+                msg: format!("symlink target is not valid UTF-8: {}", path.display()),
+            }));
+        };
+
+        let mut builder = self.archive.new_symlink(&name, target);
+        if let Some(time) = modified(metadata) {
+            builder = builder.last_modified(time);
+        }
+        if let Some(mode) = unix_mode(metadata) {
+            builder = builder.unix_permissions(mode);
+        }
+        builder.create()
+    }
+
+    fn add_file(&mut self, path: &Path, name: &str, metadata: &Metadata) -> Result<(), Error> {
+        let method = (self.method)(name);
+        let mut input = fs::File::open(path)?;
+        let mut builder = self.archive.new_file(name).compression_method(method);
+        if let Some(time) = modified(metadata) {
+            builder = builder.last_modified(time);
+        }
+        if let Some(mode) = unix_mode(metadata) {
+            builder = builder.unix_permissions(mode);
+        }
+
+        let default;
+        let compressors = match self.compressors {
+            Some(compressors) => compressors,
+            None => {
+                default = Compressors::new();
+                &default
+            }
+        };
+
+        let mut writer = builder.start_with(compressors)?;
+        io::copy(&mut input, &mut writer)?;
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+fn modified(metadata: &Metadata) -> Option<UtcDateTime> {
+    let time = metadata.modified().ok()?;
+    let (seconds, nanos) = match time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(since) => (i64::try_from(since.as_secs()).ok()?, since.subsec_nanos()),
+        Err(before) => {
+            let before = before.duration();
+            let seconds = i64::try_from(before.as_secs()).ok()?;
+            match before.subsec_nanos() {
+                0 => (-seconds, 0),
+                nanos => (-seconds - 1, 1_000_000_000 - nanos),
+            }
+        }
+    };
+
+    let time = UtcDateTime::from_unix(seconds);
+    UtcDateTime::from_components(
+        time.year(),
+        time.month(),
+        time.day(),
+        time.hour(),
+        time.minute(),
+        time.second(),
+        nanos,
+    )
+}
+
+#[cfg(unix)]
+fn unix_mode(metadata: &Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    Some(metadata.permissions().mode())
+}
+
+#[cfg(not(unix))]
+fn unix_mode(_metadata: &Metadata) -> Option<u32> {
+    None
+}
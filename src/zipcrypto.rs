@@ -9,6 +9,7 @@
 //! [`Encryptor`] is the write-side counterpart: it emits the encryption header
 //! and encrypts the (already compressed) body on its way to the archive.
 
+use crate::ZipLocalFileHeader;
 use crate::crc::crc32_byte;
 use std::io::{self, Read, Write};
 
@@ -16,6 +17,33 @@ use std::io::{self, Read, Write};
 /// bytes followed by a single check byte (see [`Decryptor::check_byte`]).
 pub(crate) const HEADER_LEN: usize = 12;
 
+/// Returns the encrypted 12-byte encryption header, advancing the cipher.
+pub(crate) fn encrypt_header(
+    cipher: &mut Cipher,
+    header_random: [u8; HEADER_LEN - 1],
+    check_byte: u8,
+) -> [u8; HEADER_LEN] {
+    let mut header = [0u8; HEADER_LEN];
+    header[..HEADER_LEN - 1].copy_from_slice(&header_random);
+    header[HEADER_LEN - 1] = check_byte;
+    cipher.encrypt(&mut header);
+    header
+}
+
+/// Returns the check byte an encoder writes for an entry: the high byte of
+/// the DOS mod time when the entry carries a data descriptor, else of the
+/// CRC32.
+///
+/// Every ingredient comes from the local header, as that is what the encoder
+/// wrote right before the encryption header.
+pub fn expected_check_byte(local_header: &ZipLocalFileHeader<'_>) -> u8 {
+    if local_header.flags().has_data_descriptor() {
+        (local_header.last_modified_dos().packed_time() >> 8) as u8
+    } else {
+        (local_header.crc32() >> 24) as u8
+    }
+}
+
 /// The traditional PKWARE ("ZipCrypto") stream cipher (PKWARE APPNOTE §6).
 #[derive(Debug, Clone)]
 pub(crate) struct Cipher {
@@ -138,6 +166,52 @@ impl<R: Read> Decryptor<R> {
         })
     }
 
+    /// Like [`new`](Decryptor::new), but rejects the password up front when
+    /// the check byte does not match the entry's local header (see
+    /// [`expected_check_byte`]).
+    ///
+    /// A wrong password still passes 1 in 256 times, so keep validating the
+    /// output against the entry's CRC32.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::ErrorKind::InvalidData`] if the check byte does not
+    /// match, or an error from reading the header.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use rawzip::ZipArchive;
+    /// # use rawzip::zipcrypto::Decryptor;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let data = std::fs::read("assets/zipcrypto.zip")?;
+    /// let archive = ZipArchive::from_slice(&data)?;
+    /// let entry = archive.entries().next().unwrap()?;
+    /// let zip_entry = archive.get_entry(entry.wayfinder())?;
+    /// let local_header = zip_entry.local_header();
+    ///
+    /// let err = Decryptor::new_checked(zip_entry.data(), b"wrong", &local_header).unwrap_err();
+    /// assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    /// assert!(Decryptor::new_checked(zip_entry.data(), b"rawzipiscool", &local_header).is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_checked(
+        reader: R,
+        password: &[u8],
+        local_header: &ZipLocalFileHeader<'_>,
+    ) -> io::Result<Self> {
+        let decryptor = Self::new(reader, password)?;
+        if decryptor.check_byte != expected_check_byte(local_header) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "incorrect zipcrypto password",
+            ));
+        }
+
+        Ok(decryptor)
+    }
+
     /// The final byte of the decrypted header, a 1-in-256 password check.
     ///
     /// Equals the high byte of the entry's CRC32, or of its DOS mod time when
@@ -248,10 +322,7 @@ impl<W: Write> Encryptor<W> {
         check_byte: u8,
     ) -> io::Result<Self> {
         let mut cipher = Cipher::new(password);
-        let mut header = [0u8; HEADER_LEN];
-        header[..HEADER_LEN - 1].copy_from_slice(&header_random);
-        header[HEADER_LEN - 1] = check_byte;
-        cipher.encrypt(&mut header);
+        let header = encrypt_header(&mut cipher, header_random, check_byte);
         writer.write_all(&header)?;
         Ok(Encryptor {
             writer,
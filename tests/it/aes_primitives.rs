@@ -0,0 +1,57 @@
+// WinZip AES primitives backed by the RustCrypto crates, shared by the
+// integration tests and the `rawzip::aes` doc examples.
+
+use aes::cipher::{BlockCipherEncrypt, KeyInit};
+use hmac::{Hmac, Mac};
+
+pub struct RustCrypto;
+
+pub enum Aes {
+    Aes128(aes::Aes128),
+    Aes192(aes::Aes192),
+    Aes256(aes::Aes256),
+}
+
+impl rawzip::aes::BlockCipher for Aes {
+    fn encrypt_block(&mut self, block: &mut [u8; 16]) {
+        let block = block.into();
+        match self {
+            Aes::Aes128(cipher) => cipher.encrypt_block(block),
+            Aes::Aes192(cipher) => cipher.encrypt_block(block),
+            Aes::Aes256(cipher) => cipher.encrypt_block(block),
+        }
+    }
+}
+
+pub struct HmacSha1(Hmac<sha1::Sha1>);
+
+impl rawzip::aes::Mac for HmacSha1 {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    fn finalize(self) -> [u8; 20] {
+        self.0.finalize().into_bytes().into()
+    }
+}
+
+impl rawzip::aes::Primitives for RustCrypto {
+    type Cipher = Aes;
+    type Mac = HmacSha1;
+
+    fn cipher(&self, key: &[u8]) -> Aes {
+        match key.len() {
+            16 => Aes::Aes128(aes::Aes128::new_from_slice(key).unwrap()),
+            24 => Aes::Aes192(aes::Aes192::new_from_slice(key).unwrap()),
+            _ => Aes::Aes256(aes::Aes256::new_from_slice(key).unwrap()),
+        }
+    }
+
+    fn mac(&self, key: &[u8]) -> HmacSha1 {
+        HmacSha1(<Hmac<sha1::Sha1> as KeyInit>::new_from_slice(key).unwrap())
+    }
+
+    fn pbkdf2(&self, password: &[u8], salt: &[u8], iterations: u32, output: &mut [u8]) {
+        pbkdf2::pbkdf2::<Hmac<sha1::Sha1>>(password, salt, iterations, output).unwrap();
+    }
+}
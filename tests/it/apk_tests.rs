@@ -0,0 +1,158 @@
+use rawzip::{
+    ErrorKind, ZipArchive, ZipArchiveWriter,
+    apk::{self, SigningBlock, SigningBlockId},
+};
+use std::io::{Read, Write};
+
+/// Builds a signing block out of id-value pairs.
+fn signing_block(pairs: &[(SigningBlockId, &[u8])]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for (id, value) in pairs {
+        body.extend_from_slice(&(value.len() as u64 + 4).to_le_bytes());
+        body.extend_from_slice(&id.as_u32().to_le_bytes());
+        body.extend_from_slice(value);
+    }
+
+    let size = (body.len() + apk::FOOTER_LEN) as u64;
+    let mut block = size.to_le_bytes().to_vec();
+    block.extend_from_slice(&body);
+    block.extend_from_slice(&size.to_le_bytes());
+    block.extend_from_slice(&apk::MAGIC);
+    block
+}
+
+fn write_apk(block: Option<&[u8]>) -> Vec<u8> {
+    let mut output = Vec::new();
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    let (mut entry, config) = archive.new_file("AndroidManifest.xml").start().unwrap();
+    let mut writer = config.wrap(&mut entry);
+    writer.write_all(b"<manifest/>").unwrap();
+    let (_, descriptor) = writer.finish().unwrap();
+    entry.finish(descriptor).unwrap();
+
+    if let Some(block) = block {
+        archive.set_apk_signing_block(block).unwrap();
+    }
+    archive.finish().unwrap();
+    output
+}
+
+#[test]
+fn test_apk_signing_block_slice() {
+    let block = signing_block(&[
+        (SigningBlockId::V2_SIGNATURE, b"v2 signer"),
+        (SigningBlockId::V3_SIGNATURE, b"v3 signer"),
+        (SigningBlockId::VERITY_PADDING, &[0; 8]),
+    ]);
+    let data = write_apk(Some(&block));
+
+    let archive = ZipArchive::from_slice(&data).unwrap();
+    let found = archive.apk_signing_block().unwrap();
+    assert_eq!(found.as_bytes(), &block[..]);
+    assert_eq!(found.range().end, archive.directory_offset());
+
+    let pairs = found.pairs().collect::<Vec<_>>();
+    assert_eq!(
+        pairs,
+        vec![
+            (SigningBlockId::V2_SIGNATURE, &b"v2 signer"[..]),
+            (SigningBlockId::V3_SIGNATURE, &b"v3 signer"[..]),
+            (SigningBlockId::VERITY_PADDING, &[0u8; 8][..]),
+        ]
+    );
+
+    // The entries are unaffected by the block
+    let entry = archive.entries().next().unwrap().unwrap();
+    assert_eq!(entry.file_path().as_ref(), b"AndroidManifest.xml");
+}
+
+#[test]
+fn test_apk_signing_block_reader() {
+    let block = signing_block(&[(SigningBlockId::V2_SIGNATURE, b"v2 signer")]);
+    let data = write_apk(Some(&block));
+
+    let mut buffer = vec![0u8; rawzip::RECOMMENDED_BUFFER_SIZE];
+    let archive = ZipArchive::from_seekable(std::io::Cursor::new(&data), &mut buffer).unwrap();
+    let mut reader = archive.apk_signing_block().unwrap().unwrap();
+    assert_eq!(reader.end_offset(), archive.directory_offset());
+
+    let offset = reader.position();
+    let mut contents = Vec::new();
+    reader.read_to_end(&mut contents).unwrap();
+    let found = SigningBlock::new(&contents, offset).unwrap();
+    assert_eq!(found.as_bytes(), &block[..]);
+
+    let slice_archive = ZipArchive::from_slice(&data).unwrap();
+    assert_eq!(
+        slice_archive.apk_signing_block().unwrap().range(),
+        found.range()
+    );
+}
+
+#[test]
+fn test_no_apk_signing_block() {
+    let data = write_apk(None);
+
+    let archive = ZipArchive::from_slice(&data).unwrap();
+    assert!(archive.apk_signing_block().is_none());
+
+    let mut buffer = vec![0u8; rawzip::RECOMMENDED_BUFFER_SIZE];
+    let archive = ZipArchive::from_seekable(std::io::Cursor::new(&data), &mut buffer).unwrap();
+    assert!(archive.apk_signing_block().unwrap().is_none());
+}
+
+#[test]
+fn test_apk_signing_block_mismatched_size() {
+    let mut block = signing_block(&[(SigningBlockId::V2_SIGNATURE, b"v2 signer")]);
+    let data = write_apk(Some(&block));
+
+    // Corrupt the leading size field in place
+    let archive = ZipArchive::from_slice(&data).unwrap();
+    let start = archive.apk_signing_block().unwrap().range().start as usize;
+    let mut data = data.clone();
+    data[start] += 1;
+
+    let archive = ZipArchive::from_slice(&data).unwrap();
+    assert!(archive.apk_signing_block().is_none());
+
+    let mut buffer = vec![0u8; rawzip::RECOMMENDED_BUFFER_SIZE];
+    let archive = ZipArchive::from_seekable(std::io::Cursor::new(&data), &mut buffer).unwrap();
+    assert!(archive.apk_signing_block().unwrap().is_none());
+
+    // The writer refuses to emit the same malformed block
+    block[0] += 1;
+    let mut output = Vec::<u8>::new();
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    let err = archive.set_apk_signing_block(block).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidInput { .. }));
+}
+
+#[test]
+fn test_copy_preserves_apk_signing_block() {
+    let block = signing_block(&[(SigningBlockId::V2_SIGNATURE, b"v2 signer")]);
+    let original = write_apk(Some(&block));
+    let archive = ZipArchive::from_slice(&original).unwrap();
+
+    let mut output = Vec::new();
+    let mut copy = ZipArchiveWriter::new(&mut output);
+    for entry in archive.entries() {
+        let entry = entry.unwrap();
+        let wayfinder = entry.wayfinder();
+        let contents = archive.get_entry(wayfinder).unwrap().data();
+        let (mut writer, config) = copy
+            .new_file(entry.file_path().try_normalize().unwrap().as_ref())
+            .compression_method(entry.compression_method())
+            .start()
+            .unwrap();
+        // Stored entries are copied as is
+        let mut data_writer = config.wrap(&mut writer);
+        data_writer.write_all(contents).unwrap();
+        let (_, descriptor) = data_writer.finish().unwrap();
+        writer.finish(descriptor).unwrap();
+    }
+    copy.set_apk_signing_block(archive.apk_signing_block().unwrap().as_bytes())
+        .unwrap();
+    copy.finish().unwrap();
+
+    assert_eq!(output, original);
+}
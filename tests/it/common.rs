@@ -0,0 +1,228 @@
+//! Registries and archive builders shared by the integration tests.
+
+use rawzip::extra_fields::ExtraFieldId;
+use rawzip::time::UtcDateTime;
+use rawzip::{
+    CompressionMethod, Compressors, Decompressors, Encoder, EntryPath, Header, ZipArchiveWriter,
+};
+use std::io::Write;
+
+/// Compressors for deflate and zstd.
+pub(crate) fn compressors() -> Compressors {
+    let mut compressors = Compressors::new();
+    compressors
+        .register(CompressionMethod::DEFLATE, |sink| {
+            let encoder = flate2::write::DeflateEncoder::new(sink, flate2::Compression::default());
+            Ok(Encoder::new(encoder, |encoder| encoder.finish()))
+        })
+        .register(CompressionMethod::ZSTD, |sink| {
+            let encoder = zstd::Encoder::new(sink, 3)?;
+            Ok(Encoder::new(encoder, |encoder| encoder.finish()))
+        });
+    compressors
+}
+
+/// Decompressors for deflate and zstd, along with every decoder built into
+/// rawzip under the enabled features.
+pub(crate) fn decompressors() -> Decompressors {
+    let mut decompressors = Decompressors::new();
+    decompressors
+        .register(CompressionMethod::DEFLATE, |reader, _| {
+            Ok(Box::new(flate2::read::DeflateDecoder::new(reader)))
+        })
+        .register(CompressionMethod::ZSTD, |reader, _| {
+            Ok(Box::new(zstd::Decoder::new(reader)?))
+        });
+
+    #[cfg(feature = "deflate64")]
+    decompressors.register(CompressionMethod::DEFLATE64, |reader, _| {
+        Ok(Box::new(rawzip::deflate64::Decoder::new(reader)))
+    });
+
+    #[cfg(feature = "dcl-implode")]
+    decompressors.register(CompressionMethod::DCL_IMPLODE, |reader, _| {
+        Ok(Box::new(rawzip::dcl_implode::Decoder::new(reader)))
+    });
+
+    #[cfg(feature = "legacy-methods")]
+    {
+        use rawzip::legacy::{ImplodeDecoder, ReduceDecoder, ShrinkDecoder};
+        decompressors
+            .register(CompressionMethod::SHRUNK, |reader, _| {
+                Ok(Box::new(ShrinkDecoder::new(reader)))
+            })
+            .register(CompressionMethod::IMPLODED, |reader, entry| {
+                let size = entry.uncompressed_size();
+                Ok(Box::new(ImplodeDecoder::new(reader, entry.flags(), size)))
+            });
+        for method in [
+            CompressionMethod::REDUCE1,
+            CompressionMethod::REDUCE2,
+            CompressionMethod::REDUCE3,
+            CompressionMethod::REDUCE4,
+        ] {
+            decompressors.register(method, |reader, entry| {
+                let size = entry.uncompressed_size();
+                let decoder = ReduceDecoder::new(reader, entry.compression_method(), size)
+                    .map_err(std::io::Error::other)?;
+                Ok(Box::new(decoder))
+            });
+        }
+    }
+
+    decompressors
+}
+
+#[derive(Debug, Clone)]
+enum TestEntryKind<'a> {
+    File(&'a [u8]),
+    Dir,
+    Symlink(&'a str),
+}
+
+/// An entry written by [`write_archive`].
+///
+/// Files are compressed with [`compressors`] unless their compressed data
+/// is given up front.
+#[derive(Debug, Clone)]
+pub(crate) struct TestEntry<'a> {
+    path: EntryPath<'a>,
+    kind: TestEntryKind<'a>,
+    method: CompressionMethod,
+    compressed: Option<&'a [u8]>,
+    permissions: Option<u32>,
+    mtime: Option<UtcDateTime>,
+    comment: &'a str,
+    extra_fields: Vec<(ExtraFieldId, &'a [u8])>,
+}
+
+impl<'a> TestEntry<'a> {
+    fn new(path: EntryPath<'a>, kind: TestEntryKind<'a>) -> Self {
+        TestEntry {
+            path,
+            kind,
+            method: CompressionMethod::STORE,
+            compressed: None,
+            permissions: None,
+            mtime: None,
+            comment: "",
+            extra_fields: Vec::new(),
+        }
+    }
+
+    pub(crate) fn file(path: impl Into<EntryPath<'a>>, data: &'a [u8]) -> Self {
+        Self::new(path.into(), TestEntryKind::File(data))
+    }
+
+    pub(crate) fn dir(name: &'a str) -> Self {
+        Self::new(name.into(), TestEntryKind::Dir)
+    }
+
+    pub(crate) fn symlink(name: &'a str, target: &'a str) -> Self {
+        Self::new(name.into(), TestEntryKind::Symlink(target))
+    }
+
+    pub(crate) fn method(mut self, method: CompressionMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Writes `compressed` as the entry's data, as produced by an encoder
+    /// that rawzip does not have.
+    #[cfg(feature = "dcl-implode")]
+    pub(crate) fn compressed(mut self, compressed: &'a [u8]) -> Self {
+        self.compressed = Some(compressed);
+        self
+    }
+
+    pub(crate) fn permissions(mut self, permissions: u32) -> Self {
+        self.permissions = Some(permissions);
+        self
+    }
+
+    pub(crate) fn mtime(mut self, mtime: UtcDateTime) -> Self {
+        self.mtime = Some(mtime);
+        self
+    }
+
+    pub(crate) fn comment(mut self, comment: &'a str) -> Self {
+        self.comment = comment;
+        self
+    }
+
+    pub(crate) fn extra_field(mut self, id: ExtraFieldId, data: &'a [u8]) -> Self {
+        self.extra_fields.push((id, data));
+        self
+    }
+}
+
+/// Writes the entries to a new archive.
+pub(crate) fn write_archive(entries: &[TestEntry<'_>]) -> Vec<u8> {
+    let mut output = Vec::new();
+    write_entries(ZipArchiveWriter::new(&mut output), entries);
+    output
+}
+
+/// Writes the entries with the given archive writer and finishes it.
+pub(crate) fn write_entries<W: Write>(mut archive: ZipArchiveWriter<W>, entries: &[TestEntry<'_>]) {
+    let compressors = compressors();
+    for entry in entries {
+        let path = entry.path.clone();
+        let data = match entry.kind {
+            TestEntryKind::File(data) => data,
+            TestEntryKind::Dir => {
+                let mut builder = archive.new_dir(path);
+                if let Some(permissions) = entry.permissions {
+                    builder = builder.unix_permissions(permissions);
+                }
+                if let Some(mtime) = entry.mtime {
+                    builder = builder.last_modified(mtime);
+                }
+                builder.create().unwrap();
+                continue;
+            }
+            TestEntryKind::Symlink(target) => {
+                let mut builder = archive.new_symlink(path, target);
+                if let Some(permissions) = entry.permissions {
+                    builder = builder.unix_permissions(permissions);
+                }
+                if let Some(mtime) = entry.mtime {
+                    builder = builder.last_modified(mtime);
+                }
+                builder.create().unwrap();
+                continue;
+            }
+        };
+
+        let mut builder = archive
+            .new_file(path)
+            .compression_method(entry.method)
+            .comment(entry.comment);
+        if let Some(permissions) = entry.permissions {
+            builder = builder.unix_permissions(permissions);
+        }
+        if let Some(mtime) = entry.mtime {
+            builder = builder.last_modified(mtime);
+        }
+        for (id, field) in &entry.extra_fields {
+            builder = builder.extra_field(*id, field, Header::default()).unwrap();
+        }
+
+        match entry.compressed {
+            Some(compressed) => {
+                let (mut writer, config) = builder.start().unwrap();
+                let mut sink = config.wrap(std::io::sink());
+                sink.write_all(data).unwrap();
+                let (_, descriptor) = sink.finish().unwrap();
+                writer.write_all(compressed).unwrap();
+                writer.finish(descriptor).unwrap();
+            }
+            None => {
+                let mut writer = builder.start_with(&compressors).unwrap();
+                writer.write_all(data).unwrap();
+                writer.finish().unwrap();
+            }
+        }
+    }
+    archive.finish().unwrap();
+}
@@ -0,0 +1,153 @@
+use crate::common::{compressors, decompressors};
+use rawzip::{CompressionMethod, Compressors, ErrorKind, ZipArchive, ZipArchiveWriter};
+use std::io::{Read, Write};
+
+#[test]
+fn test_start_with_roundtrip() {
+    let data = b"Hello, compressors! ".repeat(100);
+    let compressors = compressors();
+    let methods = [
+        CompressionMethod::STORE,
+        CompressionMethod::DEFLATE,
+        CompressionMethod::ZSTD,
+    ];
+
+    let mut output = Vec::new();
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    for method in methods {
+        let mut writer = archive
+            .new_file(format!("{method:?}.txt"))
+            .compression_method(method)
+            .start_with(&compressors)
+            .unwrap();
+        writer.write_all(&data).unwrap();
+        let compressed = writer.finish().unwrap();
+        if method == CompressionMethod::STORE {
+            assert_eq!(compressed, data.len() as u64);
+        } else {
+            assert!(compressed < data.len() as u64);
+        }
+    }
+    archive.finish().unwrap();
+
+    let decompressors = decompressors();
+    let archive = ZipArchive::from_slice(&output).unwrap();
+    let mut entries = archive.entries();
+    for method in methods {
+        let header = entries.next_entry().unwrap().unwrap();
+        assert_eq!(header.compression_method(), method);
+        let entry = archive.get_entry(header.wayfinder()).unwrap();
+        let mut actual = Vec::new();
+        let mut reader = entry.decoded_reader(&decompressors).unwrap();
+        reader.read_to_end(&mut actual).unwrap();
+        assert_eq!(actual, data);
+    }
+}
+
+#[test]
+fn test_start_with_unsupported_method_writes_nothing() {
+    let compressors = Compressors::new();
+    let mut output = Vec::new();
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    let err = archive
+        .new_file("file.txt")
+        .compression_method(CompressionMethod::BZIP2)
+        .start_with(&compressors)
+        .unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        ErrorKind::UnsupportedCompressionMethod(CompressionMethod::BZIP2)
+    ));
+    assert_eq!(archive.stream_offset(), 0);
+    archive.finish().unwrap();
+
+    let archive = ZipArchive::from_slice(&output).unwrap();
+    assert_eq!(archive.entries_hint(), 0);
+}
+
+#[test]
+fn test_start_with_factory_error_writes_nothing() {
+    let mut compressors = Compressors::new();
+    compressors.register(CompressionMethod::DEFLATE, |_| {
+        Err(std::io::Error::other("factory failed"))
+    });
+
+    let mut output = Vec::new();
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    let err = archive
+        .new_file("failed.txt")
+        .compression_method(CompressionMethod::DEFLATE)
+        .start_with(&compressors)
+        .unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::IO(_)));
+    assert_eq!(archive.stream_offset(), 0);
+
+    let mut writer = archive
+        .new_file("stored.txt")
+        .start_with(&compressors)
+        .unwrap();
+    writer.write_all(b"stored").unwrap();
+    writer.finish().unwrap();
+    archive.finish().unwrap();
+
+    let archive = ZipArchive::from_slice(&output).unwrap();
+    let mut entries = archive.entries();
+    let entry = entries.next_entry().unwrap().unwrap();
+    assert_eq!(entry.file_path().as_ref(), b"stored.txt");
+    assert_eq!(entry.local_header_offset(), 0);
+    assert!(entries.next_entry().unwrap().is_none());
+}
+
+#[test]
+fn test_start_with_rejects_encrypted() {
+    let mut output = Vec::new();
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    let err = archive
+        .new_file("file.txt")
+        .encrypted(true)
+        .start_with(&Compressors::new())
+        .unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidInput { .. }));
+}
+
+#[test]
+fn test_start_with_unfinished_entry_is_not_added() {
+    let compressors = compressors();
+    let mut output = Vec::new();
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    let mut writer = archive
+        .new_file("dropped.txt")
+        .compression_method(CompressionMethod::DEFLATE)
+        .start_with(&compressors)
+        .unwrap();
+    writer.write_all(b"never finished").unwrap();
+    drop(writer);
+    archive.finish().unwrap();
+
+    let archive = ZipArchive::from_slice(&output).unwrap();
+    assert_eq!(archive.entries_hint(), 0);
+}
+
+#[test]
+fn test_register_replaces_compressor() {
+    let mut compressors = compressors();
+    compressors.unregister(CompressionMethod::ZSTD);
+    assert!(!compressors.contains(CompressionMethod::ZSTD));
+    assert!(compressors.contains(CompressionMethod::DEFLATE));
+
+    compressors.register(CompressionMethod::STORE, |_| {
+        Err(std::io::Error::other("store disabled"))
+    });
+
+    let mut output = Vec::new();
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    let err = archive
+        .new_file("file.txt")
+        .start_with(&compressors)
+        .unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::IO(_)));
+    assert_eq!(
+        format!("{compressors:?}"),
+        "{CompressionMethod::STORE, CompressionMethod::DEFLATE}"
+    );
+}
@@ -0,0 +1,63 @@
+use crate::common::{TestEntry, decompressors, write_archive};
+use rawzip::{CompressionMethod, RECOMMENDED_BUFFER_SIZE, ZipArchive, dcl_implode};
+use std::io::Read;
+
+// Produced by a purpose-built encoder that reproduces the test vector of
+// zlib's blast byte for byte: ASCII mode with a 4 KiB dictionary, holding a
+// two byte match and a 300 byte match.
+const COMPRESSED: [u8; 26] = [
+    0x01, 0x06, 0x02, 0x05, 0xb6, 0xea, 0x1d, 0xcf, 0x34, 0x1d, 0x55, 0xc1, 0xf7, 0xbb, 0x8e, 0x67,
+    0x9a, 0x8e, 0xaa, 0x80, 0x32, 0x80, 0xe4, 0x23, 0xe0, 0x1f,
+];
+
+fn expected() -> Vec<u8> {
+    let mut expected = b"It's a blast, t,a blast!".to_vec();
+    for _ in 0..300 {
+        expected.push(expected[expected.len() - 8]);
+    }
+    expected
+}
+
+pub(crate) fn blast_archive() -> Vec<u8> {
+    let expected = expected();
+    write_archive(&[TestEntry::file("blast.txt", &expected)
+        .method(CompressionMethod::DCL_IMPLODE)
+        .compressed(&COMPRESSED)])
+}
+
+#[test]
+fn test_dcl_implode_entry() {
+    let data = blast_archive();
+    let archive = ZipArchive::from_slice(&data).unwrap();
+    let header = archive.entries().next().unwrap().unwrap();
+    assert_eq!(header.compression_method(), CompressionMethod::DCL_IMPLODE);
+
+    let decompressors = decompressors();
+
+    let entry = archive.get_entry(header.wayfinder()).unwrap();
+    let mut output = Vec::new();
+    entry
+        .decoded_reader(&decompressors)
+        .unwrap()
+        .read_to_end(&mut output)
+        .unwrap();
+    assert_eq!(output, expected());
+}
+
+#[test]
+fn test_dcl_implode_entry_from_reader() {
+    let data = blast_archive();
+    let mut buffer = vec![0u8; RECOMMENDED_BUFFER_SIZE];
+    let archive = ZipArchive::from_seekable(std::io::Cursor::new(data), &mut buffer).unwrap();
+    let mut entries = archive.entries(&mut buffer);
+    let header = entries.next_entry().unwrap().unwrap();
+    let entry = archive.get_entry(header.wayfinder()).unwrap();
+
+    let decoder = dcl_implode::Decoder::new(entry.reader());
+    let mut output = Vec::new();
+    entry
+        .verifying_reader(decoder)
+        .read_to_end(&mut output)
+        .unwrap();
+    assert_eq!(output, expected());
+}
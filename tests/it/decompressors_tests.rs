@@ -0,0 +1,144 @@
+use crate::common::{TestEntry, decompressors, write_archive};
+use rawzip::{CompressionMethod, Decompressors, ErrorKind, RECOMMENDED_BUFFER_SIZE, ZipArchive};
+use std::io::Read;
+
+#[test]
+fn test_decoded_reader() {
+    let data = b"Hello, decompressors! ".repeat(100);
+    let output = write_archive(&[
+        TestEntry::file("store.txt", &data),
+        TestEntry::file("deflate.txt", &data).method(CompressionMethod::DEFLATE),
+        TestEntry::file("zstd.txt", &data).method(CompressionMethod::ZSTD),
+    ]);
+    let decompressors = decompressors();
+
+    let archive = ZipArchive::from_slice(&output).unwrap();
+    for header in archive.entries() {
+        let header = header.unwrap();
+        let entry = archive.get_entry(header.wayfinder()).unwrap();
+        let mut actual = Vec::new();
+        let mut reader = entry.decoded_reader(&decompressors).unwrap();
+        reader.read_to_end(&mut actual).unwrap();
+        assert_eq!(actual, data);
+    }
+
+    let mut buffer = vec![0u8; RECOMMENDED_BUFFER_SIZE];
+    let archive = archive.into_reader();
+    let mut entries = archive.entries(&mut buffer);
+    while let Some(header) = entries.next_entry().unwrap() {
+        assert_eq!(
+            header.wayfinder().compression_method(),
+            header.compression_method()
+        );
+        let entry = archive.get_entry(header.wayfinder()).unwrap();
+        let mut actual = Vec::new();
+        let mut reader = entry.decoded_reader(&decompressors).unwrap();
+        reader.read_to_end(&mut actual).unwrap();
+        assert_eq!(actual, data);
+    }
+}
+
+#[test]
+fn test_decoded_reader_unsupported_method() {
+    let output =
+        write_archive(&[TestEntry::file("file.txt", b"data").method(CompressionMethod::DEFLATE)]);
+    let mut decompressors = decompressors();
+    decompressors.unregister(CompressionMethod::DEFLATE);
+    assert!(!decompressors.contains(CompressionMethod::DEFLATE));
+
+    let archive = ZipArchive::from_slice(&output).unwrap();
+    let header = archive.entries().next_entry().unwrap().unwrap();
+    let entry = archive.get_entry(header.wayfinder()).unwrap();
+    let err = entry.decoded_reader(&decompressors).err().unwrap();
+    assert!(matches!(
+        err.kind(),
+        ErrorKind::UnsupportedCompressionMethod(CompressionMethod::DEFLATE)
+    ));
+    assert_eq!(
+        err.to_string(),
+        "Unsupported compression method: 8 (DEFLATE)"
+    );
+}
+
+#[test]
+fn test_decoded_reader_verifies_data() {
+    let mut output = write_archive(&[TestEntry::file("file.txt", b"hello world")]);
+    let position = output
+        .windows(11)
+        .position(|x| x == b"hello world")
+        .unwrap();
+    output[position] = b'j';
+
+    let archive = ZipArchive::from_slice(&output).unwrap();
+    let header = archive.entries().next_entry().unwrap().unwrap();
+    let entry = archive.get_entry(header.wayfinder()).unwrap();
+    let mut reader = entry.decoded_reader(&Decompressors::new()).unwrap();
+    let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_decoded_reader_rejects_encrypted() {
+    let data = std::fs::read("assets/zipcrypto.zip").unwrap();
+    let archive = ZipArchive::from_slice(&data).unwrap();
+    let header = archive.entries().next_entry().unwrap().unwrap();
+    assert!(header.flags().is_encrypted());
+    let entry = archive.get_entry(header.wayfinder()).unwrap();
+    let err = entry.decoded_reader(&decompressors()).err().unwrap();
+    assert!(matches!(err.kind(), ErrorKind::InvalidInput { .. }));
+}
+
+#[test]
+fn test_register_replaces_and_shares_across_threads() {
+    let output = write_archive(&[TestEntry::file("file.txt", b"abc")]);
+    let mut decompressors = Decompressors::new();
+    decompressors.register(CompressionMethod::STORE, |reader, _| {
+        Ok(Box::new(reader.chain(&b"!"[..])))
+    });
+
+    // The replacement appends data, so verification fails
+    let decompressors = std::sync::Arc::new(decompressors);
+    let shared = decompressors.clone();
+    std::thread::spawn(move || {
+        let archive = ZipArchive::from_slice(&output).unwrap();
+        let header = archive.entries().next_entry().unwrap().unwrap();
+        let entry = archive.get_entry(header.wayfinder()).unwrap();
+        let mut reader = entry.decoded_reader(&shared).unwrap();
+        assert!(reader.read_to_end(&mut Vec::new()).is_err());
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(format!("{decompressors:?}"), "{CompressionMethod::STORE}");
+}
+
+#[test]
+#[cfg(any(
+    feature = "deflate64",
+    feature = "dcl-implode",
+    feature = "legacy-methods"
+))]
+fn test_decoded_reader_builtin_decoders() {
+    let decompressors = decompressors();
+    let archives: Vec<Vec<u8>> = vec![
+        #[cfg(feature = "deflate64")]
+        std::fs::read("assets/deflate64.zip").unwrap(),
+        #[cfg(feature = "dcl-implode")]
+        crate::dcl_implode_tests::blast_archive(),
+        #[cfg(feature = "legacy-methods")]
+        std::fs::read("assets/legacy.zip").unwrap(),
+    ];
+
+    for data in archives {
+        let archive = ZipArchive::from_slice(&data).unwrap();
+        for header in archive.entries() {
+            let header = header.unwrap();
+            assert!(decompressors.contains(header.compression_method()));
+            let entry = archive.get_entry(header.wayfinder()).unwrap();
+            let mut output = Vec::new();
+            let mut reader = entry.decoded_reader(&decompressors).unwrap();
+            reader.read_to_end(&mut output).unwrap();
+            assert_eq!(output.len() as u64, header.uncompressed_size_hint());
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use crate::common::decompressors;
+use rawzip::{CompressionMethod, RECOMMENDED_BUFFER_SIZE, ZipArchive, deflate64};
+use std::io::Read;
+
+// assets/deflate64.zip was produced by a purpose-built encoder and checked
+// with Info-ZIP's `unzip -t`. Its first entry mixes dynamic, fixed, and
+// stored blocks and uses length code 285 with 16 extra bits as well as
+// distance codes 30 and 31.
+const FIXTURE: &str = "assets/deflate64.zip";
+
+#[test]
+fn test_deflate64_fixture() {
+    let data = std::fs::read(FIXTURE).unwrap();
+    let archive = ZipArchive::from_slice(&data).unwrap();
+    let decompressors = decompressors();
+
+    let mut actual = Vec::new();
+    for header in archive.entries() {
+        let header = header.unwrap();
+        assert_eq!(header.compression_method(), CompressionMethod::DEFLATE64);
+        let entry = archive.get_entry(header.wayfinder()).unwrap();
+        let mut output = Vec::new();
+        let mut reader = entry.decoded_reader(&decompressors).unwrap();
+        reader.read_to_end(&mut output).unwrap();
+        actual.push((header.file_path().as_ref().to_vec(), output));
+    }
+
+    assert_eq!(actual.len(), 3);
+    assert_eq!(actual[0].0, b"deflate64.txt");
+    assert_eq!(actual[0].1.len(), 132400);
+    assert!(
+        actual[0]
+            .1
+            .ends_with(b"line 00399: the quick brown fox jumps over the lazy dog\n")
+    );
+    assert_eq!(actual[1].0, b"fixed.txt");
+    assert_eq!(
+        actual[1].1,
+        b"Deflate64 shares its format with deflate, except for a 64 KiB window.\n"
+    );
+    assert_eq!(actual[2].0, b"empty.txt");
+    assert_eq!(actual[2].1, b"");
+}
+
+#[test]
+fn test_deflate64_small_reads() {
+    let file = std::fs::File::open(FIXTURE).unwrap();
+    let mut buffer = vec![0u8; RECOMMENDED_BUFFER_SIZE];
+    let archive = ZipArchive::from_file(file, &mut buffer).unwrap();
+    let mut entries = archive.entries(&mut buffer);
+    let header = entries.next_entry().unwrap().unwrap();
+    let entry = archive.get_entry(header.wayfinder()).unwrap();
+
+    let mut expected = Vec::new();
+    let decoder = deflate64::Decoder::new(entry.reader());
+    entry
+        .verifying_reader(decoder)
+        .read_to_end(&mut expected)
+        .unwrap();
+
+    for size in [1, 7, 4096] {
+        let mut decoder = entry.verifying_reader(deflate64::Decoder::new(entry.reader()));
+        let mut chunk = vec![0u8; size];
+        let mut actual = Vec::new();
+        loop {
+            let read = decoder.read(&mut chunk).unwrap();
+            if read == 0 {
+                break;
+            }
+            actual.extend_from_slice(&chunk[..read]);
+        }
+        assert_eq!(actual, expected, "read size {size}");
+    }
+}
+
+#[test]
+fn test_deflate64_truncated() {
+    let data = std::fs::read(FIXTURE).unwrap();
+    let archive = ZipArchive::from_slice(&data).unwrap();
+    let header = archive.entries().next_entry().unwrap().unwrap();
+    let entry = archive.get_entry(header.wayfinder()).unwrap();
+    let compressed = entry.data();
+
+    let mut decoder = deflate64::Decoder::new(&compressed[..compressed.len() / 2]);
+    let err = decoder.read_to_end(&mut Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+}
@@ -0,0 +1,187 @@
+use crate::common::{TestEntry, decompressors, write_archive};
+use rawzip::diff::{ChangeClass, Changes, Differ, EntryChange};
+use rawzip::time::UtcDateTime;
+use rawzip::{CompressionMethod, Decompressors, ZipArchive};
+use std::io::Cursor;
+
+/// Two contents of the same length and CRC32.
+const COLLIDING_A: &[u8] = b"release-1.0\n\x00\x00\x00\x00";
+const COLLIDING_B: &[u8] = b"release-2.0\n\xee\xaf\xb5\x12";
+
+/// A stored file with fixed metadata, so that archives differ only where a
+/// test changes them.
+fn file<'a>(name: &'a str, contents: &'a [u8]) -> TestEntry<'a> {
+    TestEntry::file(name, contents)
+        .mtime(UtcDateTime::from_unix(1_700_000_000))
+        .permissions(0o644)
+}
+
+fn diff(
+    old: &[u8],
+    new: &[u8],
+    decompressors: Option<&Decompressors>,
+) -> rawzip::diff::ArchiveDiff {
+    let old = ZipArchive::from_slice(old).unwrap().into_reader();
+    let new = ZipArchive::from_slice(new).unwrap().into_reader();
+    let differ = match decompressors {
+        Some(decompressors) => Differ::new().verify_content(decompressors),
+        None => Differ::new(),
+    };
+    differ.diff(&old, &new).unwrap()
+}
+
+#[test]
+fn test_identical_archives() {
+    let files = [file("a.txt", b"one"), file("b.txt", b"two")];
+    let data = write_archive(&files);
+    assert!(diff(&data, &data, None).is_empty());
+    assert!(diff(&data, &data, Some(&Decompressors::new())).is_empty());
+}
+
+#[test]
+fn test_added_removed_changed_ordered_by_name() {
+    let old = write_archive(&[
+        file("z.txt", b"last"),
+        file("b.txt", b"two"),
+        file("a.txt", b"one"),
+    ]);
+    let new = write_archive(&[
+        file("c.txt", b"three"),
+        file("b.txt", b"2"),
+        file("z.txt", b"last"),
+    ]);
+
+    let result = diff(&old, &new, None);
+    let summary = result
+        .entries()
+        .iter()
+        .map(|x| (x.name(), *x.change()))
+        .collect::<Vec<_>>();
+
+    // b.txt moves to the front, which shifts z.txt without changing it
+    let b_changes = Changes::CRC32 | Changes::UNCOMPRESSED_SIZE | Changes::COMPRESSED_SIZE;
+    assert_eq!(
+        summary,
+        vec![
+            ("a.txt", EntryChange::Removed),
+            ("b.txt", EntryChange::Changed(b_changes | Changes::OFFSET)),
+            ("c.txt", EntryChange::Added),
+            ("z.txt", EntryChange::Changed(Changes::OFFSET)),
+        ]
+    );
+
+    assert_eq!(result.entries()[1].class(), Some(ChangeClass::Content));
+    assert_eq!(result.entries()[3].class(), Some(ChangeClass::Layout));
+    assert_eq!(result.added().count(), 1);
+    assert_eq!(result.removed().count(), 1);
+    assert_eq!(result.changed().count(), 2);
+}
+
+#[test]
+fn test_metadata_changes() {
+    let file = file("a.txt", b"one");
+    let old = write_archive(std::slice::from_ref(&file));
+
+    let cases = [
+        (
+            file.clone().mtime(UtcDateTime::from_unix(1_800_000_000)),
+            Changes::LAST_MODIFIED,
+        ),
+        (file.clone().permissions(0o755), Changes::MODE),
+        (file.clone().comment("hello"), Changes::COMMENT),
+    ];
+
+    for (changed, expected) in cases {
+        let new = write_archive(&[changed]);
+        let result = diff(&old, &new, None);
+        let entry = &result.entries()[0];
+        let EntryChange::Changed(changes) = *entry.change() else {
+            panic!("expected a change: {:?}", entry.change());
+        };
+        assert!(changes.contains(expected), "{changes:?} lacks {expected:?}");
+        assert_eq!(entry.class(), Some(ChangeClass::Metadata));
+    }
+}
+
+#[test]
+fn test_recompressed_entry_is_layout_only() {
+    let contents = b"the same contents, stored and deflated, the same contents".repeat(4);
+    let stored = file("a.txt", &contents);
+    let deflated = stored.clone().method(CompressionMethod::DEFLATE);
+    let old = write_archive(&[stored]);
+    let new = write_archive(&[deflated]);
+
+    let decompressors = decompressors();
+    let result = diff(&old, &new, Some(&decompressors));
+    let entry = &result.entries()[0];
+    let EntryChange::Changed(changes) = *entry.change() else {
+        panic!("expected a change: {:?}", entry.change());
+    };
+    assert!(changes.contains(Changes::COMPRESSION_METHOD | Changes::COMPRESSED_SIZE));
+    assert!(!changes.contains(Changes::DATA));
+    assert_eq!(entry.class(), Some(ChangeClass::Layout));
+}
+
+#[test]
+fn test_verify_content_detects_crc_collision() {
+    assert_eq!(rawzip::crc32(COLLIDING_A), rawzip::crc32(COLLIDING_B));
+    let old = write_archive(&[file("a.bin", COLLIDING_A)]);
+    let new = write_archive(&[file("a.bin", COLLIDING_B)]);
+
+    // The records alone are identical
+    assert!(diff(&old, &new, None).is_empty());
+
+    let result = diff(&old, &new, Some(&Decompressors::new()));
+    assert_eq!(
+        result.entries()[0].change(),
+        &EntryChange::Changed(Changes::DATA)
+    );
+    assert_eq!(result.entries()[0].class(), Some(ChangeClass::Content));
+}
+
+#[test]
+fn test_verify_content_requires_decompressor() {
+    let deflated = file("a.txt", b"one").method(CompressionMethod::DEFLATE);
+    let data = write_archive(&[deflated]);
+
+    let old = ZipArchive::from_slice(&data).unwrap().into_reader();
+    let new = ZipArchive::from_slice(&data).unwrap().into_reader();
+    let decompressors = Decompressors::new();
+    let err = Differ::new()
+        .verify_content(&decompressors)
+        .diff(&old, &new)
+        .unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        rawzip::ErrorKind::UnsupportedCompressionMethod(CompressionMethod::DEFLATE)
+    ));
+}
+
+#[test]
+fn test_duplicate_names_pair_in_order() {
+    let old = write_archive(&[file("dup", b"first"), file("dup", b"second")]);
+    let new = write_archive(&[file("dup", b"first")]);
+
+    let result = diff(&old, &new, None);
+    let summary = result
+        .entries()
+        .iter()
+        .map(|x| (x.raw_name(), *x.change()))
+        .collect::<Vec<_>>();
+    assert_eq!(summary, vec![(&b"dup"[..], EntryChange::Removed)]);
+}
+
+#[test]
+fn test_reader_archives() {
+    let old = write_archive(&[file("a.txt", b"one")]);
+    let new = write_archive(&[file("a.txt", b"uno")]);
+
+    let mut buffer = vec![0u8; rawzip::RECOMMENDED_BUFFER_SIZE];
+    let old = ZipArchive::from_seekable(Cursor::new(old), &mut buffer).unwrap();
+    let new = ZipArchive::from_seekable(Cursor::new(new), &mut buffer).unwrap();
+    let result = Differ::new().diff(&old, &new).unwrap();
+    assert_eq!(
+        result.entries()[0].change(),
+        &EntryChange::Changed(Changes::CRC32)
+    );
+}
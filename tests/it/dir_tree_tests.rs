@@ -0,0 +1,272 @@
+use crate::extract_tests::TestDir;
+use rawzip::time::ZipDateTimeKind;
+use rawzip::{
+    CompressionMethod, Compressors, Encoder, ErrorKind, SymlinkHandling, ZipArchive,
+    ZipArchiveWriter,
+};
+use std::io::Read;
+use std::path::Path;
+
+struct ListedEntry {
+    name: String,
+    method: CompressionMethod,
+    mode: u32,
+    data: Vec<u8>,
+    modified: ZipDateTimeKind,
+}
+
+fn list(data: &[u8]) -> Vec<ListedEntry> {
+    let archive = ZipArchive::from_slice(data).unwrap();
+    let mut entries = archive.entries();
+    let mut result = Vec::new();
+    while let Some(entry) = entries.next_entry().unwrap() {
+        let file = archive.get_entry(entry.wayfinder()).unwrap();
+        let mut data = Vec::new();
+        match entry.compression_method() {
+            CompressionMethod::DEFLATE => {
+                let decoder = flate2::read::DeflateDecoder::new(file.data());
+                let mut reader = file.verifying_reader(decoder);
+                reader.read_to_end(&mut data).unwrap();
+            }
+            _ => {
+                let mut reader = file.verifying_reader(file.data());
+                reader.read_to_end(&mut data).unwrap();
+            }
+        }
+
+        result.push(ListedEntry {
+            name: String::from_utf8(entry.file_path().as_ref().to_vec()).unwrap(),
+            method: entry.compression_method(),
+            mode: entry.mode().value(),
+            data,
+            modified: entry.last_modified(),
+        });
+    }
+    result
+}
+
+fn names(entries: &[ListedEntry]) -> Vec<&str> {
+    entries.iter().map(|x| x.name.as_str()).collect()
+}
+
+fn create_tree(root: &Path) {
+    std::fs::create_dir_all(root.join("src/bin")).unwrap();
+    std::fs::create_dir_all(root.join("target")).unwrap();
+    std::fs::write(root.join("README.md"), b"# readme").unwrap();
+    std::fs::write(root.join("src/lib.rs"), b"pub fn lib() {}").unwrap();
+    std::fs::write(root.join("src/bin/main.rs"), b"fn main() {}").unwrap();
+    std::fs::write(root.join("target/out"), b"binary").unwrap();
+}
+
+#[test]
+fn test_add_dir_all() {
+    let dir = TestDir::new("dir-tree-basic");
+    create_tree(dir.path());
+
+    let mut output = Vec::new();
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    archive.add_dir_all(dir.path()).create().unwrap();
+    archive.finish().unwrap();
+
+    let entries = list(&output);
+    assert_eq!(
+        names(&entries),
+        [
+            "README.md",
+            "src/",
+            "src/bin/",
+            "src/bin/main.rs",
+            "src/lib.rs",
+            "target/",
+            "target/out",
+        ]
+    );
+
+    let main = &entries[3];
+    assert_eq!(main.data, b"fn main() {}");
+    assert_eq!(main.method, CompressionMethod::STORE);
+
+    let mtime = std::fs::metadata(dir.path().join("src/bin/main.rs"))
+        .unwrap()
+        .modified()
+        .unwrap()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap();
+    match main.modified {
+        ZipDateTimeKind::Utc(time) => assert_eq!(time.to_unix(), mtime.as_secs() as i64),
+        ZipDateTimeKind::Local(_) => panic!("expected a UTC modification time"),
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.path().join("src/lib.rs");
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o750)).unwrap();
+
+        let mut output = Vec::new();
+        let mut archive = ZipArchiveWriter::new(&mut output);
+        archive.add_dir_all(dir.path()).create().unwrap();
+        archive.finish().unwrap();
+
+        let entries = list(&output);
+        assert_eq!(entries[1].mode & 0o170000, 0o040000);
+        assert_eq!(entries[4].mode, 0o100750);
+    }
+}
+
+#[test]
+fn test_add_dir_all_filter_and_compressor() {
+    let dir = TestDir::new("dir-tree-filter");
+    create_tree(dir.path());
+
+    let mut compressors = Compressors::new();
+    compressors.register(CompressionMethod::DEFLATE, |sink| {
+        let encoder = flate2::write::DeflateEncoder::new(sink, flate2::Compression::best());
+        Ok(Encoder::new(encoder, |encoder| encoder.finish()))
+    });
+
+    let mut visited = Vec::new();
+    let mut output = Vec::new();
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    archive
+        .add_dir_all(dir.path())
+        .filter(|name| {
+            visited.push(name.to_string());
+            name != "target/" && name != "src/bin/"
+        })
+        .compressor(
+            |name| match name.ends_with(".rs") {
+                true => CompressionMethod::DEFLATE,
+                false => CompressionMethod::STORE,
+            },
+            &compressors,
+        )
+        .create()
+        .unwrap();
+    archive.finish().unwrap();
+
+    // Excluded directories are not descended into
+    assert_eq!(
+        visited,
+        ["README.md", "src/", "src/bin/", "src/lib.rs", "target/"]
+    );
+
+    let entries = list(&output);
+    assert_eq!(names(&entries), ["README.md", "src/", "src/lib.rs"]);
+    assert_eq!(entries[0].method, CompressionMethod::STORE);
+    assert_eq!(entries[2].method, CompressionMethod::DEFLATE);
+    assert_eq!(entries[2].data, b"pub fn lib() {}");
+}
+
+#[test]
+fn test_add_dir_all_requires_directory() {
+    let dir = TestDir::new("dir-tree-not-dir");
+    std::fs::create_dir_all(dir.path()).unwrap();
+    std::fs::write(dir.path().join("file"), b"data").unwrap();
+
+    let mut output = Vec::new();
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    let err = archive
+        .add_dir_all(dir.path().join("file"))
+        .create()
+        .unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::IO(_)));
+
+    let err = archive
+        .add_dir_all(dir.path().join("missing"))
+        .create()
+        .unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::IO(_)));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_add_dir_all_symlinks() {
+    use std::os::unix::fs::symlink;
+
+    let dir = TestDir::new("dir-tree-symlinks");
+    create_tree(dir.path());
+    symlink("src/lib.rs", dir.path().join("lib-link")).unwrap();
+    symlink("src", dir.path().join("src-link")).unwrap();
+
+    let archive_with = |symlinks| {
+        let mut output = Vec::new();
+        let mut archive = ZipArchiveWriter::new(&mut output);
+        archive
+            .add_dir_all(dir.path())
+            .symlinks(symlinks)
+            .filter(|name| !name.starts_with("target/"))
+            .create()
+            .unwrap();
+        archive.finish().unwrap();
+        list(&output)
+    };
+
+    let entries = archive_with(SymlinkHandling::Store);
+    assert_eq!(
+        names(&entries),
+        [
+            "README.md",
+            "lib-link",
+            "src/",
+            "src/bin/",
+            "src/bin/main.rs",
+            "src/lib.rs",
+            "src-link",
+        ]
+    );
+    assert_eq!(entries[1].mode & 0o170000, 0o120000);
+    assert_eq!(entries[1].data, b"src/lib.rs");
+    assert_eq!(entries[6].data, b"src");
+
+    let entries = archive_with(SymlinkHandling::Skip);
+    assert_eq!(
+        names(&entries),
+        [
+            "README.md",
+            "src/",
+            "src/bin/",
+            "src/bin/main.rs",
+            "src/lib.rs"
+        ]
+    );
+
+    let entries = archive_with(SymlinkHandling::Follow);
+    assert_eq!(
+        names(&entries),
+        [
+            "README.md",
+            "lib-link",
+            "src/",
+            "src/bin/",
+            "src/bin/main.rs",
+            "src/lib.rs",
+            "src-link/",
+            "src-link/bin/",
+            "src-link/bin/main.rs",
+            "src-link/lib.rs",
+        ]
+    );
+    assert_eq!(entries[1].mode & 0o170000, 0o100000);
+    assert_eq!(entries[1].data, b"pub fn lib() {}");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_add_dir_all_follow_cycle() {
+    let dir = TestDir::new("dir-tree-cycle");
+    std::fs::create_dir_all(dir.path().join("a")).unwrap();
+    std::os::unix::fs::symlink("..", dir.path().join("a/up")).unwrap();
+
+    let mut output = Vec::new();
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    let err = archive
+        .add_dir_all(dir.path())
+        .symlinks(SymlinkHandling::Follow)
+        .create()
+        .unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidInput { .. }));
+
+    // Stored links are never traversed
+    archive.add_dir_all(dir.path()).create().unwrap();
+}
@@ -1,224 +1,70 @@
-use aes::{Aes128, Aes192, Aes256};
-use ctr::cipher::{KeyIvInit, StreamCipher};
-use hmac::{Hmac, KeyInit, Mac};
-use pbkdf2::pbkdf2;
+use crate::aes_primitives::RustCrypto;
 use rawzip::{
-    CompressionMethod, Crc32Option, Header, RECOMMENDED_BUFFER_SIZE, ZipArchive, ZipArchiveWriter,
-    ZipLocalFileHeader, ZipLocator,
-    extra_fields::ExtraFieldId,
-    zipcrypto::{Decryptor, Encryptor},
+    CompressionMethod, Compressors, Encoder, Encryption, ErrorKind, RECOMMENDED_BUFFER_SIZE,
+    ZipArchive, ZipArchiveWriter, ZipLocator,
+    aes::{self as winzip_aes, Parameters, Strength, Version},
+    zipcrypto::{self, Decryptor, Encryptor},
 };
-use sha1::Sha1;
-use std::io::{self, Read, Write};
-
-// WinZip AES always uses a 128-bit (AES block size) little-endian CTR counter,
-// regardless of the key size.
-type Aes128Ctr = ctr::Ctr128LE<Aes128>;
-type Aes192Ctr = ctr::Ctr128LE<Aes192>;
-type Aes256Ctr = ctr::Ctr128LE<Aes256>;
-type HmacSha1 = Hmac<Sha1>;
+use std::io::{Read, Write};
 
 const PASSWORD: &[u8] = b"rawzipiscool";
-const AES_EXTRA_FIELD_LEN: usize = 7;
-const PASSWORD_VERIFIER_LEN: usize = 2;
-const AUTH_CODE_LEN: usize = 10;
-
-/// Salt and key lengths for the AES strength byte (APPNOTE Appendix E). The
-/// salt is always half the key length.
-fn aes_key_lengths(strength: u8) -> (usize /* salt */, usize /* key */) {
-    let key_len = match strength {
-        1 => 16, // AES-128
-        2 => 24, // AES-192
-        3 => 32, // AES-256
-        other => unreachable!("invalid AES strength: {other}"),
-    };
-    (key_len / 2, key_len)
-}
-
-/// An AES-CTR keystream cipher whose key size is selected at runtime from the
-/// strength byte. Using an enum instead of `Box<dyn StreamCipher>` avoids the
-/// heap allocation and per-read virtual dispatch.
-enum AesCipher {
-    Aes128(Aes128Ctr),
-    Aes192(Aes192Ctr),
-    Aes256(Aes256Ctr),
-}
-
-impl AesCipher {
-    fn apply_keystream(&mut self, buf: &mut [u8]) {
-        match self {
-            AesCipher::Aes128(c) => c.apply_keystream(buf),
-            AesCipher::Aes192(c) => c.apply_keystream(buf),
-            AesCipher::Aes256(c) => c.apply_keystream(buf),
-        }
-    }
-}
-
-/// Constructs the appropriate AES-CTR keystream cipher for the given strength.
-fn new_cipher(strength: u8, key: &[u8], counter: &[u8; 16]) -> AesCipher {
-    match strength {
-        1 => AesCipher::Aes128(Aes128Ctr::new_from_slices(key, counter).unwrap()),
-        2 => AesCipher::Aes192(Aes192Ctr::new_from_slices(key, counter).unwrap()),
-        3 => AesCipher::Aes256(Aes256Ctr::new_from_slices(key, counter).unwrap()),
-        other => unreachable!("invalid AES strength: {other}"),
-    }
-}
-
-#[derive(Debug, PartialEq, Eq)]
-struct AesExtraField {
-    vendor_version: u16,
-    vendor_id: [u8; 2],
-    strength: u8,
-    compression_method: CompressionMethod,
-}
-
-struct AesReader<R> {
-    reader: R,
-    cipher: AesCipher,
-    mac: HmacSha1,
-}
-
-impl<R: Read> Read for AesReader<R> {
-    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        let read = self.reader.read(buf)?;
-        self.mac.update(&buf[..read]);
-        self.cipher.apply_keystream(&mut buf[..read]);
-        Ok(read)
-    }
-}
 
-impl<R> AesReader<R> {
-    fn into_parts(self) -> (R, HmacSha1) {
-        (self.reader, self.mac)
-    }
-}
-
-fn parse_aes_extra_field(data: &[u8]) -> AesExtraField {
-    assert_eq!(data.len(), AES_EXTRA_FIELD_LEN);
-    AesExtraField {
-        vendor_version: u16::from_le_bytes(data[0..2].try_into().unwrap()),
-        vendor_id: data[2..4].try_into().unwrap(),
-        strength: data[4],
-        compression_method: u16::from_le_bytes(data[5..7].try_into().unwrap()).into(),
-    }
-}
-
-fn find_aes_extra_field<'a>(
-    fields: impl Iterator<Item = (ExtraFieldId, &'a [u8])>,
-) -> AesExtraField {
-    let fields = fields
-        .filter(|(id, _)| *id == ExtraFieldId::AES)
-        .map(|(_, data)| parse_aes_extra_field(data))
-        .collect::<Vec<_>>();
-    assert_eq!(fields.len(), 1, "expected exactly one AES extra field");
-    fields.into_iter().next().unwrap()
-}
-
-/// The keys and password verifier WinZip AES derives from the password and salt
-/// via PBKDF2-HMAC-SHA1 (1000 iterations), in their on-disk order (WinZip AES /
-/// APPNOTE Appendix E).
-struct AesKeys {
-    encryption_key: Vec<u8>,
-    authentication_key: Vec<u8>,
-    password_verifier: [u8; PASSWORD_VERIFIER_LEN],
-}
-
-fn derive_aes_keys(salt: &[u8], key_len: usize) -> AesKeys {
-    let mut derived = vec![0u8; key_len * 2 + PASSWORD_VERIFIER_LEN];
-    pbkdf2::<HmacSha1>(PASSWORD, salt, 1_000, &mut derived).unwrap();
-    let (encryption_key, rest) = derived.split_at(key_len);
-    let (authentication_key, password_verifier) = rest.split_at(key_len);
-    AesKeys {
-        encryption_key: encryption_key.to_vec(),
-        authentication_key: authentication_key.to_vec(),
-        password_verifier: password_verifier.try_into().unwrap(),
-    }
-}
-
-/// Reads a WinZip AES payload (salt, password verifier, ciphertext, auth code)
-/// from `reader`, then decrypts, decompresses, and verifies it, returning the
-/// recovered plaintext.
+/// Decrypts and decompresses a WinZip AES payload (salt, password verifier,
+/// ciphertext, auth code) from `reader`, checking the authentication code and
+/// that the whole payload was consumed.
 fn decrypt_winzip_aes_payload<R: Read>(
-    mut reader: R,
+    reader: R,
     compressed_size: u64,
-    strength: u8,
-    compression_method: CompressionMethod,
+    parameters: &Parameters,
 ) -> Vec<u8> {
-    let (salt_len, key_len) = aes_key_lengths(strength);
-    let ciphertext_len = compressed_size
-        .checked_sub((salt_len + PASSWORD_VERIFIER_LEN + AUTH_CODE_LEN) as u64)
-        .unwrap();
-
-    let mut salt = vec![0u8; salt_len];
-    let mut password_verifier = [0u8; PASSWORD_VERIFIER_LEN];
-    reader.read_exact(&mut salt).unwrap();
-    reader.read_exact(&mut password_verifier).unwrap();
-
-    let keys = derive_aes_keys(&salt, key_len);
-    assert_eq!(password_verifier, keys.password_verifier);
-
-    let mac = HmacSha1::new_from_slice(&keys.authentication_key).unwrap();
-    let mut counter = [0u8; 16];
-    counter[0] = 1;
-    let cipher = new_cipher(strength, &keys.encryption_key, &counter);
-    let ciphertext = reader.by_ref().take(ciphertext_len);
-    let aes_reader = AesReader {
-        reader: ciphertext,
-        cipher,
-        mac,
-    };
+    let decryptor = winzip_aes::Decryptor::new(
+        reader,
+        &RustCrypto,
+        PASSWORD,
+        parameters.strength,
+        compressed_size,
+    )
+    .unwrap();
 
     let mut output = Vec::new();
-    let mut decoder = match compression_method {
-        CompressionMethod::DEFLATE => flate2::read::DeflateDecoder::new(aes_reader),
+    let mut decoder = match parameters.compression_method {
+        CompressionMethod::DEFLATE => flate2::read::DeflateDecoder::new(decryptor),
         method => panic!("unsupported AES compression method: {method:?}"),
     };
     decoder.read_to_end(&mut output).unwrap();
 
-    let aes_reader = decoder.into_inner();
-    let (ciphertext, mac) = aes_reader.into_parts();
-    assert_eq!(ciphertext.limit(), 0);
-
-    let mut authentication_code = [0u8; AUTH_CODE_LEN];
-    reader.read_exact(&mut authentication_code).unwrap();
+    let mut reader = decoder.into_inner().finish().unwrap();
     assert_eq!(reader.read(&mut [0u8; 1]).unwrap(), 0);
-    let computed_authentication_code = mac.finalize().into_bytes();
-    assert_eq!(
-        &authentication_code,
-        &computed_authentication_code[..AUTH_CODE_LEN]
-    );
-
     output
 }
 
 #[test]
 fn decrypt_winzip_aes128_entry_using_rawzip_primitives() {
-    decrypt_winzip_aes_entry("assets/aes128.zip", 1, 2);
+    decrypt_winzip_aes_entry("assets/aes128.zip", Strength::Aes128, Version::Ae2);
 }
 
 #[test]
 fn decrypt_winzip_aes192_entry_using_rawzip_primitives() {
-    decrypt_winzip_aes_entry("assets/aes192.zip", 2, 2);
+    decrypt_winzip_aes_entry("assets/aes192.zip", Strength::Aes192, Version::Ae2);
 }
 
 #[test]
 fn decrypt_winzip_aes256_entry_using_rawzip_primitives() {
-    decrypt_winzip_aes_entry("assets/aes256.zip", 3, 2);
+    decrypt_winzip_aes_entry("assets/aes256.zip", Strength::Aes256, Version::Ae2);
 }
 
 #[test]
 fn decrypt_winzip_aes256_ae1_entry_using_rawzip_primitives() {
-    decrypt_winzip_aes_entry("assets/aes256-ae1.zip", 3, 1);
+    decrypt_winzip_aes_entry("assets/aes256-ae1.zip", Strength::Aes256, Version::Ae1);
 }
 
-fn decrypt_winzip_aes_entry(path: &str, expected_strength: u8, expected_vendor_version: u16) {
+fn decrypt_winzip_aes_entry(path: &str, expected_strength: Strength, expected_version: Version) {
     let file = std::fs::File::open(path).unwrap();
     let mut buffer = vec![0u8; RECOMMENDED_BUFFER_SIZE];
     let archive = ZipArchive::from_file(file, &mut buffer).unwrap();
 
-    let expected_metadata = AesExtraField {
-        vendor_version: expected_vendor_version,
-        vendor_id: *b"AE",
+    let expected_parameters = Parameters {
+        version: expected_version,
         strength: expected_strength,
         compression_method: CompressionMethod::DEFLATE,
     };
@@ -230,17 +76,27 @@ fn decrypt_winzip_aes_entry(path: &str, expected_strength: u8, expected_vendor_v
     assert!(entry.flags().is_encrypted());
     assert!(!entry.flags().has_strong_encryption());
 
-    let central_metadata = find_aes_extra_field(entry.extra_fields());
-    assert_eq!(central_metadata, expected_metadata);
+    let central_parameters = Parameters::from_extra_fields(entry.extra_fields()).unwrap();
+    assert_eq!(central_parameters, expected_parameters);
+    assert_eq!(
+        entry.encryption(),
+        Encryption::WinZipAes {
+            version: Some(expected_version),
+            strength: Some(expected_strength),
+        }
+    );
+    assert_eq!(
+        entry.effective_compression_method(),
+        CompressionMethod::DEFLATE
+    );
 
     let stored_crc = entry.crc32();
-    match central_metadata.vendor_version {
+    match central_parameters.version {
         // APPNOTE Appendix E.6.2 requires AE-2 entries to store zero in the CRC
         // field.
-        2 => assert_eq!(stored_crc, 0),
+        Version::Ae2 => assert_eq!(stored_crc, 0),
         // AE-1 entries retain the real CRC32 of the uncompressed data
-        1 => assert_eq!(stored_crc, 2783462679),
-        other => panic!("unexpected AES vendor version: {other}"),
+        Version::Ae1 => assert_eq!(stored_crc, 2783462679),
     }
 
     let compressed_size = entry.compressed_size_hint();
@@ -250,15 +106,19 @@ fn decrypt_winzip_aes_entry(path: &str, expected_strength: u8, expected_vendor_v
     let local_header = encrypted_entry
         .local_header(&mut local_header_buffer)
         .unwrap();
-    let local_metadata = find_aes_extra_field(local_header.extra_fields());
-    assert_eq!(local_metadata, expected_metadata);
+    let local_parameters = Parameters::from_extra_fields(local_header.extra_fields()).unwrap();
+    assert_eq!(local_parameters, expected_parameters);
     assert!(local_header.flags().is_encrypted());
+    assert_eq!(local_header.encryption(), entry.encryption());
+    assert_eq!(
+        local_header.effective_compression_method(),
+        CompressionMethod::DEFLATE
+    );
 
     let output = decrypt_winzip_aes_payload(
         encrypted_entry.reader(),
         compressed_size,
-        central_metadata.strength,
-        central_metadata.compression_method,
+        &central_parameters,
     );
 
     assert_eq!(output.len() as u64, uncompressed_size);
@@ -267,128 +127,67 @@ fn decrypt_winzip_aes_entry(path: &str, expected_strength: u8, expected_vendor_v
     let output_crc = rawzip::crc32(&output);
     assert_ne!(output_crc, 0);
     // AE-1 stores the real CRC32, so it must match the decrypted data.
-    if central_metadata.vendor_version == 1 {
+    if central_parameters.version.stores_crc32() {
         assert_eq!(output_crc, stored_crc);
     }
 }
 
-const AES_WRITE_BUFFER_LEN: usize = 8 * 1024;
-
-/// A writer that encrypts compressed data with AES-CTR and accumulates the
-/// WinZip authentication code over the produced ciphertext (encrypt-then-MAC).
-///
-/// This is the write-side counterpart to [`AesReader`]: deflate-compressed
-/// bytes are fed in, encrypted, and forwarded to the underlying archive writer.
-struct AesWriter<W> {
-    writer: W,
-    cipher: AesCipher,
-    mac: HmacSha1,
-    scratch: Box<[u8; AES_WRITE_BUFFER_LEN]>,
-}
-
-impl<W: Write> Write for AesWriter<W> {
-    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        for chunk in buf.chunks(AES_WRITE_BUFFER_LEN) {
-            let encrypted = &mut self.scratch[..chunk.len()];
-            encrypted.copy_from_slice(chunk);
-            self.cipher.apply_keystream(encrypted);
-            self.mac.update(encrypted);
-            self.writer.write_all(encrypted)?;
-        }
-        Ok(buf.len())
-    }
-
-    fn flush(&mut self) -> io::Result<()> {
-        self.writer.flush()
-    }
-}
-
-impl<W> AesWriter<W> {
-    fn into_mac(self) -> HmacSha1 {
-        self.mac
-    }
-}
+#[test]
+fn decrypt_winzip_aes_wrong_password_fails() {
+    let data = std::fs::read("assets/aes256.zip").unwrap();
+    let archive = ZipArchive::from_slice(&data).unwrap();
+    let entry = archive.entries().next().unwrap().unwrap();
+    let zip_entry = archive.get_entry(entry.wayfinder()).unwrap();
 
-/// Builds the 7-byte WinZip AES extra field (APPNOTE Appendix E):
-/// vendor version, the "AE" vendor id, the AES strength byte, and the
-/// compression method actually applied before encryption.
-fn aes_extra_field(vendor_version: u16, strength: u8, method: CompressionMethod) -> [u8; 7] {
-    let mut field = [0u8; AES_EXTRA_FIELD_LEN];
-    field[0..2].copy_from_slice(&vendor_version.to_le_bytes());
-    field[2..4].copy_from_slice(b"AE");
-    field[4] = strength;
-    // The actual compression method id (8 for deflate) lives here; the entry's
-    // own compression method is 99 (AES).
-    let method_id = match method {
-        CompressionMethod::DEFLATE => 8u16,
-        CompressionMethod::STORE => 0u16,
-        other => panic!("unsupported AES compression method: {other:?}"),
-    };
-    field[5..7].copy_from_slice(&method_id.to_le_bytes());
-    field
+    let err = winzip_aes::Decryptor::new(
+        zip_entry.data(),
+        &RustCrypto,
+        b"wrongpassword",
+        Strength::Aes256,
+        entry.compressed_size_hint(),
+    )
+    .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
 }
 
 /// Creates a single-entry, WinZip AES-encrypted, deflate-compressed ZIP archive
-/// using only rawzip's public writer primitives plus the RustCrypto stack, and
+/// using rawzip's writer and AES framing with the RustCrypto primitives, and
 /// returns the raw archive bytes.
 ///
 /// A deterministic salt is used so the output is reproducible; real archives
 /// must use a cryptographically random salt.
-fn create_winzip_aes_entry(strength: u8, vendor_version: u16, plaintext: &[u8]) -> Vec<u8> {
-    let (salt_len, key_len) = aes_key_lengths(strength);
-    let salt: Vec<u8> = (0..salt_len as u8).collect();
-    let keys = derive_aes_keys(&salt, key_len);
-
-    let mut counter = [0u8; 16];
-    counter[0] = 1;
-    let cipher = new_cipher(strength, &keys.encryption_key, &counter);
-    let mac = HmacSha1::new_from_slice(&keys.authentication_key).unwrap();
-
-    // AE-2 must store a zero CRC; AE-1 retains the real CRC32 of the plaintext.
-    let crc32_option = match vendor_version {
-        2 => Crc32Option::Skip,
-        1 => Crc32Option::Calculate,
-        other => panic!("unsupported AES vendor version: {other}"),
-    };
+fn create_winzip_aes_entry(parameters: &Parameters, plaintext: &[u8]) -> Vec<u8> {
+    let salt: Vec<u8> = (0..parameters.strength.salt_len() as u8).collect();
 
     let mut output = std::io::Cursor::new(Vec::new());
     let mut archive = ZipArchiveWriter::new(&mut output);
 
-    let extra = aes_extra_field(vendor_version, strength, CompressionMethod::DEFLATE);
+    // Sets method 99, the encrypted flag, the AES extra field, and the CRC32
+    // the version calls for (zero for AE-2).
     let (mut entry, config) = archive
         .new_file("test.txt")
-        .compression_method(CompressionMethod::AES)
-        .encrypted(true)
-        .extra_field(ExtraFieldId::AES, &extra, Header::default())
+        .aes(parameters)
         .unwrap()
-        .crc32(crc32_option)
         .start()
         .unwrap();
 
     // Payload layout: salt, password verifier, AES-CTR encrypted deflate stream,
     // then the 10-byte authentication code. Salt and verifier are unencrypted.
-    entry.write_all(&salt).unwrap();
-    entry.write_all(&keys.password_verifier).unwrap();
-
-    let aes_writer = AesWriter {
-        writer: &mut entry,
-        cipher,
-        mac,
-        scratch: Box::new([0u8; AES_WRITE_BUFFER_LEN]),
-    };
-    let deflater = flate2::write::DeflateEncoder::new(aes_writer, flate2::Compression::default());
+    let encryptor = winzip_aes::Encryptor::new(
+        &mut entry,
+        &RustCrypto,
+        PASSWORD,
+        parameters.strength,
+        &salt,
+    )
+    .unwrap();
+    let deflater = flate2::write::DeflateEncoder::new(encryptor, flate2::Compression::default());
 
     // The data writer tracks the plaintext CRC32/length for the data descriptor.
     let mut writer = config.wrap(deflater);
     writer.write_all(plaintext).unwrap();
     let (deflater, descriptor) = writer.finish().unwrap();
-    let aes_writer = deflater.finish().unwrap();
-    let mac = aes_writer.into_mac();
-
-    // The authentication code is the first 10 bytes of the HMAC-SHA1 over the
-    // ciphertext, written immediately after the encrypted data.
-    let auth_code = mac.finalize().into_bytes();
-    entry.write_all(&auth_code[..AUTH_CODE_LEN]).unwrap();
+    deflater.finish().unwrap().finish().unwrap();
 
     entry.finish(descriptor).unwrap();
     archive.finish().unwrap();
@@ -396,9 +195,14 @@ fn create_winzip_aes_entry(strength: u8, vendor_version: u16, plaintext: &[u8])
     output.into_inner()
 }
 
-fn roundtrip_winzip_aes_entry(strength: u8, vendor_version: u16) {
+fn roundtrip_winzip_aes_entry(strength: Strength, version: Version) {
     let plaintext = b"the quick brown fox jumps over the lazy dog".repeat(8);
-    let zip = create_winzip_aes_entry(strength, vendor_version, &plaintext);
+    let parameters = Parameters {
+        version,
+        strength,
+        compression_method: CompressionMethod::DEFLATE,
+    };
+    let zip = create_winzip_aes_entry(&parameters, &plaintext);
 
     let mut buffer = vec![0u8; RECOMMENDED_BUFFER_SIZE];
     let archive = ZipLocator::new()
@@ -406,13 +210,6 @@ fn roundtrip_winzip_aes_entry(strength: u8, vendor_version: u16) {
         .map_err(|(_, e)| e)
         .unwrap();
 
-    let expected_metadata = AesExtraField {
-        vendor_version,
-        vendor_id: *b"AE",
-        strength,
-        compression_method: CompressionMethod::DEFLATE,
-    };
-
     let mut entries = archive.entries(&mut buffer);
     let entry = entries.next_entry().unwrap().unwrap();
     assert_eq!(entry.file_path().as_ref(), b"test.txt");
@@ -422,14 +219,13 @@ fn roundtrip_winzip_aes_entry(strength: u8, vendor_version: u16) {
 
     // The AES extra field must be present in both the central directory and the
     // local file header.
-    let central_metadata = find_aes_extra_field(entry.extra_fields());
-    assert_eq!(central_metadata, expected_metadata);
+    let central_parameters = Parameters::from_extra_fields(entry.extra_fields()).unwrap();
+    assert_eq!(central_parameters, parameters);
 
     let stored_crc = entry.crc32();
-    match vendor_version {
-        2 => assert_eq!(stored_crc, 0),
-        1 => assert_eq!(stored_crc, rawzip::crc32(&plaintext)),
-        other => panic!("unexpected AES vendor version: {other}"),
+    match version {
+        Version::Ae2 => assert_eq!(stored_crc, 0),
+        Version::Ae1 => assert_eq!(stored_crc, rawzip::crc32(&plaintext)),
     }
 
     let compressed_size = entry.compressed_size_hint();
@@ -441,28 +237,28 @@ fn roundtrip_winzip_aes_entry(strength: u8, vendor_version: u16) {
     let local_header = encrypted_entry
         .local_header(&mut local_header_buffer)
         .unwrap();
-    let local_metadata = find_aes_extra_field(local_header.extra_fields());
-    assert_eq!(local_metadata, expected_metadata);
+    let local_parameters = Parameters::from_extra_fields(local_header.extra_fields()).unwrap();
+    assert_eq!(local_parameters, parameters);
     assert!(local_header.flags().is_encrypted());
 
-    // Decrypt using the same primitives the read-side tests rely on.
-    let decoded = decrypt_winzip_aes_payload(
-        encrypted_entry.reader(),
-        compressed_size,
-        strength,
-        CompressionMethod::DEFLATE,
-    );
+    let decoded =
+        decrypt_winzip_aes_payload(encrypted_entry.reader(), compressed_size, &parameters);
     assert_eq!(decoded, plaintext);
 }
 
 #[test]
 fn roundtrip_winzip_aes256_ae2() {
-    roundtrip_winzip_aes_entry(3, 2);
+    roundtrip_winzip_aes_entry(Strength::Aes256, Version::Ae2);
 }
 
 #[test]
 fn roundtrip_winzip_aes256_ae1() {
-    roundtrip_winzip_aes_entry(3, 1);
+    roundtrip_winzip_aes_entry(Strength::Aes256, Version::Ae1);
+}
+
+#[test]
+fn roundtrip_winzip_aes128_ae2() {
+    roundtrip_winzip_aes_entry(Strength::Aes128, Version::Ae2);
 }
 
 /// The traditional PKWARE ("ZipCrypto") encryption check byte for an entry.
@@ -471,21 +267,6 @@ fn roundtrip_winzip_aes256_ae1() {
 /// exposing the ingredients ([`Decryptor::check_byte`], the entry's CRC32, DOS
 /// mod time, and flags) rather than baking the policy in.
 ///
-/// The encryption header (and its check byte) is part of the *local* entry,
-/// written by the encoder from the values in the local header it just wrote. So
-/// every ingredient is read from the local header — the flag that selects the
-/// source (general purpose bit 3), the DOS mod time, and the CRC32 — never the
-/// central directory, which is a separate copy that can legitimately differ. The
-/// check byte is the high byte of the packed DOS mod time for data-descriptor
-/// entries, or the high byte of the CRC32 otherwise.
-fn expected_zipcrypto_check_byte(local_header: &ZipLocalFileHeader) -> u8 {
-    if local_header.flags().has_data_descriptor() {
-        (local_header.last_modified_dos().packed_time() >> 8) as u8
-    } else {
-        (local_header.crc32() >> 24) as u8
-    }
-}
-
 /// Decodes a traditional PKWARE ("ZipCrypto") encrypted entry using the
 /// `rawzip` [`Decryptor`] layered under a deflate decoder. The entry in
 /// `assets/zipcrypto.zip` is a deflate-compressed `test.txt` carrying a data
@@ -504,6 +285,11 @@ fn decrypt_zipcrypto_entry() {
     // ZipCrypto leaves the compression method untouched; encryption is signaled
     // by general purpose bit 0, not by a dedicated method like WinZip AES.
     assert_eq!(entry.compression_method(), CompressionMethod::DEFLATE);
+    assert_eq!(
+        entry.effective_compression_method(),
+        CompressionMethod::DEFLATE
+    );
+    assert_eq!(entry.encryption(), Encryption::ZipCrypto);
     assert!(entry.flags().has_data_descriptor());
     assert!(entry.flags().is_encrypted());
 
@@ -514,6 +300,7 @@ fn decrypt_zipcrypto_entry() {
     // what the encoder used when it wrote the encryption header.
     let mut local_buffer = vec![0u8; RECOMMENDED_BUFFER_SIZE];
     let local_header = zip_entry.local_header(&mut local_buffer).unwrap();
+    assert_eq!(local_header.encryption(), Encryption::ZipCrypto);
 
     // Eagerly verify the password against the check byte (here the high byte of
     // the DOS mod time, since the entry carries a data descriptor), as every
@@ -521,7 +308,7 @@ fn decrypt_zipcrypto_entry() {
     let decryptor = Decryptor::new(zip_entry.reader(), PASSWORD).unwrap();
     assert_eq!(
         decryptor.check_byte(),
-        expected_zipcrypto_check_byte(&local_header)
+        zipcrypto::expected_check_byte(&local_header)
     );
     assert_eq!(decryptor.check_byte(), 0x38);
 
@@ -560,7 +347,7 @@ fn decrypt_zipcrypto_wrong_password_fails() {
     let decryptor = Decryptor::new(zip_entry.reader(), b"wrongpassword").unwrap();
     assert_ne!(
         decryptor.check_byte(),
-        expected_zipcrypto_check_byte(&local_header)
+        zipcrypto::expected_check_byte(&local_header)
     );
 
     // And the decode fails regardless of the check byte: the garbage plaintext
@@ -654,7 +441,7 @@ fn roundtrip_zipcrypto(method: CompressionMethod, plaintext: &[u8]) {
     let decryptor = Decryptor::new(zip_entry.reader(), PASSWORD).unwrap();
     assert_eq!(
         decryptor.check_byte(),
-        expected_zipcrypto_check_byte(&local_header)
+        zipcrypto::expected_check_byte(&local_header)
     );
 
     let mut output = Vec::new();
@@ -751,7 +538,7 @@ fn decrypt_zipcrypto_no_data_descriptor_entry() {
     let decryptor = Decryptor::new(zip_entry.reader(), PASSWORD).unwrap();
     assert_eq!(
         decryptor.check_byte(),
-        expected_zipcrypto_check_byte(&local_header)
+        zipcrypto::expected_check_byte(&local_header)
     );
 
     // The entry is stored, so the decryptor's output is the plaintext directly;
@@ -765,3 +552,159 @@ fn decrypt_zipcrypto_no_data_descriptor_entry() {
     assert_eq!(output.len() as u64, uncompressed_size);
     assert_eq!(output, b"rawzip zipcrypto no-data-descriptor fixture\n");
 }
+
+/// Writes a ZipCrypto entry with the builder's integrated encryption, which
+/// picks the check byte and encrypts below the compressor.
+fn create_builder_zipcrypto_entry(method: CompressionMethod, plaintext: &[u8]) -> Vec<u8> {
+    let mut output = std::io::Cursor::new(Vec::new());
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    let (mut entry, config) = archive
+        .new_file("test.txt")
+        .compression_method(method)
+        .zipcrypto(PASSWORD, |bytes| bytes.fill(0x5a))
+        .start()
+        .unwrap();
+
+    let descriptor = match method {
+        CompressionMethod::DEFLATE => {
+            let deflater =
+                flate2::write::DeflateEncoder::new(&mut entry, flate2::Compression::default());
+            let mut writer = config.wrap(deflater);
+            writer.write_all(plaintext).unwrap();
+            let (deflater, descriptor) = writer.finish().unwrap();
+            deflater.finish().unwrap();
+            descriptor
+        }
+        CompressionMethod::STORE => {
+            let mut writer = config.wrap(&mut entry);
+            writer.write_all(plaintext).unwrap();
+            writer.finish().unwrap().1
+        }
+        other => panic!("unsupported method: {other:?}"),
+    };
+
+    entry.finish(descriptor).unwrap();
+    archive.finish().unwrap();
+    output.into_inner()
+}
+
+fn read_checked_zipcrypto(zip: &[u8], password: &[u8]) -> std::io::Result<Vec<u8>> {
+    let archive = ZipArchive::from_slice(zip).unwrap();
+    let entry = archive.entries().next_entry().unwrap().unwrap();
+    assert_eq!(entry.encryption(), Encryption::ZipCrypto);
+    let zip_entry = archive.get_entry(entry.wayfinder()).unwrap();
+    let local_header = zip_entry.local_header();
+
+    let decryptor = Decryptor::new_checked(zip_entry.data(), password, &local_header)?;
+    let mut output = Vec::new();
+    match entry.compression_method() {
+        CompressionMethod::DEFLATE => zip_entry
+            .verifying_reader(flate2::read::DeflateDecoder::new(decryptor))
+            .read_to_end(&mut output)?,
+        _ => zip_entry
+            .verifying_reader(decryptor)
+            .read_to_end(&mut output)?,
+    };
+    Ok(output)
+}
+
+#[test]
+fn builder_zipcrypto_roundtrip() {
+    let plaintext = b"the quick brown fox jumps over the lazy dog".repeat(100);
+    for method in [CompressionMethod::DEFLATE, CompressionMethod::STORE] {
+        let zip = create_builder_zipcrypto_entry(method, &plaintext);
+        assert_eq!(read_checked_zipcrypto(&zip, PASSWORD).unwrap(), plaintext);
+    }
+}
+
+#[test]
+fn builder_zipcrypto_counts_header_in_compressed_size() {
+    let plaintext = b"aaaaaaaaaaaaaaaa\n";
+    let zip = create_builder_zipcrypto_entry(CompressionMethod::STORE, plaintext);
+    let archive = ZipArchive::from_slice(&zip).unwrap();
+    let entry = archive.entries().next_entry().unwrap().unwrap();
+    assert_eq!(entry.compressed_size_hint(), plaintext.len() as u64 + 12);
+
+    // Interoperates with the standalone decryptor and its check byte
+    let zip_entry = archive.get_entry(entry.wayfinder()).unwrap();
+    let local_header = zip_entry.local_header();
+    let decryptor = Decryptor::new(zip_entry.data(), PASSWORD).unwrap();
+    assert_eq!(
+        decryptor.check_byte(),
+        zipcrypto::expected_check_byte(&local_header)
+    );
+}
+
+#[test]
+fn builder_zipcrypto_with_compressors() {
+    let mut compressors = Compressors::new();
+    compressors.register(CompressionMethod::DEFLATE, |sink| {
+        let encoder = flate2::write::DeflateEncoder::new(sink, flate2::Compression::default());
+        Ok(Encoder::new(encoder, |encoder| encoder.finish()))
+    });
+
+    let plaintext = b"the quick brown fox".repeat(20);
+    let mut output = std::io::Cursor::new(Vec::new());
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    let mut writer = archive
+        .new_file("test.txt")
+        .compression_method(CompressionMethod::DEFLATE)
+        .zipcrypto(PASSWORD, |bytes| bytes.fill(7))
+        .start_with(&compressors)
+        .unwrap();
+    writer.write_all(&plaintext).unwrap();
+    writer.finish().unwrap();
+    archive.finish().unwrap();
+
+    let zip = output.into_inner();
+    assert_eq!(read_checked_zipcrypto(&zip, PASSWORD).unwrap(), plaintext);
+}
+
+#[test]
+fn builder_zipcrypto_with_aes_is_rejected() {
+    let parameters = Parameters {
+        version: Version::Ae2,
+        strength: Strength::Aes256,
+        compression_method: CompressionMethod::STORE,
+    };
+
+    let mut output = std::io::Cursor::new(Vec::new());
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    let err = archive
+        .new_file("aes-first.txt")
+        .aes(&parameters)
+        .unwrap()
+        .zipcrypto(PASSWORD, |bytes| bytes.fill(7))
+        .start()
+        .unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidInput { .. }));
+
+    let err = archive
+        .new_file("zipcrypto-first.txt")
+        .zipcrypto(PASSWORD, |bytes| bytes.fill(7))
+        .aes(&parameters)
+        .unwrap()
+        .start_with(&Compressors::new())
+        .unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidInput { .. }));
+
+    // Nothing was written for either entry
+    archive.finish().unwrap();
+    let zip = output.into_inner();
+    let archive = ZipArchive::from_slice(&zip).unwrap();
+    assert_eq!(archive.entries_hint(), 0);
+}
+
+#[test]
+fn new_checked_rejects_wrong_password() {
+    let zip = create_builder_zipcrypto_entry(CompressionMethod::STORE, b"secret");
+    let err = read_checked_zipcrypto(&zip, b"wrongpassword").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    // The external fixture, without a data descriptor, checks against the CRC32
+    let data = std::fs::read("assets/zipcrypto-no-data-descriptor.zip").unwrap();
+    assert_eq!(
+        read_checked_zipcrypto(&data, PASSWORD).unwrap(),
+        b"rawzip zipcrypto no-data-descriptor fixture\n"
+    );
+}
@@ -1,3 +1,4 @@
+use rawzip::path::{CollisionDetector, CollisionKind};
 use rawzip::{EntryPath, ZipArchive, ZipArchiveWriter};
 use std::io::Write;
 
@@ -122,3 +123,57 @@ fn owned_paths_accepted() {
     .collect();
     assert_eq!(names, vec![b"d/".to_vec(), b"f.txt".to_vec()]);
 }
+
+#[test]
+fn collision_detector_from_reader_entries() {
+    let mut output = Vec::new();
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    for name in ["\u{3b1}\u{3c2}.txt", "b.txt", "\u{391}\u{3a3}.txt"] {
+        let (mut entry, config) = archive.new_file(name).start().unwrap();
+        let (_, descriptor) = config.wrap(&mut entry).finish().unwrap();
+        entry.finish(descriptor).unwrap();
+    }
+    archive.finish().unwrap();
+
+    let mut buffer = vec![0u8; rawzip::RECOMMENDED_BUFFER_SIZE];
+    let archive = rawzip::ZipLocator::new()
+        .locate_in_reader(output.as_slice(), &mut buffer, output.len() as u64)
+        .map_err(|(_, e)| e)
+        .unwrap();
+    let detector = CollisionDetector::from_entries(archive.entries(&mut buffer)).unwrap();
+    assert_eq!(detector.len(), 3);
+
+    let collisions = detector.collisions();
+    assert_eq!(collisions.len(), 1);
+    assert_eq!(collisions[0].kind(), CollisionKind::Equivalent);
+    assert_eq!(
+        collisions[0].paths(),
+        ["\u{3b1}\u{3c2}.txt", "\u{391}\u{3a3}.txt"]
+    );
+}
+
+#[test]
+fn collision_detector_decodes_cp437_names() {
+    let mut output = Vec::new();
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    let names = [
+        EntryPath::verbatim(&b"R\x90SUM\x90.txt"[..]),
+        EntryPath::conformant("r\u{e9}sum\u{e9}.txt"),
+    ];
+    for name in names {
+        let (mut entry, config) = archive.new_file(name).start().unwrap();
+        let (_, descriptor) = config.wrap(&mut entry).finish().unwrap();
+        entry.finish(descriptor).unwrap();
+    }
+    archive.finish().unwrap();
+
+    let archive = ZipArchive::from_slice(&output).unwrap();
+    let detector = CollisionDetector::from_slice_entries(archive.entries()).unwrap();
+    let collisions = detector.collisions();
+    assert_eq!(collisions.len(), 1);
+    assert_eq!(collisions[0].kind(), CollisionKind::Equivalent);
+    assert_eq!(
+        collisions[0].paths(),
+        ["R\u{c9}SUM\u{c9}.txt", "r\u{e9}sum\u{e9}.txt"]
+    );
+}
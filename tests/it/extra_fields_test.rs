@@ -204,3 +204,126 @@ fn test_extra_field_deduplication_behavior() {
     assert_eq!(central_instances[1], b"second");
     assert_eq!(central_instances[2], b"third");
 }
+
+#[test]
+fn test_typed_extra_field_placement() {
+    use rawzip::extra_fields::{ExtraField, ExtraFieldValue};
+
+    let mut output = Cursor::new(Vec::new());
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    let (mut entry, config) = archive
+        .new_file("caf\u{e9}.txt")
+        .typed_extra_field(ExtraFieldValue::InfoZipUnix { uid: 501, gid: 20 })
+        .unwrap()
+        .typed_extra_field(ExtraFieldValue::UnixUidGid {
+            uid: 1 << 40,
+            gid: 20,
+        })
+        .unwrap()
+        .typed_extra_field(ExtraFieldValue::UnicodePath {
+            original: b"cafe.txt",
+            path: "caf\u{e9}.txt",
+        })
+        .unwrap()
+        .typed_extra_field(ExtraFieldValue::UnicodeComment {
+            original: b"",
+            comment: "r\u{e9}sum\u{e9}",
+        })
+        .unwrap()
+        .start()
+        .unwrap();
+    let mut writer = config.wrap(&mut entry);
+    writer.write_all(b"data").unwrap();
+    let (_, desc) = writer.finish().unwrap();
+    entry.finish(desc).unwrap();
+    archive.finish().unwrap();
+
+    let zip_data = output.into_inner();
+    let archive = ZipArchive::from_slice(&zip_data).unwrap();
+    let entry = archive.entries().next_entry().unwrap().unwrap();
+
+    let central = entry.extra_fields().typed().collect::<Vec<_>>();
+    assert_eq!(central.len(), 4);
+    let ExtraField::InfoZipUnix(unix) = central[0] else {
+        panic!("expected info-zip unix");
+    };
+    assert_eq!(unix.uid(), None);
+    let ExtraField::UnixUidGid(ids) = central[1] else {
+        panic!("expected uid/gid");
+    };
+    assert_eq!(ids.uid_bytes().len(), 8);
+    assert_eq!((ids.uid(), ids.gid()), (Some(1 << 40), Some(20)));
+    let ExtraField::UnicodePath(path) = central[2] else {
+        panic!("expected unicode path");
+    };
+    assert_eq!(path.crc32(), rawzip::crc32(b"cafe.txt"));
+    assert_eq!(path.as_bytes(), "caf\u{e9}.txt".as_bytes());
+    let ExtraField::UnicodeComment(comment) = central[3] else {
+        panic!("expected unicode comment");
+    };
+    assert_eq!(comment.as_bytes(), "r\u{e9}sum\u{e9}".as_bytes());
+
+    let local = archive.get_entry(entry.wayfinder()).unwrap().local_header();
+    let local_ids = local
+        .extra_fields()
+        .typed()
+        .map(|x| x.id())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        local_ids,
+        vec![
+            ExtraFieldId::INFO_ZIP_UNIX,
+            ExtraFieldId::INFO_ZIP_UNIX_UID_GID,
+            ExtraFieldId::INFO_ZIP_UNICODE_PATH
+        ]
+    );
+    let Some(ExtraField::InfoZipUnix(unix)) = local.extra_fields().typed().next() else {
+        panic!("expected info-zip unix");
+    };
+    assert_eq!((unix.uid(), unix.gid()), (Some(501), Some(20)));
+}
+
+#[test]
+fn test_extra_fields_written_by_rawzip_are_rejected() {
+    let time = rawzip::time::UtcDateTime::from_unix(1_700_000_000);
+    let mut output = Cursor::new(Vec::new());
+    let mut archive = ZipArchiveWriter::new(&mut output);
+
+    let result = archive
+        .new_file("zip64.txt")
+        .extra_field(ExtraFieldId::ZIP64, &[0u8; 8], Header::default())
+        .unwrap()
+        .start();
+    assert!(result.is_err());
+
+    let result = archive
+        .new_dir("dir/")
+        .last_modified(time)
+        .extra_field(
+            ExtraFieldId::EXTENDED_TIMESTAMP,
+            &[1, 0, 0, 0, 0],
+            Header::CENTRAL,
+        )
+        .unwrap()
+        .create();
+    assert!(result.is_err());
+
+    // Without a modification time rawzip does not write the field
+    archive
+        .new_dir("other/")
+        .extra_field(
+            ExtraFieldId::EXTENDED_TIMESTAMP,
+            &[1, 0, 0, 0, 0],
+            Header::CENTRAL,
+        )
+        .unwrap()
+        .create()
+        .unwrap();
+    archive.finish().unwrap();
+
+    let zip_data = output.into_inner();
+    let archive = ZipArchive::from_slice(&zip_data).unwrap();
+    assert_eq!(archive.entries_hint(), 1);
+    let entry = archive.entries().next_entry().unwrap().unwrap();
+    assert_eq!(entry.file_path().as_ref(), b"other/");
+}
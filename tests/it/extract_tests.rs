@@ -0,0 +1,355 @@
+use crate::common::{TestEntry, decompressors, write_archive};
+use rawzip::extract::{ExtractOutcome, ExtractReport, Extractor, Overwrite, SkipReason};
+use rawzip::path::UnsafePathKind;
+use rawzip::time::UtcDateTime;
+use rawzip::{CompressionMethod, Decompressors, EntryPath, ErrorKind, ZipArchive};
+use std::path::{Path, PathBuf};
+
+/// A directory under the system temp directory that is removed on drop.
+pub(crate) struct TestDir(PathBuf);
+
+impl TestDir {
+    pub(crate) fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("rawzip-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        TestDir(path)
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// A file with the permissions and time that the tests check for.
+fn file<'a>(path: impl Into<EntryPath<'a>>, data: &'a [u8]) -> TestEntry<'a> {
+    TestEntry::file(path, data)
+        .permissions(0o600)
+        .mtime(UtcDateTime::from_unix(1_600_000_000))
+}
+
+/// A directory with the permissions and time that the tests check for.
+fn directory(name: &str) -> TestEntry<'_> {
+    TestEntry::dir(name)
+        .permissions(0o750)
+        .mtime(UtcDateTime::from_unix(1_500_000_000))
+}
+
+fn extract(extractor: &Extractor, data: &[u8]) -> Result<ExtractReport, rawzip::Error> {
+    let archive = ZipArchive::from_slice(data).unwrap().into_reader();
+    extractor.extract(&archive, &decompressors())
+}
+
+fn outcomes(report: &ExtractReport) -> Vec<(&str, &ExtractOutcome)> {
+    report
+        .entries()
+        .iter()
+        .map(|x| (x.name(), x.outcome()))
+        .collect()
+}
+
+#[test]
+fn test_extract_files_and_directories() {
+    let dir = TestDir::new("extract-basic");
+    let root = dir.path();
+    let data = write_archive(&[
+        directory("docs/"),
+        file("docs/readme.txt", b"hello"),
+        file("a/b/c.txt", b"deflated deflated deflated").method(CompressionMethod::DEFLATE),
+    ]);
+
+    let report = extract(&Extractor::new(root), &data).unwrap();
+    assert_eq!(
+        outcomes(&report),
+        vec![
+            ("docs/", &ExtractOutcome::Directory(root.join("docs"))),
+            (
+                "docs/readme.txt",
+                &ExtractOutcome::File(root.join("docs").join("readme.txt"))
+            ),
+            (
+                "a/b/c.txt",
+                &ExtractOutcome::File(root.join("a").join("b").join("c.txt"))
+            ),
+        ]
+    );
+    assert_eq!(report.skipped().count(), 0);
+
+    assert_eq!(
+        std::fs::read(root.join("docs/readme.txt")).unwrap(),
+        b"hello"
+    );
+    assert_eq!(
+        std::fs::read(root.join("a/b/c.txt")).unwrap(),
+        b"deflated deflated deflated"
+    );
+
+    let metadata = std::fs::metadata(root.join("docs/readme.txt")).unwrap();
+    let mtime = metadata.modified().unwrap();
+    let expected = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_600_000_000);
+    assert_eq!(mtime, expected);
+
+    // Directory times are applied after the files within are written
+    let mtime = std::fs::metadata(root.join("docs")).unwrap().modified();
+    let expected = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_500_000_000);
+    assert_eq!(mtime.unwrap(), expected);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        let metadata = std::fs::metadata(root.join("docs")).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o750);
+    }
+}
+
+#[test]
+fn test_extract_leaves_existing_directories_alone() {
+    let dir = TestDir::new("extract-existing-dir");
+    let root = dir.path();
+    let existing = root.join("docs");
+    std::fs::create_dir_all(&existing).unwrap();
+    let before = std::fs::metadata(&existing).unwrap();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let permissions = std::fs::Permissions::from_mode(0o700);
+        std::fs::set_permissions(&existing, permissions).unwrap();
+    }
+
+    let data = write_archive(&[
+        directory("docs/"),
+        directory("docs/new/"),
+        file("docs/readme.txt", b"hello"),
+    ]);
+    extract(&Extractor::new(root), &data).unwrap();
+
+    let after = std::fs::metadata(&existing).unwrap();
+    assert_ne!(
+        after.modified().unwrap(),
+        std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_500_000_000)
+    );
+    assert!(after.modified().unwrap() >= before.modified().unwrap());
+
+    let created = std::fs::metadata(existing.join("new")).unwrap();
+    assert_eq!(
+        created.modified().unwrap(),
+        std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_500_000_000)
+    );
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(after.permissions().mode() & 0o777, 0o700);
+        assert_eq!(created.permissions().mode() & 0o777, 0o750);
+    }
+}
+
+#[test]
+fn test_extract_skips_unsafe_paths() {
+    let dir = TestDir::new("extract-unsafe");
+    let root = dir.path().join("root");
+    let data = write_archive(&[
+        file(EntryPath::verbatim(&b"../evil.txt"[..]), b"evil"),
+        file(EntryPath::verbatim(&b"/abs.txt"[..]), b"evil"),
+        file(EntryPath::verbatim(&b"C:\\win.txt"[..]), b"evil"),
+        file(EntryPath::verbatim(&b"ok.txt:hidden"[..]), b"evil"),
+        file(EntryPath::verbatim(&b"ok.txt"[..]), b"ok"),
+    ]);
+
+    let report = extract(&Extractor::new(&root), &data).unwrap();
+    let reasons = report
+        .skipped()
+        .map(|x| x.outcome().clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        reasons,
+        vec![
+            ExtractOutcome::Skipped(SkipReason::UnsafePath(UnsafePathKind::ParentTraversal)),
+            ExtractOutcome::Skipped(SkipReason::UnsafePath(UnsafePathKind::Absolute)),
+            ExtractOutcome::Skipped(SkipReason::UnsafePath(UnsafePathKind::DriveLetter)),
+            ExtractOutcome::Skipped(SkipReason::UnsafePath(UnsafePathKind::Colon)),
+        ]
+    );
+    assert!(!dir.path().join("evil.txt").exists());
+    assert_eq!(std::fs::read(root.join("ok.txt")).unwrap(), b"ok");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_extract_symlinks() {
+    let dir = TestDir::new("extract-symlinks");
+    let root = dir.path();
+    let data = write_archive(&[
+        directory("sub/"),
+        TestEntry::symlink("sub/link", "../target.txt"),
+        TestEntry::symlink("escape", "../outside"),
+        TestEntry::symlink("through", "sub/link/../.."),
+        TestEntry::symlink("dirlink", "sub"),
+        file("dirlink/file.txt", b"written through a link"),
+        file("target.txt", b"target"),
+    ]);
+
+    let report = extract(&Extractor::new(root), &data).unwrap();
+    assert_eq!(
+        outcomes(&report),
+        vec![
+            ("sub/", &ExtractOutcome::Directory(root.join("sub"))),
+            (
+                "sub/link",
+                &ExtractOutcome::Symlink(root.join("sub").join("link"))
+            ),
+            (
+                "escape",
+                &ExtractOutcome::Skipped(SkipReason::UnsafeSymlink)
+            ),
+            (
+                "through",
+                &ExtractOutcome::Skipped(SkipReason::UnsafeSymlink)
+            ),
+            ("dirlink", &ExtractOutcome::Symlink(root.join("dirlink"))),
+            (
+                "dirlink/file.txt",
+                &ExtractOutcome::Skipped(SkipReason::PathConflict)
+            ),
+            ("target.txt", &ExtractOutcome::File(root.join("target.txt"))),
+        ]
+    );
+
+    assert_eq!(std::fs::read(root.join("sub/link")).unwrap(), b"target");
+    assert!(!root.join("sub/file.txt").exists());
+
+    let report = extract(
+        &Extractor::new(dir.path().join("nolinks")).symlinks(false),
+        &data,
+    )
+    .unwrap();
+    assert_eq!(
+        report.entries()[1].outcome(),
+        &ExtractOutcome::Skipped(SkipReason::Symlink)
+    );
+}
+
+#[test]
+fn test_extract_overwrite_policies() {
+    let dir = TestDir::new("extract-overwrite");
+    let root = dir.path();
+    let first = write_archive(&[file("a.txt", b"first")]);
+    let second = write_archive(&[file("a.txt", b"second")]);
+    extract(&Extractor::new(root), &first).unwrap();
+
+    let err = extract(&Extractor::new(root), &second).unwrap_err();
+    match err.kind() {
+        ErrorKind::IO(e) => assert_eq!(e.kind(), std::io::ErrorKind::AlreadyExists),
+        kind => panic!("unexpected error: {kind}"),
+    }
+
+    let extractor = Extractor::new(root).overwrite(Overwrite::Skip);
+    let report = extract(&extractor, &second).unwrap();
+    assert_eq!(
+        report.entries()[0].outcome(),
+        &ExtractOutcome::Skipped(SkipReason::Exists)
+    );
+    assert_eq!(std::fs::read(root.join("a.txt")).unwrap(), b"first");
+
+    let extractor = Extractor::new(root).overwrite(Overwrite::Replace);
+    extract(&extractor, &second).unwrap();
+    assert_eq!(std::fs::read(root.join("a.txt")).unwrap(), b"second");
+
+    // A directory is never replaced by a file
+    std::fs::create_dir(root.join("d")).unwrap();
+    let data = write_archive(&[file("d", b"file")]);
+    let report = extract(&extractor, &data).unwrap();
+    assert_eq!(
+        report.entries()[0].outcome(),
+        &ExtractOutcome::Skipped(SkipReason::PathConflict)
+    );
+}
+
+#[test]
+fn test_extract_size_limits() {
+    let dir = TestDir::new("extract-limits");
+    let data = write_archive(&[
+        file("a.txt", b"0123456789"),
+        file("big.txt", &[b'x'; 100]),
+        file("b.txt", b"0123456789"),
+        file("c.txt", b"0123456789"),
+    ]);
+
+    let extractor = Extractor::new(dir.path())
+        .max_entry_size(50)
+        .max_total_size(25);
+    let report = extract(&extractor, &data).unwrap();
+    let outcomes = report
+        .entries()
+        .iter()
+        .map(|x| x.outcome())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        outcomes,
+        vec![
+            &ExtractOutcome::File(dir.path().join("a.txt")),
+            &ExtractOutcome::Skipped(SkipReason::EntryTooLarge),
+            &ExtractOutcome::File(dir.path().join("b.txt")),
+            &ExtractOutcome::Skipped(SkipReason::TotalSizeExceeded),
+        ]
+    );
+}
+
+#[test]
+#[cfg(feature = "legacy-methods")]
+fn test_extract_legacy_methods() {
+    let dir = TestDir::new("extract-legacy");
+    let root = dir.path();
+    let data = std::fs::read("assets/legacy.zip").unwrap();
+    let archive = ZipArchive::from_slice(&data).unwrap().into_reader();
+    let report = Extractor::new(root)
+        .extract(&archive, &decompressors())
+        .unwrap();
+
+    assert_eq!(report.entries().len(), 9);
+    assert_eq!(report.skipped().count(), 0);
+    let shrunk = std::fs::read(root.join("shrunk.txt")).unwrap();
+    assert_eq!(shrunk.len(), 80000);
+    let reduced = std::fs::read(root.join("reduced1.txt")).unwrap();
+    let imploded = std::fs::read(root.join("imploded6.txt")).unwrap();
+    assert_eq!(reduced.len(), 15592);
+    assert_eq!(reduced, imploded);
+}
+
+#[test]
+fn test_extract_unsupported_method_and_corruption() {
+    let dir = TestDir::new("extract-corrupt");
+    let root = dir.path();
+    let data = write_archive(&[
+        file("deflated.txt", b"data").method(CompressionMethod::DEFLATE),
+        file("stored.txt", b"stored data"),
+    ]);
+
+    let archive = ZipArchive::from_slice(&data).unwrap().into_reader();
+    let report = Extractor::new(root)
+        .extract(&archive, &Decompressors::new())
+        .unwrap();
+    assert_eq!(
+        report.entries()[0].outcome(),
+        &ExtractOutcome::Skipped(SkipReason::UnsupportedMethod(CompressionMethod::DEFLATE))
+    );
+    assert!(!root.join("deflated.txt").exists());
+
+    // Corrupt the stored data so the checksum no longer matches
+    let mut corrupted = data.clone();
+    let pos = corrupted
+        .windows(11)
+        .position(|x| x == b"stored data")
+        .unwrap();
+    corrupted[pos] = b'S';
+    let err = extract(&Extractor::new(root.join("corrupt")), &corrupted).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidChecksum { .. }));
+    assert!(!root.join("corrupt").join("stored.txt").exists());
+}
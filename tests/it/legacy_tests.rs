@@ -0,0 +1,105 @@
+use crate::common::decompressors;
+use rawzip::{CompressionMethod, DecoderContext, RECOMMENDED_BUFFER_SIZE, ZipArchive};
+use std::io::Read;
+
+// assets/legacy.zip was produced by purpose-built encoders. Info-ZIP's
+// `unzip -t` verifies the Shrink entry (which exercises partial clears and
+// 13-bit codes) and the Implode entries of all four flag combinations;
+// Info-ZIP does not support Reduce.
+const FIXTURE: &str = "assets/legacy.zip";
+
+#[test]
+fn test_legacy_fixture() {
+    let data = std::fs::read(FIXTURE).unwrap();
+    let archive = ZipArchive::from_slice(&data).unwrap();
+    let decompressors = decompressors();
+
+    let mut methods = Vec::new();
+    let mut texts = Vec::new();
+    for header in archive.entries() {
+        let header = header.unwrap();
+        let entry = archive.get_entry(header.wayfinder()).unwrap();
+        let mut output = Vec::new();
+        entry
+            .decoded_reader(&decompressors)
+            .unwrap()
+            .read_to_end(&mut output)
+            .unwrap();
+
+        let method = header.compression_method();
+        let flags = header.flags();
+        methods.push((
+            method,
+            flags.has_implode_8k_dictionary(),
+            flags.has_implode_three_trees(),
+        ));
+        if method != CompressionMethod::SHRUNK {
+            texts.push(output);
+        } else {
+            assert_eq!(output.len(), 80000);
+        }
+    }
+
+    assert_eq!(
+        methods,
+        [
+            (CompressionMethod::SHRUNK, false, false),
+            (CompressionMethod::REDUCE1, false, false),
+            (CompressionMethod::REDUCE2, false, false),
+            (CompressionMethod::REDUCE3, false, false),
+            (CompressionMethod::REDUCE4, false, false),
+            (CompressionMethod::IMPLODED, false, false),
+            (CompressionMethod::IMPLODED, true, false),
+            (CompressionMethod::IMPLODED, false, true),
+            (CompressionMethod::IMPLODED, true, true),
+        ]
+    );
+
+    // Every entry other than the Shrink entry holds the same text
+    assert!(texts.windows(2).all(|pair| pair[0] == pair[1]));
+}
+
+#[test]
+fn test_legacy_small_reads() {
+    let file = std::fs::File::open(FIXTURE).unwrap();
+    let mut buffer = vec![0u8; RECOMMENDED_BUFFER_SIZE];
+    let archive = ZipArchive::from_file(file, &mut buffer).unwrap();
+    let decompressors = decompressors();
+    let mut entries = archive.entries(&mut buffer);
+    while let Some(header) = entries.next_entry().unwrap() {
+        let entry = archive.get_entry(header.wayfinder()).unwrap();
+        let mut reader = entry.decoded_reader(&decompressors).unwrap();
+        let mut chunk = [0u8; 7];
+        let mut total = 0;
+        loop {
+            let read = reader.read(&mut chunk).unwrap();
+            if read == 0 {
+                break;
+            }
+            total += read;
+        }
+        assert_eq!(total as u64, header.uncompressed_size_hint());
+    }
+}
+
+#[test]
+fn test_legacy_truncated() {
+    let data = std::fs::read(FIXTURE).unwrap();
+    let archive = ZipArchive::from_slice(&data).unwrap();
+    let decompressors = decompressors();
+    for header in archive.entries() {
+        let header = header.unwrap();
+        let entry = archive.get_entry(header.wayfinder()).unwrap();
+        let compressed = entry.data();
+        let context = DecoderContext::new(
+            header.compression_method(),
+            header.flags(),
+            header.uncompressed_size_hint(),
+        );
+        let decoder = decompressors
+            .decoder(&context, Box::new(&compressed[..compressed.len() / 2]))
+            .unwrap();
+        let mut reader = entry.verifying_reader(decoder);
+        assert!(reader.read_to_end(&mut Vec::new()).is_err());
+    }
+}
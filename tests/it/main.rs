@@ -9,16 +9,33 @@ use std::fs::File;
 use std::io::{Cursor, Read, Seek, SeekFrom};
 use std::path::Path;
 
+mod aes_primitives;
+mod apk_tests;
+mod common;
+mod compressors_tests;
 mod concurrent_tests;
 mod crc_tests;
+#[cfg(feature = "dcl-implode")]
+mod dcl_implode_tests;
+mod decompressors_tests;
+#[cfg(feature = "deflate64")]
+mod deflate64_tests;
+mod diff_tests;
+mod dir_tree_tests;
 mod encryption_tests;
 mod entry_path_tests;
 mod extra_data_zip_tests;
 mod extra_fields_test;
+mod extract_tests;
 mod false_sentinel_tests;
 mod false_signature_tests;
+#[cfg(feature = "legacy-methods")]
+mod legacy_tests;
 mod modification_time_tests;
 mod permission_tests;
+mod reproducible_tests;
+mod snapshot_tests;
+mod symlink_tests;
 mod utf8_tests;
 mod zip64_tests;
 
@@ -1,6 +1,6 @@
 use rawzip::{
     ZipArchive, ZipArchiveWriter,
-    extra_fields::{ExtraFieldId, ExtraFields},
+    extra_fields::{ExtraField, ExtraFieldId, ExtraFields},
     time::{LocalDateTime, UtcDateTime, ZipDateTimeKind},
 };
 use std::io::Write;
@@ -459,3 +459,156 @@ fn test_last_modified_dos_raw_packed_fields() {
     assert_eq!(local_dos.packed_date(), dos.packed_date());
     assert_eq!(local_dos.packed_time(), dos.packed_time());
 }
+
+/// Test that sub-second and pre-1970 times round trip through the NTFS field
+#[rstest::rstest]
+#[case::subsecond(UtcDateTime::from_components(2023, 6, 15, 14, 30, 45, 123_456_700).unwrap())]
+#[case::pre_epoch(UtcDateTime::from_components(1965, 3, 4, 5, 6, 7, 0).unwrap())]
+#[case::post_2106(UtcDateTime::from_components(2200, 1, 2, 3, 4, 5, 0).unwrap())]
+fn test_ntfs_timestamp_written_when_lossy(#[case] datetime: UtcDateTime) {
+    let mut output = Vec::new();
+    {
+        let mut archive = ZipArchiveWriter::new(&mut output);
+        let (mut entry, config) = archive
+            .new_file("test.txt")
+            .last_modified(datetime)
+            .start()
+            .unwrap();
+        let mut writer = config.wrap(&mut entry);
+        writer.write_all(b"Hello, world!").unwrap();
+        let (_, descriptor) = writer.finish().unwrap();
+        entry.finish(descriptor).unwrap();
+        archive.finish().unwrap();
+    }
+
+    let archive = ZipArchive::from_slice(&output).unwrap();
+    let mut entries = archive.entries();
+    let entry = entries.next_entry().unwrap().unwrap();
+    assert_eq!(entry.last_modified(), ZipDateTimeKind::Utc(datetime));
+
+    let central_ids = entry.extra_fields().map(|(id, _)| id).collect::<Vec<_>>();
+    assert_eq!(central_ids.last(), Some(&ExtraFieldId::NTFS));
+
+    let local = archive.get_entry(entry.wayfinder()).unwrap().local_header();
+    assert_eq!(local.last_modified(), ZipDateTimeKind::Utc(datetime));
+}
+
+/// Test that a time past the NTFS range saturates instead of overflowing
+#[test]
+fn test_ntfs_timestamp_far_future() {
+    let datetime = UtcDateTime::from_components(65000, 1, 1, 0, 0, 0, 0).unwrap();
+    let mut output = Vec::new();
+    {
+        let mut archive = ZipArchiveWriter::new(&mut output);
+        archive
+            .new_dir("dir/")
+            .last_modified(datetime)
+            .create()
+            .unwrap();
+        archive.finish().unwrap();
+    }
+
+    let archive = ZipArchive::from_slice(&output).unwrap();
+    let entry = archive.entries().next_entry().unwrap().unwrap();
+    let ZipDateTimeKind::Utc(modified) = entry.last_modified() else {
+        panic!("expected a utc modification time");
+    };
+    assert_eq!(modified.year(), 60056);
+}
+
+/// Test that access or creation times beyond the Extended Timestamp range are
+/// kept in the NTFS field when no modification time is set
+#[test]
+fn test_ntfs_timestamp_written_without_modification_time() {
+    let atime = UtcDateTime::from_components(2200, 1, 1, 0, 0, 0, 0).unwrap();
+    let ctime = UtcDateTime::from_components(2023, 6, 15, 14, 30, 45, 0).unwrap();
+    let mut output = Vec::new();
+    {
+        let mut archive = ZipArchiveWriter::new(&mut output);
+        archive
+            .new_dir("dir/")
+            .last_accessed(atime)
+            .created(ctime)
+            .create()
+            .unwrap();
+        archive.finish().unwrap();
+    }
+
+    let archive = ZipArchive::from_slice(&output).unwrap();
+    let mut entries = archive.entries();
+    let entry = entries.next_entry().unwrap().unwrap();
+    assert!(matches!(entry.last_modified(), ZipDateTimeKind::Local(_)));
+
+    let fields = entry.extra_fields().typed().collect::<Vec<_>>();
+    let [ExtraField::Ntfs(ntfs)] = fields.as_slice() else {
+        panic!("expected only an ntfs field, got {fields:?}");
+    };
+    assert_eq!(ntfs.modified_ticks(), 0);
+    assert_eq!(ntfs.accessed(), atime);
+    assert_eq!(ntfs.created(), ctime);
+}
+
+/// Test that access and creation times are written in the full local form
+#[test]
+fn test_extended_timestamp_access_and_creation_times() {
+    let mtime = UtcDateTime::from_unix(1_700_000_000);
+    let atime = UtcDateTime::from_unix(1_700_000_100);
+    let ctime = UtcDateTime::from_unix(1_600_000_000);
+    let mut output = Vec::new();
+    {
+        let mut archive = ZipArchiveWriter::new(&mut output);
+        let (mut entry, config) = archive
+            .new_file("test.txt")
+            .last_modified(mtime)
+            .last_accessed(atime)
+            .created(ctime)
+            .start()
+            .unwrap();
+        let mut writer = config.wrap(&mut entry);
+        writer.write_all(b"Hello, world!").unwrap();
+        let (_, descriptor) = writer.finish().unwrap();
+        entry.finish(descriptor).unwrap();
+        archive
+            .new_dir("dir/")
+            .last_accessed(atime)
+            .create()
+            .unwrap();
+        archive.finish().unwrap();
+    }
+
+    let archive = ZipArchive::from_slice(&output).unwrap();
+    let mut entries = archive.entries();
+    let entry = entries.next_entry().unwrap().unwrap();
+    assert_eq!(entry.last_modified(), ZipDateTimeKind::Utc(mtime));
+
+    let central = entry.extra_fields().collect::<Vec<_>>();
+    let mut expected_central = vec![0b111];
+    expected_central.extend_from_slice(&1_700_000_000u32.to_le_bytes());
+    assert_eq!(
+        central,
+        vec![(
+            ExtraFieldId::EXTENDED_TIMESTAMP,
+            expected_central.as_slice()
+        )]
+    );
+
+    let local = archive.get_entry(entry.wayfinder()).unwrap().local_header();
+    let local_fields = local.extra_fields().collect::<Vec<_>>();
+    let mut expected_local = vec![0b111];
+    expected_local.extend_from_slice(&1_700_000_000u32.to_le_bytes());
+    expected_local.extend_from_slice(&1_700_000_100u32.to_le_bytes());
+    expected_local.extend_from_slice(&1_600_000_000u32.to_le_bytes());
+    assert_eq!(
+        local_fields,
+        vec![(ExtraFieldId::EXTENDED_TIMESTAMP, expected_local.as_slice())]
+    );
+
+    // An access time alone does not provide a modification time
+    let entry = entries.next_entry().unwrap().unwrap();
+    assert!(matches!(entry.last_modified(), ZipDateTimeKind::Local(_)));
+    let central = entry.extra_fields().collect::<Vec<_>>();
+    assert_eq!(
+        central,
+        vec![(ExtraFieldId::EXTENDED_TIMESTAMP, &[0b010][..])]
+    );
+}
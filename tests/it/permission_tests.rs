@@ -1,4 +1,4 @@
-use rawzip::{ZipArchive, ZipArchiveWriter};
+use rawzip::{CreatorOs, DosAttributes, EntryMode, FileType, ZipArchive, ZipArchiveWriter};
 use std::io::Write;
 
 #[test]
@@ -116,3 +116,114 @@ fn test_permissions_without_unix_permissions() {
         "Default permissions: expected 0o100666, got 0o{actual_mode:o}"
     );
 }
+
+#[test]
+fn test_entry_mode_roundtrip() {
+    let cases = [
+        (FileType::Regular, 0o4755, 0o104755),
+        (FileType::Directory, 0o1777, 0o041777),
+        (FileType::Symlink, 0o777, 0o120777),
+        (FileType::Fifo, 0o2644, 0o012644),
+        (FileType::CharDevice, 0o600, 0o020600),
+        (FileType::BlockDevice, 0o660, 0o060660),
+        (FileType::Socket, 0o755, 0o140755),
+    ];
+
+    let mut output = Vec::new();
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    for (i, (file_type, permissions, _)) in cases.iter().enumerate() {
+        let mode = EntryMode::from_parts(*file_type, *permissions);
+        if *file_type == FileType::Directory {
+            archive.new_dir("dir/").mode(mode).create().unwrap();
+            continue;
+        }
+
+        let (mut entry, config) = archive
+            .new_file(format!("file{i}"))
+            .mode(mode)
+            .start()
+            .unwrap();
+        let (_, descriptor) = config.wrap(&mut entry).finish().unwrap();
+        entry.finish(descriptor).unwrap();
+    }
+    archive.finish().unwrap();
+
+    let archive = ZipArchive::from_slice(&output).unwrap();
+    let mut entries = archive.entries();
+    for (file_type, permissions, expected) in cases {
+        let entry = entries.next_entry().unwrap().unwrap();
+        let mode = entry.mode();
+        assert_eq!(mode.value(), expected);
+        assert_eq!(mode.file_type(), file_type);
+        assert_eq!(mode.permissions(), permissions & 0o777);
+        assert_eq!(mode.is_setuid(), permissions & 0o4000 != 0);
+        assert_eq!(mode.is_setgid(), permissions & 0o2000 != 0);
+        assert_eq!(mode.is_sticky(), permissions & 0o1000 != 0);
+        assert_eq!(mode.is_dir(), file_type == FileType::Directory);
+        assert_eq!(entry.creator_os(), CreatorOs::UNIX);
+        assert_eq!(entry.external_attributes(), expected << 16);
+    }
+}
+
+#[test]
+fn test_dos_attributes_roundtrip() {
+    let mut output = Vec::new();
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    let (mut entry, config) = archive
+        .new_file("hidden.txt")
+        .dos_attributes(DosAttributes::HIDDEN | DosAttributes::ARCHIVE | DosAttributes::READ_ONLY)
+        .start()
+        .unwrap();
+    let (_, descriptor) = config.wrap(&mut entry).finish().unwrap();
+    entry.finish(descriptor).unwrap();
+    archive
+        .new_dir("dir/")
+        .unix_permissions(0o755)
+        .dos_attributes(DosAttributes::DIRECTORY)
+        .create()
+        .unwrap();
+    archive.finish().unwrap();
+
+    let archive = ZipArchive::from_slice(&output).unwrap();
+    let mut entries = archive.entries();
+
+    let entry = entries.next_entry().unwrap().unwrap();
+    assert_eq!(entry.creator_os(), CreatorOs::MSDOS);
+    assert_eq!(entry.external_attributes(), 0x23);
+    let attributes = entry.dos_attributes();
+    assert!(attributes.contains(DosAttributes::HIDDEN | DosAttributes::ARCHIVE));
+    assert!(!attributes.contains(DosAttributes::SYSTEM));
+    assert_eq!(entry.mode().value(), 0o100444);
+
+    // Unix permissions and DOS attributes are stored side by side
+    let entry = entries.next_entry().unwrap().unwrap();
+    assert_eq!(entry.creator_os(), CreatorOs::UNIX);
+    assert_eq!(entry.external_attributes(), (0o755 << 16) | 0x10);
+    assert_eq!(entry.dos_attributes(), DosAttributes::DIRECTORY);
+    assert_eq!(entry.mode().value(), 0o040755);
+}
+
+#[test]
+fn test_dos_attributes_from_windows_archive() {
+    let data = std::fs::read("assets/winxp.zip").unwrap();
+    let archive = ZipArchive::from_slice(&data).unwrap();
+    let mut entries = archive.entries();
+    while let Some(entry) = entries.next_entry().unwrap() {
+        assert_eq!(entry.creator_os(), CreatorOs::MVS);
+        let name = entry
+            .file_path()
+            .try_normalize()
+            .unwrap()
+            .as_ref()
+            .to_string();
+        let attributes = entry.dos_attributes();
+        assert_eq!(
+            attributes.contains(DosAttributes::READ_ONLY),
+            name == "readonly"
+        );
+        assert_eq!(
+            attributes.contains(DosAttributes::DIRECTORY),
+            entry.is_dir()
+        );
+    }
+}
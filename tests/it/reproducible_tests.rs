@@ -0,0 +1,140 @@
+use crate::common::{TestEntry, write_entries};
+use rawzip::extra_fields::ExtraFieldId;
+use rawzip::time::{UtcDateTime, ZipDateTimeKind};
+use rawzip::{ReproducibleOptions, ZipArchive, ZipArchiveWriter};
+
+const UID_GID: &[u8] = &[1, 4, 0xe8, 3, 0, 0, 4, 0xe8, 3, 0, 0];
+
+/// A file with a Unix UID/GID extra field, which reproducible archives
+/// strip, and an unrelated extra field, which they keep.
+fn file<'a>(name: &'a str, data: &'a [u8]) -> TestEntry<'a> {
+    TestEntry::file(name, data)
+        .extra_field(ExtraFieldId::INFO_ZIP_UNIX_UID_GID, UID_GID)
+        .extra_field(ExtraFieldId::new(0xcafe), b"kept")
+}
+
+fn write_archive(options: ReproducibleOptions, entries: &[TestEntry<'_>]) -> Vec<u8> {
+    let mut output = Vec::new();
+    let archive = ZipArchiveWriter::builder()
+        .reproducible(options)
+        .build(&mut output);
+    write_entries(archive, entries);
+    output
+}
+
+fn utc(seconds: i64) -> UtcDateTime {
+    UtcDateTime::from_unix(seconds)
+}
+
+#[test]
+fn test_reproducible_sorted_output_is_order_independent() {
+    let entries = [
+        file("b.txt", b"bbb")
+            .permissions(0o600)
+            .mtime(utc(1_700_000_000)),
+        TestEntry::dir("dir/").mtime(utc(1_600_000_000)),
+        file("a.sh", b"#!/bin/sh").permissions(0o700),
+    ];
+
+    let options = || {
+        ReproducibleOptions::new()
+            .sort_entries(true)
+            .fixed_mtime(utc(1_000_000_000))
+    };
+
+    let forward = write_archive(options(), &entries);
+    let mut reversed_entries = entries;
+    reversed_entries.reverse();
+    let reversed = write_archive(options(), &reversed_entries);
+    assert_eq!(forward, reversed);
+
+    let archive = ZipArchive::from_slice(&forward).unwrap();
+    let mut names = Vec::new();
+    let mut entries = archive.entries();
+    while let Some(entry) = entries.next_entry().unwrap() {
+        let name = entry
+            .file_path()
+            .try_normalize()
+            .unwrap()
+            .as_ref()
+            .to_string();
+        let expected_mode = match name.as_str() {
+            "a.sh" => 0o100755,
+            "b.txt" => 0o100644,
+            "dir/" => 0o040755,
+            _ => unreachable!(),
+        };
+        assert_eq!(entry.mode().value(), expected_mode, "{name}");
+        assert_eq!(
+            entry.last_modified(),
+            ZipDateTimeKind::Utc(utc(1_000_000_000)),
+            "{name}"
+        );
+
+        let ids = entry.extra_fields().map(|(id, _)| id).collect::<Vec<_>>();
+        assert!(!ids.contains(&ExtraFieldId::INFO_ZIP_UNIX_UID_GID));
+
+        // Entries are readable at their rewritten offsets
+        let file = archive.get_entry(entry.wayfinder()).unwrap();
+        assert_eq!(file.local_header().file_path().as_bytes(), name.as_bytes());
+        let expected_data: &[u8] = match name.as_str() {
+            "a.sh" => b"#!/bin/sh",
+            "b.txt" => b"bbb",
+            _ => b"",
+        };
+        assert_eq!(file.data(), expected_data);
+        names.push(name);
+    }
+
+    assert_eq!(names, vec!["a.sh", "b.txt", "dir/"]);
+}
+
+#[test]
+fn test_reproducible_clamp_mtime() {
+    let entries = [
+        file("old.txt", b"old").mtime(utc(1_000)),
+        file("new.txt", b"new").mtime(utc(2_000_000_000)),
+        file("none.txt", b"none"),
+    ];
+
+    let output = write_archive(
+        ReproducibleOptions::new().clamp_mtime(utc(1_500_000_000)),
+        &entries,
+    );
+    let archive = ZipArchive::from_slice(&output).unwrap();
+    let mut entries = archive.entries();
+
+    let entry = entries.next_entry().unwrap().unwrap();
+    assert_eq!(entry.last_modified(), ZipDateTimeKind::Utc(utc(1_000)));
+    assert_eq!(entry.mode().value(), 0o100644);
+
+    let entry = entries.next_entry().unwrap().unwrap();
+    assert_eq!(
+        entry.last_modified(),
+        ZipDateTimeKind::Utc(utc(1_500_000_000))
+    );
+
+    let entry = entries.next_entry().unwrap().unwrap();
+    assert!(matches!(entry.last_modified(), ZipDateTimeKind::Local(_)));
+}
+
+#[test]
+fn test_reproducible_keeps_unrelated_extra_fields() {
+    let entries = [file("a.txt", b"a")];
+
+    let output = write_archive(ReproducibleOptions::new(), &entries);
+    let archive = ZipArchive::from_slice(&output).unwrap();
+    let mut entries = archive.entries();
+    let entry = entries.next_entry().unwrap().unwrap();
+    let fields = entry.extra_fields().collect::<Vec<_>>();
+    assert_eq!(fields, vec![(ExtraFieldId::new(0xcafe), &b"kept"[..])]);
+
+    let output = write_archive(
+        ReproducibleOptions::new().strip_extra_fields(false),
+        &[file("a.txt", b"a")],
+    );
+    let archive = ZipArchive::from_slice(&output).unwrap();
+    let mut entries = archive.entries();
+    let entry = entries.next_entry().unwrap().unwrap();
+    assert_eq!(entry.extra_fields().count(), 2);
+}
@@ -0,0 +1,136 @@
+use rawzip::{ErrorKind, RECOMMENDED_BUFFER_SIZE, ZipArchive, ZipSnapshot};
+use rstest::rstest;
+use std::io::Read;
+
+fn parse(data: &[u8]) -> ZipArchive<Vec<u8>> {
+    ZipArchive::from_slice(data.to_vec()).unwrap().into_reader()
+}
+
+#[rstest]
+#[case("test.zip")]
+#[case("test-prefix.zip")]
+#[case("test-trailing-junk.zip")]
+#[case("zip64.zip")]
+#[case("winxp.zip")]
+#[case("unix.zip")]
+fn test_snapshot_matches_central_directory(#[case] name: &str) {
+    let data = std::fs::read(format!("assets/{name}")).unwrap();
+    let archive = parse(&data);
+    let saved = archive.snapshot().unwrap().to_bytes();
+    let snapshot = ZipSnapshot::from_bytes(&saved).unwrap();
+    assert_eq!(snapshot.archive_size(), archive.end_offset());
+
+    let reopened = snapshot.open(data.as_slice()).unwrap();
+    assert_eq!(reopened.directory_offset(), archive.directory_offset());
+    assert_eq!(reopened.entries_hint(), archive.entries_hint());
+    assert_eq!(reopened.end_offset(), archive.end_offset());
+
+    let mut buffer = vec![0u8; RECOMMENDED_BUFFER_SIZE];
+    let mut entries = archive.entries(&mut buffer);
+    let mut snapshot_entries = snapshot.entries();
+    assert_eq!(snapshot_entries.len(), snapshot.len());
+    while let Some(expected) = entries.next_entry().unwrap() {
+        let actual = snapshot_entries.next().unwrap();
+        assert_eq!(actual.file_path().as_ref(), expected.file_path().as_ref());
+        assert_eq!(actual.wayfinder(), expected.wayfinder());
+        assert_eq!(actual.flags(), expected.flags());
+        assert_eq!(actual.crc32(), expected.crc32());
+        assert_eq!(actual.mode(), expected.mode());
+        assert_eq!(actual.last_modified_dos(), expected.last_modified_dos());
+        assert_eq!(actual.external_attributes(), expected.external_attributes());
+        assert_eq!(actual.creator_os(), expected.creator_os());
+        assert_eq!(actual.local_header_offset(), expected.local_header_offset());
+
+        // Entries read through the reopened archive match the original
+        let mut want = Vec::new();
+        let mut got = Vec::new();
+        let wayfinder = expected.wayfinder();
+        archive
+            .get_entry(wayfinder)
+            .unwrap()
+            .reader()
+            .read_to_end(&mut want)
+            .unwrap();
+        reopened
+            .get_entry(actual.wayfinder())
+            .unwrap()
+            .reader()
+            .read_to_end(&mut got)
+            .unwrap();
+        assert_eq!(got, want);
+    }
+    assert!(snapshot_entries.next().is_none());
+}
+
+#[test]
+fn test_snapshot_rejects_other_archive() {
+    let test = std::fs::read("assets/test.zip").unwrap();
+    let other = std::fs::read("assets/test-prefix.zip").unwrap();
+    let snapshot = parse(&test).snapshot().unwrap();
+
+    let err = snapshot.open(other.as_slice()).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidInput { .. }));
+
+    // A rewritten comment changes the EOCD region
+    let mut modified = test.clone();
+    let last = modified.len() - 1;
+    modified[last] ^= 0x20;
+    let err = snapshot.open(modified.as_slice()).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidInput { .. }));
+}
+
+#[test]
+fn test_snapshot_rejects_truncated_archive() {
+    let data = std::fs::read("assets/test.zip").unwrap();
+    let snapshot = parse(&data).snapshot().unwrap();
+    let err = snapshot.open(&data[..data.len() - 1]).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::IO(_)));
+}
+
+#[test]
+fn test_snapshot_rejects_extended_archive() {
+    let data = std::fs::read("assets/test.zip").unwrap();
+    let snapshot = parse(&data).snapshot().unwrap();
+    let mut extended = data.clone();
+    extended.extend_from_slice(b"appended");
+    let err = snapshot.open(extended.as_slice()).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidInput { .. }));
+
+    // Trailing data present when the snapshot was taken is allowed, but
+    // must still be there
+    let snapshot =
+        ZipSnapshot::from_bytes(&parse(&extended).snapshot().unwrap().to_bytes()).unwrap();
+    assert!(snapshot.open(extended.as_slice()).is_ok());
+    let err = snapshot.open(data.as_slice()).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidInput { .. }));
+}
+
+#[test]
+fn test_snapshot_validates_local_headers_lazily() {
+    let data = std::fs::read("assets/test.zip").unwrap();
+    let snapshot = parse(&data).snapshot().unwrap();
+
+    // Corrupt the signature of the first local header, leaving the EOCD
+    // region intact
+    let first = snapshot.entries().next().unwrap();
+    let mut corrupted = data.clone();
+    corrupted[first.local_header_offset() as usize] ^= 0xff;
+
+    let archive = snapshot.open(corrupted.as_slice()).unwrap();
+    let err = archive.get_entry(first.wayfinder()).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidSignature { .. }));
+
+    let second = snapshot.entries().nth(1).unwrap();
+    assert!(archive.get_entry(second.wayfinder()).is_ok());
+}
+
+#[test]
+fn test_snapshot_of_empty_archive() {
+    let mut data = Vec::new();
+    rawzip::ZipArchiveWriter::new(&mut data).finish().unwrap();
+
+    let snapshot = parse(&data).snapshot().unwrap();
+    assert!(snapshot.is_empty());
+    let snapshot = ZipSnapshot::from_bytes(&snapshot.to_bytes()).unwrap();
+    assert_eq!(snapshot.open(data.as_slice()).unwrap().entries_hint(), 0);
+}
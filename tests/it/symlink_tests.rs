@@ -0,0 +1,71 @@
+use rawzip::time::UtcDateTime;
+use rawzip::{CompressionMethod, ErrorKind, RECOMMENDED_BUFFER_SIZE, ZipArchive, ZipArchiveWriter};
+use std::io::Write;
+
+#[test]
+fn test_read_symlink_asset() {
+    let data = std::fs::read("assets/symlink.zip").unwrap();
+    let archive = ZipArchive::from_slice(&data).unwrap();
+    let mut entries = archive.entries();
+    let entry = entries.next_entry().unwrap().unwrap();
+    assert!(entry.mode().is_symlink());
+
+    let data = archive.get_entry(entry.wayfinder()).unwrap().data();
+    let target = entry.symlink_target(data).unwrap();
+    assert_eq!(target.as_str(), "../target");
+    assert!(target.escapes_root());
+    assert!(target.resolved().is_none());
+}
+
+#[test]
+fn test_write_symlink_roundtrip() {
+    let mut output = Vec::new();
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    archive
+        .new_symlink("docs/latest", "v2/readme.md")
+        .unix_permissions(0o755)
+        .last_modified(UtcDateTime::from_unix(1_700_000_000))
+        .create()
+        .unwrap();
+    let (mut entry, config) = archive.new_file("docs/v2/readme.md").start().unwrap();
+    let mut writer = config.wrap(&mut entry);
+    writer.write_all(b"readme").unwrap();
+    let (_, descriptor) = writer.finish().unwrap();
+    entry.finish(descriptor).unwrap();
+    archive.finish().unwrap();
+
+    let mut buffer = vec![0u8; RECOMMENDED_BUFFER_SIZE];
+    let archive = ZipArchive::from_slice(&output).unwrap().into_reader();
+    let mut entries = archive.entries(&mut buffer);
+
+    let entry = entries.next_entry().unwrap().unwrap();
+    assert_eq!(entry.mode().value(), 0o120755);
+    assert_eq!(entry.compression_method(), CompressionMethod::STORE);
+    assert_eq!(entry.uncompressed_size_hint(), 12);
+
+    let file = archive.get_entry(entry.wayfinder()).unwrap();
+    let reader = file.verifying_reader(file.reader());
+    let target = entry.read_symlink_target(reader).unwrap();
+    assert_eq!(target.as_str(), "v2/readme.md");
+    assert_eq!(target.resolved().unwrap().as_str(), "docs/v2/readme.md");
+
+    // Regular files are not symbolic links
+    let entry = entries.next_entry().unwrap().unwrap();
+    assert!(!entry.mode().is_symlink());
+    let err = entry.symlink_target(b"readme").unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidInput { .. }));
+}
+
+#[test]
+fn test_write_symlink_rejects_invalid_targets() {
+    let mut output = Vec::new();
+    let mut archive = ZipArchiveWriter::new(&mut output);
+    for target in ["", "a\0b"] {
+        let err = archive.new_symlink("link", target).create().unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::InvalidInput { .. }));
+    }
+
+    archive.finish().unwrap();
+    let archive = ZipArchive::from_slice(&output).unwrap();
+    assert_eq!(archive.entries_hint(), 0);
+}
@@ -93,3 +93,135 @@ fn extract_flags_from_zip(zip_data: &[u8]) -> u16 {
     // Extract general purpose bit flag (bytes 6-7)
     u16::from_le_bytes([zip_data[6], zip_data[7]])
 }
+
+/// Test that the Unicode Path and Comment fields supersede the raw bytes
+#[test]
+fn test_unicode_path_and_comment_fields() {
+    use rawzip::extra_fields::{ExtraFieldId, ExtraFieldValue};
+    use rawzip::{EntryPath, Header};
+
+    let mut output = Vec::new();
+    {
+        let mut archive = rawzip::ZipArchiveWriter::new(&mut output);
+        let (mut entry, config) = archive
+            .new_file(EntryPath::verbatim_with_unicode_path(
+                &b"caf\x82.txt"[..],
+                "caf\u{e9}.txt",
+            ))
+            .comment(&b"r\x82sum\x82"[..])
+            .typed_extra_field(ExtraFieldValue::UnicodeComment {
+                original: b"r\x82sum\x82",
+                comment: "r\u{e9}sum\u{e9}",
+            })
+            .unwrap()
+            .start()
+            .unwrap();
+        let mut writer = config.wrap(&mut entry);
+        writer.write_all(b"data").unwrap();
+        let (_, desc) = writer.finish().unwrap();
+        entry.finish(desc).unwrap();
+
+        // A Unicode Path field whose CRC does not match the header name
+        let mut stale = vec![1];
+        stale.extend_from_slice(&rawzip::crc32(b"old.txt").to_le_bytes());
+        stale.extend_from_slice(b"old.txt");
+        archive
+            .new_dir(EntryPath::verbatim(&b"new/"[..]))
+            .extra_field(
+                ExtraFieldId::INFO_ZIP_UNICODE_PATH,
+                &stale,
+                Header::default(),
+            )
+            .unwrap()
+            .create()
+            .unwrap();
+        archive.finish().unwrap();
+    }
+
+    let archive = rawzip::ZipArchive::from_slice(&output).unwrap();
+    let mut entries = archive.entries();
+    let entry = entries.next_entry().unwrap().unwrap();
+    assert!(!entry.flags().is_utf8());
+    assert_eq!(entry.file_path().as_ref(), b"caf\x82.txt");
+    assert_eq!(
+        entry.unicode_file_path().try_normalize().unwrap().as_ref(),
+        "caf\u{e9}.txt"
+    );
+    assert_eq!(entry.comment().as_bytes(), b"r\x82sum\x82");
+    assert_eq!(
+        entry.unicode_comment().as_bytes(),
+        "r\u{e9}sum\u{e9}".as_bytes()
+    );
+
+    let local = archive.get_entry(entry.wayfinder()).unwrap().local_header();
+    assert_eq!(
+        local.unicode_file_path().as_ref(),
+        "caf\u{e9}.txt".as_bytes()
+    );
+
+    let entry = entries.next_entry().unwrap().unwrap();
+    assert_eq!(entry.unicode_file_path().as_ref(), b"new/");
+    assert_eq!(entry.unicode_comment().as_bytes(), b"");
+}
+
+/// Test that names are written as CP437 when representable and decoded back
+#[rstest]
+#[case("caf\u{e9}/r\u{e9}sum\u{e9}.txt", Some(&b"caf\x82/r\x82sum\x82.txt"[..]))]
+#[case("file~backup.txt", Some(&b"file~backup.txt"[..]))]
+#[case("\u{1f980}.txt", None)]
+fn test_cp437_entry_names(#[case] name: &str, #[case] expected_raw: Option<&[u8]>) {
+    let mut output = Vec::new();
+    {
+        let mut archive = rawzip::ZipArchiveWriter::new(&mut output);
+        let (mut entry, config) = archive
+            .new_file(rawzip::EntryPath::cp437(name))
+            .start()
+            .unwrap();
+        let mut writer = config.wrap(&mut entry);
+        writer.write_all(b"data").unwrap();
+        let (_, desc) = writer.finish().unwrap();
+        entry.finish(desc).unwrap();
+        archive.finish().unwrap();
+    }
+
+    let archive = rawzip::ZipArchive::from_slice(&output).unwrap();
+    let entry = archive.entries().next_entry().unwrap().unwrap();
+    match expected_raw {
+        Some(raw) => {
+            assert!(!entry.flags().is_utf8());
+            assert_eq!(entry.file_path().as_ref(), raw);
+        }
+        None => {
+            assert!(entry.flags().is_utf8());
+            assert_eq!(entry.file_path().as_ref(), name.as_bytes());
+        }
+    }
+    assert_eq!(entry.decoded_file_path().unwrap().as_str(), name);
+}
+
+/// Test that names without the UTF-8 flag are decoded as UTF-8 when valid,
+/// and as CP437 otherwise
+#[rstest]
+#[case("r\u{e9}sum\u{e9}.txt".as_bytes(), "r\u{e9}sum\u{e9}.txt")]
+#[case(b"r\x82sum\x82.txt", "r\u{e9}sum\u{e9}.txt")]
+fn test_flagless_entry_names(#[case] raw: &[u8], #[case] expected: &str) {
+    let mut output = Vec::new();
+    {
+        let mut archive = rawzip::ZipArchiveWriter::new(&mut output);
+        let (mut entry, config) = archive
+            .new_file(rawzip::EntryPath::verbatim(raw))
+            .start()
+            .unwrap();
+        let mut writer = config.wrap(&mut entry);
+        writer.write_all(b"data").unwrap();
+        let (_, desc) = writer.finish().unwrap();
+        entry.finish(desc).unwrap();
+        archive.finish().unwrap();
+    }
+
+    let archive = rawzip::ZipArchive::from_slice(&output).unwrap();
+    let entry = archive.entries().next_entry().unwrap().unwrap();
+    assert!(!entry.flags().is_utf8());
+    assert_eq!(entry.file_path().as_ref(), raw);
+    assert_eq!(entry.decoded_file_path().unwrap().as_str(), expected);
+}